@@ -0,0 +1,156 @@
+use noise::NoiseFn;
+use wasm_bindgen::prelude::*;
+
+use crate::gradient::{Gradient, EPSILON};
+use crate::sampling::Source4D;
+
+/// The offsets of the 3 noise potentials of the 3D curl, far apart so that they don't correlate.
+const POTENTIAL_OFFSETS: [[f64; 3]; 3] = [[0.0, 0.0, 0.0], [31.416, -47.853, 12.793], [-233.145, -113.408, -185.31]];
+
+/// The settings of a curl noise flow, whose velocities are the curl of the noise and so have no divergence: the
+/// particles swirl without gathering or spreading, like smoke.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct CurlFlow {
+    /// The frequency of the noise over the positions, 1 by default.
+    pub scale: f64,
+
+    /// The velocity for a slope of 1 of the noise, 1 by default.
+    pub speed: f64,
+
+    /// The extra dimension of the noise, the flow changes smoothly as it advances.
+    pub time: f64,
+
+    /// How long the particles take to follow the flow in seconds, they follow it at once with 0, the default.
+    pub inertia: f64,
+}
+
+#[wasm_bindgen]
+impl CurlFlow {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            scale: 1.0,
+            speed: 1.0,
+            time: 0.0,
+            inertia: 0.0,
+        }
+    }
+}
+
+impl Default for CurlFlow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the velocities of the flow at the packed points, the 2D ones are the curl of the noise at
+/// `[x, y, time]` and the 3D ones the curl of 3 offset noise potentials at `[x, y, z, time]`.
+pub(crate) fn sample_curl<F>(source: &F, flow: &CurlFlow, points: &[f64], dimensions: u32) -> Result<Vec<f64>, JsError>
+where
+    F: Gradient + NoiseFn<f64, 4> + Source4D,
+{
+    let dimensions = check_dimensions(source, points.len(), dimensions)?;
+    Ok(velocities(source, flow, points, dimensions))
+}
+
+/// Moves the packed particles by `dt` seconds along the flow, the velocities follow the flow with the inertia.
+pub(crate) fn advance_particles<F>(
+    source: &F,
+    flow: &CurlFlow,
+    positions: &mut [f32],
+    velocities_out: &mut [f32],
+    dt: f64,
+    dimensions: u32,
+) -> Result<(), JsError>
+where
+    F: Gradient + NoiseFn<f64, 4> + Source4D,
+{
+    let dimensions = check_dimensions(source, positions.len(), dimensions)?;
+    if velocities_out.len() != positions.len() {
+        return Err(JsError::new(&format!(
+            "The velocities have {} coordinates but the positions have {}",
+            velocities_out.len(),
+            positions.len()
+        )));
+    }
+    let points: Vec<f64> = positions.iter().map(|p| *p as f64).collect();
+    let targets = velocities(source, flow, &points, dimensions);
+    let follow = if flow.inertia > 0.0 {
+        1.0 - (-dt / flow.inertia).exp()
+    } else {
+        1.0
+    };
+    for ((position, velocity), target) in positions.iter_mut().zip(velocities_out.iter_mut()).zip(targets) {
+        let v = *velocity as f64 + (target - *velocity as f64) * follow;
+        *velocity = v as f32;
+        *position = (*position as f64 + v * dt) as f32;
+    }
+    Ok(())
+}
+
+/// Checks the packed points, the 3D flow samples the noise in 4D with the time.
+fn check_dimensions<F: Source4D>(source: &F, len: usize, dimensions: u32) -> Result<usize, JsError> {
+    let dimensions = dimensions as usize;
+    if !(2..=3).contains(&dimensions) {
+        return Err(JsError::new("Only accepts 2 or 3 dimensions"));
+    }
+    if len % dimensions != 0 {
+        return Err(JsError::new(&format!(
+            "The number of coordinates {} is not a multiple of {}",
+            len, dimensions
+        )));
+    }
+    if dimensions == 3 {
+        source.check_4d()?;
+    }
+    Ok(dimensions)
+}
+
+fn velocities<F>(source: &F, flow: &CurlFlow, points: &[f64], dimensions: usize) -> Vec<f64>
+where
+    F: Gradient + NoiseFn<f64, 4>,
+{
+    if dimensions == 2 {
+        // The 2D curl is the gradient of the noise turned by a right angle, the gradient is exact for some noise.
+        let noise_points: Vec<f64> = points
+            .chunks_exact(2)
+            .flat_map(|point| [point[0] * flow.scale, point[1] * flow.scale, flow.time])
+            .collect();
+        return source
+            .gradients(&noise_points, 3)
+            .chunks_exact(4)
+            .flat_map(|gradient| [gradient[2] * flow.speed, -gradient[1] * flow.speed])
+            .collect();
+    }
+    let mut out = Vec::with_capacity(points.len());
+    for point in points.chunks_exact(3) {
+        let [d1, d2, d3] = POTENTIAL_OFFSETS.map(|offset| {
+            let potential_point = [
+                point[0] * flow.scale + offset[0],
+                point[1] * flow.scale + offset[1],
+                point[2] * flow.scale + offset[2],
+                flow.time,
+            ];
+            spatial_gradient(source, potential_point)
+        });
+        out.extend_from_slice(&[
+            (d3[1] - d2[2]) * flow.speed,
+            (d1[2] - d3[0]) * flow.speed,
+            (d2[0] - d1[1]) * flow.speed,
+        ]);
+    }
+    out
+}
+
+/// The derivatives of the noise along x, y and z at a 4D point, by central differences.
+fn spatial_gradient<F: NoiseFn<f64, 4> + ?Sized>(source: &F, point: [f64; 4]) -> [f64; 3] {
+    let mut gradient = [0.0; 3];
+    for (axis, derivative) in gradient.iter_mut().enumerate() {
+        let (mut before, mut after) = (point, point);
+        before[axis] -= EPSILON;
+        after[axis] += EPSILON;
+        *derivative = (source.get(after) - source.get(before)) / (2.0 * EPSILON);
+    }
+    gradient
+}
@@ -0,0 +1,94 @@
+use noise::NoiseFn;
+use wasm_bindgen::prelude::*;
+
+use crate::sampling::Source4D;
+
+/// Moves each vertex along its normal by `amplitude` times the noise at `[x, y, z, time]` of the vertex, and then
+/// computes the smooth normals of the `indices` triangles when they are given.
+pub(crate) fn displace_vertices<F: NoiseFn<f64, 4> + Source4D>(
+    source: &F,
+    positions: &mut [f32],
+    normals: &mut [f32],
+    amplitude: f64,
+    time: f64,
+    indices: Option<&[u32]>,
+) -> Result<(), JsError> {
+    if positions.len() % 3 != 0 {
+        return Err(JsError::new(&format!(
+            "The number of coordinates {} is not a multiple of 3",
+            positions.len()
+        )));
+    }
+    if normals.len() != positions.len() {
+        return Err(JsError::new(&format!(
+            "The normals have {} coordinates but the positions have {}",
+            normals.len(),
+            positions.len()
+        )));
+    }
+    if let Some(indices) = indices {
+        check_indices(indices, positions.len() / 3)?;
+    }
+    source.check_4d()?;
+
+    for (position, normal) in positions.chunks_exact_mut(3).zip(normals.chunks_exact(3)) {
+        let point = [position[0] as f64, position[1] as f64, position[2] as f64, time];
+        let offset = source.get(point) * amplitude;
+        for (p, n) in position.iter_mut().zip(normal) {
+            *p = (*p as f64 + *n as f64 * offset) as f32;
+        }
+    }
+    if let Some(indices) = indices {
+        compute_normals(positions, indices, normals);
+    }
+    Ok(())
+}
+
+fn check_indices(indices: &[u32], vertices: usize) -> Result<(), JsError> {
+    if indices.len() % 3 != 0 {
+        return Err(JsError::new(&format!(
+            "The number of indices {} is not a multiple of 3",
+            indices.len()
+        )));
+    }
+    match indices.iter().find(|index| **index as usize >= vertices) {
+        Some(index) => Err(JsError::new(&format!(
+            "The index {} is out of the {} vertices",
+            index, vertices
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// The smooth normals of `BABYLON.VertexData.ComputeNormals`, the sum of the unit normals of the faces around each
+/// vertex with the clockwise winding of Babylon.js.
+fn compute_normals(positions: &[f32], indices: &[u32], normals: &mut [f32]) {
+    let vertex = |index: u32| {
+        let i = index as usize * 3;
+        [positions[i] as f64, positions[i + 1] as f64, positions[i + 2] as f64]
+    };
+    let mut sums = vec![[0.0f64; 3]; positions.len() / 3];
+    for face in indices.chunks_exact(3) {
+        let (p1, p2, p3) = (vertex(face[0]), vertex(face[1]), vertex(face[2]));
+        let (a, b) = (sub(p1, p2), sub(p3, p2));
+        let normal = [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]];
+        let length = normal.iter().map(|n| n * n).sum::<f64>().sqrt();
+        let length = if length == 0.0 { 1.0 } else { length };
+        for index in face {
+            for (sum, n) in sums[*index as usize].iter_mut().zip(normal) {
+                *sum += n / length;
+            }
+        }
+    }
+    for (normal, sum) in normals.chunks_exact_mut(3).zip(sums) {
+        let length = sum.iter().map(|n| n * n).sum::<f64>().sqrt();
+        let length = if length == 0.0 { 1.0 } else { length };
+        for (n, s) in normal.iter_mut().zip(sum) {
+            *n = (s / length) as f32;
+        }
+    }
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
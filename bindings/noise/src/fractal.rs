@@ -0,0 +1,235 @@
+use std::fmt::Debug;
+
+use noise::{MultiFractal, NoiseFn, Seedable};
+use wasm_bindgen::prelude::*;
+
+use crate::gradient::Gradient;
+use crate::sampling::{impl_sampling, Source4D};
+
+/// The gradient noise sampled by the octaves of a fractal.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseSource {
+    Perlin,
+    Simplex,
+    OpenSimplex,
+}
+
+/// A noise function of any source, the fractals are boxed as this because wasm classes can't be generic.
+pub(crate) trait Sampler: NoiseFn<f64, 2> + NoiseFn<f64, 3> + NoiseFn<f64, 4> + Debug {}
+
+impl<T> Sampler for T where T: NoiseFn<f64, 2> + NoiseFn<f64, 3> + NoiseFn<f64, 4> + Debug {}
+
+impl Gradient for Box<dyn Sampler> {}
+impl Source4D for Box<dyn Sampler> {}
+
+/// The parameters of a fractal, which is built again from them when one is set.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FractalParams {
+    pub(crate) seed: u32,
+    pub(crate) source: NoiseSource,
+    pub(crate) octaves: usize,
+    pub(crate) frequency: f64,
+    pub(crate) lacunarity: f64,
+    pub(crate) persistence: f64,
+    pub(crate) attenuation: f64,
+}
+
+/// The octaves are seeded `seed`, `seed + 1`... like the `noise` crate does, but wrapping around so that any `u32`
+/// seed works.
+fn sources<T: Default + Seedable>(seed: u32, octaves: usize) -> Vec<T> {
+    (0..octaves)
+        .map(|octave| T::default().set_seed(seed.wrapping_add(octave as u32)))
+        .collect()
+}
+
+/// Defines a wasm class for a fractal of the `noise` crate, `$configure` applies the parameters besides the ones
+/// of `MultiFractal`.
+macro_rules! impl_fractal {
+    ($(#[$meta:meta])* $name:ident, $configure:expr) => {
+        $(#[$meta])*
+        #[wasm_bindgen]
+        #[derive(Debug)]
+        pub struct $name {
+            params: FractalParams,
+            handle: Box<dyn Sampler>,
+        }
+
+        #[wasm_bindgen]
+        impl $name {
+            /// Creates the fractal with the default parameters of the `noise` crate, over Perlin noise unless another
+            /// `source` is given.
+            #[wasm_bindgen(constructor)]
+            pub fn new(seed: u32, source: Option<NoiseSource>) -> Self {
+                Self::with_params(seed, source.unwrap_or(NoiseSource::Perlin), |_| {})
+            }
+
+            #[wasm_bindgen(getter)]
+            pub fn seed(&self) -> u32 {
+                self.params.seed
+            }
+
+            #[wasm_bindgen(setter)]
+            pub fn set_seed(&mut self, v: u32) {
+                self.update(|params| params.seed = v);
+            }
+
+            #[wasm_bindgen(getter)]
+            pub fn source(&self) -> NoiseSource {
+                self.params.source
+            }
+
+            #[wasm_bindgen(setter)]
+            pub fn set_source(&mut self, v: NoiseSource) {
+                self.update(|params| params.source = v);
+            }
+
+            /// The number of octaves, from 1 to 32.
+            #[wasm_bindgen(getter)]
+            pub fn octaves(&self) -> u32 {
+                self.params.octaves as u32
+            }
+
+            #[wasm_bindgen(setter)]
+            pub fn set_octaves(&mut self, v: u32) {
+                self.update(|params| params.octaves = (v as usize).clamp(1, noise::$name::<noise::Perlin>::MAX_OCTAVES));
+            }
+
+            #[wasm_bindgen(getter)]
+            pub fn frequency(&self) -> f64 {
+                self.params.frequency
+            }
+
+            #[wasm_bindgen(setter)]
+            pub fn set_frequency(&mut self, v: f64) {
+                self.update(|params| params.frequency = v);
+            }
+
+            #[wasm_bindgen(getter)]
+            pub fn lacunarity(&self) -> f64 {
+                self.params.lacunarity
+            }
+
+            #[wasm_bindgen(setter)]
+            pub fn set_lacunarity(&mut self, v: f64) {
+                self.update(|params| params.lacunarity = v);
+            }
+
+            #[wasm_bindgen(getter)]
+            pub fn persistence(&self) -> f64 {
+                self.params.persistence
+            }
+
+            #[wasm_bindgen(setter)]
+            pub fn set_persistence(&mut self, v: f64) {
+                self.update(|params| params.persistence = v);
+            }
+
+            #[wasm_bindgen]
+            pub fn get(&mut self, input: Vec<f64>) -> Result<f64, JsError> {
+                match input.len() {
+                    2 => Ok(self.handle.get([ input[0], input[1] ])),
+                    3 => Ok(self.handle.get([ input[0], input[1], input[2] ])),
+                    4 => Ok(self.handle.get([ input[0], input[1], input[2], input[3] ])),
+                    _ => Err(JsError::new("Only accepts 2, 3 or 4 numbers")),
+                }
+            }
+        }
+
+        impl $name {
+            /// Builds the fractal from the defaults with the parameters changed by `f`.
+            pub(crate) fn with_params(seed: u32, source: NoiseSource, f: impl FnOnce(&mut FractalParams)) -> Self {
+                let defaults = noise::$name::<noise::Perlin>::default();
+                let mut params = FractalParams {
+                    seed,
+                    source,
+                    octaves: defaults.octaves,
+                    frequency: defaults.frequency,
+                    lacunarity: defaults.lacunarity,
+                    persistence: defaults.persistence,
+                    attenuation: noise::RidgedMulti::<noise::Perlin>::DEFAULT_ATTENUATION,
+                };
+                f(&mut params);
+                Self::from_params(params)
+            }
+
+            pub(crate) fn into_sampler(self) -> Box<dyn Sampler> {
+                self.handle
+            }
+
+            fn from_params(params: FractalParams) -> Self {
+                let handle: Box<dyn Sampler> = match params.source {
+                    NoiseSource::Perlin => Box::new(Self::build::<noise::Perlin>(&params)),
+                    NoiseSource::Simplex => Box::new(Self::build::<noise::Simplex>(&params)),
+                    NoiseSource::OpenSimplex => Box::new(Self::build::<noise::OpenSimplex>(&params)),
+                };
+                Self { params, handle }
+            }
+
+            fn build<T: Default + Seedable>(params: &FractalParams) -> noise::$name<T> {
+                let configure: fn(noise::$name<T>, &FractalParams) -> noise::$name<T> = $configure;
+                let fractal = noise::$name::<T>::default()
+                    .set_octaves(params.octaves)
+                    .set_frequency(params.frequency)
+                    .set_lacunarity(params.lacunarity)
+                    .set_persistence(params.persistence)
+                    .set_sources(sources(params.seed, params.octaves));
+                configure(fractal, params)
+            }
+
+            fn update(&mut self, f: impl FnOnce(&mut FractalParams)) {
+                let mut params = self.params;
+                f(&mut params);
+                *self = Self::from_params(params);
+            }
+        }
+
+        impl Clone for $name {
+            fn clone(&self) -> Self {
+                Self::from_params(self.params)
+            }
+        }
+    };
+}
+
+impl_fractal!(
+    /// Fractal Brownian motion, the sum of the octaves of increasing frequency and decreasing amplitude, for
+    /// terrains and clouds.
+    Fbm,
+    |fractal, _| fractal
+);
+
+impl_fractal!(
+    /// Like `Fbm` with the absolute value of each octave, which gives billowy clouds and rocks.
+    Billow,
+    |fractal, _| fractal
+);
+
+impl_fractal!(
+    /// Inverted absolute octaves weighted by the previous ones, which gives sharp ridges like mountain ranges.
+    RidgedMulti,
+    |fractal, params| fractal.set_attenuation(params.attenuation)
+);
+
+impl_fractal!(
+    /// Octaves weighted by the previous ones, which gives smooth valleys and rough peaks.
+    HybridMulti,
+    |fractal, _| fractal
+);
+
+#[wasm_bindgen]
+impl RidgedMulti {
+    /// How quickly the weight of the octaves decreases, 2 by default.
+    #[wasm_bindgen(getter)]
+    pub fn attenuation(&self) -> f64 {
+        self.params.attenuation
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_attenuation(&mut self, v: f64) {
+        self.update(|params| params.attenuation = v);
+    }
+}
+
+impl_sampling!(Fbm, Billow, RidgedMulti, HybridMulti);
@@ -0,0 +1,228 @@
+use noise::NoiseFn;
+use wasm_bindgen::prelude::*;
+
+use crate::gradient::{central_gradients, Gradient};
+use crate::sampling::{impl_sampling, no_4d_noise, Source4D};
+
+/// The generators of `Generator`, the JS side chooses one by name with `GeneratorType[name]`.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeneratorType {
+    Perlin,
+    Simplex,
+    OpenSimplex,
+    Value,
+    SuperSimplex,
+    /// Blocks of -1 and 1, for debugging.
+    Checkerboard,
+    /// Concentric cylinders around the z axis, from -1 between them to 1 on them.
+    Cylinders,
+    /// Concentric spheres around the origin, from -1 between them to 1 on them.
+    Spheres,
+}
+
+/// The largest `Checkerboard` size, the blocks are `2^size` wide, which `noise` computes in an `isize` that has 32
+/// bits in wasm.
+pub(crate) const MAX_CHECKERBOARD_SIZE: u32 = 30;
+
+/// `noise::SuperSimplex`, which has no 4D noise: the 4D points are sampled without their w coordinate for the graph
+/// modules, and rejected by `Source4D` where they are sampled.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SuperSimplex(noise::SuperSimplex);
+
+impl NoiseFn<f64, 2> for SuperSimplex {
+    fn get(&self, point: [f64; 2]) -> f64 {
+        self.0.get(point)
+    }
+}
+
+impl NoiseFn<f64, 3> for SuperSimplex {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        self.0.get(point)
+    }
+}
+
+impl NoiseFn<f64, 4> for SuperSimplex {
+    fn get(&self, point: [f64; 4]) -> f64 {
+        self.0.get([point[0], point[1], point[2]])
+    }
+}
+
+/// The `Spheres` of libnoise, which the `noise` crate no longer has.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Spheres {
+    frequency: f64,
+}
+
+impl<const N: usize> NoiseFn<f64, N> for Spheres {
+    fn get(&self, point: [f64; N]) -> f64 {
+        let distance = point.iter().map(|v| (v * self.frequency).powi(2)).sum::<f64>().sqrt();
+        let from_smaller_sphere = distance - distance.floor();
+        let nearest = from_smaller_sphere.min(1.0 - from_smaller_sphere);
+        1.0 - nearest * 4.0
+    }
+}
+
+/// A generator of any type, dispatched by a match instead of a box so that it samples like the generator itself.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum AnyGenerator {
+    Perlin(noise::Perlin),
+    Simplex(noise::Simplex),
+    OpenSimplex(noise::OpenSimplex),
+    Value(noise::Value),
+    SuperSimplex(SuperSimplex),
+    Checkerboard(noise::Checkerboard),
+    Cylinders(noise::Cylinders),
+    Spheres(Spheres),
+}
+
+impl AnyGenerator {
+    /// Creates the generator, the seed is for the gradient and value noise, the frequency for `Cylinders` and
+    /// `Spheres` and the size for `Checkerboard`.
+    pub(crate) fn new(kind: GeneratorType, seed: u32, frequency: f64, size: u32) -> Self {
+        match kind {
+            GeneratorType::Perlin => AnyGenerator::Perlin(noise::Perlin::new(seed)),
+            GeneratorType::Simplex => AnyGenerator::Simplex(noise::Simplex::new(seed)),
+            GeneratorType::OpenSimplex => AnyGenerator::OpenSimplex(noise::OpenSimplex::new(seed)),
+            GeneratorType::Value => AnyGenerator::Value(noise::Value::new(seed)),
+            GeneratorType::SuperSimplex => AnyGenerator::SuperSimplex(SuperSimplex(noise::SuperSimplex::new(seed))),
+            GeneratorType::Checkerboard => {
+                AnyGenerator::Checkerboard(noise::Checkerboard::new(size.min(MAX_CHECKERBOARD_SIZE) as usize))
+            }
+            GeneratorType::Cylinders => AnyGenerator::Cylinders(noise::Cylinders::new().set_frequency(frequency)),
+            GeneratorType::Spheres => AnyGenerator::Spheres(Spheres { frequency }),
+        }
+    }
+}
+
+macro_rules! impl_any_generator {
+    ($($dim:literal),*) => {
+        $(
+            impl NoiseFn<f64, $dim> for AnyGenerator {
+                fn get(&self, point: [f64; $dim]) -> f64 {
+                    match self {
+                        AnyGenerator::Perlin(generator) => generator.get(point),
+                        AnyGenerator::Simplex(generator) => generator.get(point),
+                        AnyGenerator::OpenSimplex(generator) => generator.get(point),
+                        AnyGenerator::Value(generator) => generator.get(point),
+                        AnyGenerator::SuperSimplex(generator) => generator.get(point),
+                        AnyGenerator::Checkerboard(generator) => generator.get(point),
+                        AnyGenerator::Cylinders(generator) => generator.get(point),
+                        AnyGenerator::Spheres(generator) => generator.get(point),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_any_generator!(2, 3, 4);
+
+impl Source4D for AnyGenerator {
+    fn check_4d(&self) -> Result<(), JsError> {
+        match self {
+            AnyGenerator::SuperSimplex(_) => Err(no_4d_noise()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Gradient for AnyGenerator {
+    fn gradients(&self, points: &[f64], dimensions: usize) -> Vec<f64> {
+        match self {
+            AnyGenerator::Simplex(simplex) => simplex.gradients(points, dimensions),
+            _ => central_gradients(self, points, dimensions),
+        }
+    }
+}
+
+/// Any generator of the `noise` crate chosen by its type, with the same methods as the dedicated classes.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Generator {
+    kind: GeneratorType,
+    seed: u32,
+    frequency: f64,
+    size: u32,
+    handle: AnyGenerator,
+}
+
+#[wasm_bindgen]
+impl Generator {
+    /// Creates the generator of `kind` with the `seed`, 0 by default like the `noise` crate.
+    #[wasm_bindgen(constructor)]
+    pub fn new(kind: GeneratorType, seed: Option<u32>) -> Self {
+        let seed = seed.unwrap_or(0);
+        Self {
+            kind,
+            seed,
+            frequency: 1.0,
+            size: 0,
+            handle: AnyGenerator::new(kind, seed, 1.0, 0),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> GeneratorType {
+        self.kind
+    }
+
+    /// The seed of the gradient and value noise, the other generators have none.
+    #[wasm_bindgen(getter)]
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_seed(&mut self, v: u32) {
+        self.seed = v;
+        self.rebuild();
+    }
+
+    /// The number of `Cylinders` or `Spheres` per unit length, 1 by default.
+    #[wasm_bindgen(getter)]
+    pub fn frequency(&self) -> f64 {
+        self.frequency
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_frequency(&mut self, v: f64) {
+        self.frequency = v;
+        self.rebuild();
+    }
+
+    /// The `Checkerboard` blocks are `2^size` wide, the size is 0 by default and at most 30.
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_size(&mut self, v: u32) {
+        self.size = v.min(MAX_CHECKERBOARD_SIZE);
+        self.rebuild();
+    }
+
+    /// Samples a 2D, 3D or 4D point, `SuperSimplex` throws for the 4D points since it has no 4D noise.
+    #[wasm_bindgen]
+    pub fn get(&mut self, input: Vec<f64>) -> Result<f64, JsError> {
+        match input.len() {
+            2 => Ok(self.handle.get([ input[0], input[1] ])),
+            3 => Ok(self.handle.get([ input[0], input[1], input[2] ])),
+            4 => {
+                self.handle.check_4d()?;
+                Ok(self.handle.get([ input[0], input[1], input[2], input[3] ]))
+            }
+            _ => Err(JsError::new("Only accepts 2, 3 or 4 numbers")),
+        }
+    }
+}
+
+impl Generator {
+    fn rebuild(&mut self) {
+        self.handle = AnyGenerator::new(self.kind, self.seed, self.frequency, self.size);
+    }
+}
+
+impl_sampling!(Generator);
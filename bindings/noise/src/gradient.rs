@@ -0,0 +1,140 @@
+use noise::core::simplex::simplex_3d;
+use noise::permutationtable::PermutationTable;
+use noise::{NoiseFn, Seedable};
+use wasm_bindgen::prelude::*;
+
+/// The step of the central differences, small next to the features of the noise at a frequency of 1.
+pub(crate) const EPSILON: f64 = 1e-4;
+
+/// The noise value with its gradient, which is analytic for the noise that has one and estimated by central
+/// differences for the other noise.
+pub(crate) trait Gradient: NoiseFn<f64, 2> + NoiseFn<f64, 3> {
+    /// Returns the value and the gradient of each packed point as `[value, dx, dy(, dz)]`.
+    fn gradients(&self, points: &[f64], dimensions: usize) -> Vec<f64> {
+        central_gradients(self, points, dimensions)
+    }
+}
+
+impl Gradient for noise::OpenSimplex {}
+impl Gradient for noise::Perlin {}
+impl Gradient for noise::Worley {}
+
+/// The 3D simplex noise has the analytic gradient of `noise::core::simplex`, the 2D one of the crate doesn't match the
+/// noise so it is estimated.
+impl Gradient for noise::Simplex {
+    fn gradients(&self, points: &[f64], dimensions: usize) -> Vec<f64> {
+        if dimensions != 3 {
+            return central_gradients(self, points, dimensions);
+        }
+        // The permutation table of the generator is private, it is the same for the same seed.
+        let hasher = PermutationTable::new(self.seed());
+        let mut out = Vec::with_capacity(points.len() / 3 * 4);
+        for point in points.chunks_exact(3) {
+            let (value, gradient) = simplex_3d([point[0], point[1], point[2]], &hasher);
+            out.push(value);
+            out.extend_from_slice(&gradient);
+        }
+        out
+    }
+}
+
+pub(crate) fn central_gradients<F>(source: &F, points: &[f64], dimensions: usize) -> Vec<f64>
+where
+    F: NoiseFn<f64, 2> + NoiseFn<f64, 3> + ?Sized,
+{
+    let mut out = Vec::with_capacity(points.len() / dimensions * (dimensions + 1));
+    for point in points.chunks_exact(dimensions) {
+        match point {
+            [x, y] => push_central(source, [*x, *y], &mut out),
+            [x, y, z] => push_central(source, [*x, *y, *z], &mut out),
+            _ => unreachable!(),
+        }
+    }
+    out
+}
+
+fn push_central<F, const N: usize>(source: &F, point: [f64; N], out: &mut Vec<f64>)
+where
+    F: NoiseFn<f64, N> + ?Sized,
+{
+    out.push(source.get(point));
+    for axis in 0..N {
+        let (mut before, mut after) = (point, point);
+        before[axis] -= EPSILON;
+        after[axis] += EPSILON;
+        out.push((source.get(after) - source.get(before)) / (2.0 * EPSILON));
+    }
+}
+
+pub(crate) fn sample_gradients<F: Gradient + ?Sized>(
+    source: &F,
+    points: &[f64],
+    dimensions: u32,
+) -> Result<Vec<f64>, JsError> {
+    let dimensions = dimensions as usize;
+    if !(2..=3).contains(&dimensions) {
+        return Err(JsError::new("Only accepts 2 or 3 dimensions"));
+    }
+    if points.len() % dimensions != 0 {
+        return Err(JsError::new(&format!(
+            "The number of coordinates {} is not a multiple of {}",
+            points.len(),
+            dimensions
+        )));
+    }
+    Ok(source.gradients(points, dimensions))
+}
+
+/// Converts a `[width, height]` height field row by row, e.g. from `fillGrid2D()`, into the RGBA pixels of a
+/// tangent-space normal map. The rows go along +y, and the pixels are `spacing_x` apart along x and `spacing_y` along y
+/// in the units of the heights, so smaller spacings give steeper slopes. The edges take the opposite edges as
+/// neighbors when `wrap` is set, for the height fields which tile.
+#[wasm_bindgen(js_name = normalMap)]
+pub fn normal_map(
+    heights: &[f32],
+    width: u32,
+    height: u32,
+    spacing_x: f64,
+    spacing_y: f64,
+    wrap: Option<bool>,
+) -> Result<Vec<u8>, JsError> {
+    let (width, height, wrap) = (width as usize, height as usize, wrap.unwrap_or(false));
+    if !(spacing_x > 0.0 && spacing_y > 0.0) {
+        return Err(JsError::new("The spacings of the pixels must be positive"));
+    }
+    if heights.len() != width * height {
+        return Err(JsError::new(&format!(
+            "The field holds {} values but the grid has {}",
+            heights.len(),
+            width * height
+        )));
+    }
+    // The neighbors of a pixel on an axis and their distance, which is 1 on the clamped edges.
+    let neighbors = |i: usize, len: usize| {
+        if wrap {
+            ((i + len - 1) % len, (i + 1) % len, 2.0)
+        } else {
+            let (before, after) = (i.saturating_sub(1), (i + 1).min(len - 1));
+            (before, after, (after - before).max(1) as f64)
+        }
+    };
+    let value = |x: usize, y: usize| heights[y * width + x] as f64;
+
+    let mut pixels = vec![0; width * height * 4];
+    for y in 0..height {
+        let (down, up, span_y) = neighbors(y, height);
+        for x in 0..width {
+            let (left, right, span_x) = neighbors(x, width);
+            let dx = (value(right, y) - value(left, y)) / (span_x * spacing_x);
+            let dy = (value(x, up) - value(x, down)) / (span_y * spacing_y);
+            let normal = [-dx, -dy, 1.0];
+            let length = normal.iter().map(|n| n * n).sum::<f64>().sqrt();
+            let pixel = &mut pixels[(y * width + x) * 4..][..4];
+            for (channel, n) in pixel.iter_mut().zip(normal) {
+                *channel = ((n / length * 0.5 + 0.5) * 255.0).round() as u8;
+            }
+            pixel[3] = 255;
+        }
+    }
+    Ok(pixels)
+}
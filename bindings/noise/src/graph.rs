@@ -0,0 +1,715 @@
+use std::fmt;
+use std::rc::Rc;
+
+use noise::{
+    Abs, Add, Blend, Clamp, Constant, Curve, Exponent, Max, Min, Multiply, Negate, NoiseFn, Power, RotatePoint,
+    ScaleBias, ScalePoint, Select, Terrace, TranslatePoint, Turbulence,
+};
+use wasm_bindgen::prelude::*;
+
+use crate::fractal::{Billow, Fbm, FractalParams, HybridMulti, NoiseSource, RidgedMulti};
+use crate::generator::{AnyGenerator, GeneratorType, MAX_CHECKERBOARD_SIZE};
+use crate::gradient::Gradient;
+use crate::json::Json;
+use crate::sampling::{impl_sampling, no_4d_noise, Source4D};
+use crate::seed::{seed_from_number, seed_from_str};
+use crate::warp::{DomainWarp, MAX_WARP_ITERATIONS};
+use crate::worley::{DistanceFunction, WorleyReturnType};
+
+/// A noise function compiled from a JSON description of a graph of the `noise` crate modules, as an object or its
+/// text, so that a chain of modules costs one wasm call per sample, or per batch with the sampling methods.
+///
+/// Each node is an object with a `type` and its parameters, the nodes it reads are in `source`, `sources` (a pair)
+/// and `control`, and a number stands for a `Constant` node:
+///
+/// - `Perlin`, `Simplex`, `OpenSimplex`, `Value`, `SuperSimplex`: `seed`.
+/// - `Checkerboard`: `size`, the blocks are `2^size` wide and it's at most 30; `Cylinders`, `Spheres`: `frequency`.
+/// - `Worley`: `seed`, `frequency`, `distanceFunction` and `returnType` by their names.
+/// - `Fbm`, `Billow`, `RidgedMulti`, `HybridMulti`: `seed`, `source` (`"Perlin"`, `"Simplex"` or `"OpenSimplex"`),
+///   `octaves`, `frequency`, `lacunarity`, `persistence` and `attenuation` for `RidgedMulti`.
+/// - `Constant`: `value`.
+/// - `Abs`, `Negate`; `Clamp`: `bounds`; `Exponent`: `exponent`; `ScaleBias`: `scale`, `bias`.
+/// - `Curve`: `controlPoints` as `[input, output]` pairs, at least 4; `Terrace`: `controlPoints`, at least 2, and
+///   `inverted`.
+/// - `Turbulence`: `seed`, `frequency`, `power`, `roughness`.
+/// - `ScalePoint`: `scale`; `TranslatePoint`: `translation`, a number for all the axes or `[x, y, z, w]`;
+///   `RotatePoint`: `angles` as `[x, y, z, w]` in degrees, the 2D points rotate by the z angle.
+/// - `Add`, `Multiply`, `Min`, `Max`, `Power`: `sources`.
+/// - `Select`: `sources`, `control`, `bounds` and `falloff`; `Blend`: `sources`, `control`.
+/// - `Warp`: `source`, `displacements` with one node per axis or fewer which are reused in turn, `strength` and
+///   `iterations`.
+///
+/// The seeds are numbers or strings hashed like `hashSeed()`. For example:
+///
+/// ```json
+/// { "type": "Select", "sources": [{ "type": "Perlin" }, { "type": "Fbm", "seed": "rocks" }],
+///   "control": { "type": "Worley", "frequency": 4 }, "bounds": [0, 1] }
+/// ```
+#[wasm_bindgen]
+pub struct NoiseGraph {
+    handle: Graph,
+}
+
+#[wasm_bindgen]
+impl NoiseGraph {
+    /// Compiles the description object or its JSON text, the errors start with the path of the invalid node such as
+    /// `$.sources[1].control`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(description: &JsValue) -> Result<NoiseGraph, JsError> {
+        Graph::compile(description)
+            .map(|handle| Self { handle })
+            .map_err(|err| JsError::new(&err.to_string()))
+    }
+
+    /// Samples a 2D, 3D or 4D point, the graphs with a `SuperSimplex` node throw for the 4D points since it has no
+    /// 4D noise.
+    #[wasm_bindgen]
+    pub fn get(&mut self, input: Vec<f64>) -> Result<f64, JsError> {
+        match input.len() {
+            2 => Ok(self.handle.get([ input[0], input[1] ])),
+            3 => Ok(self.handle.get([ input[0], input[1], input[2] ])),
+            4 => {
+                self.handle.check_4d()?;
+                Ok(self.handle.get([ input[0], input[1], input[2], input[3] ]))
+            }
+            _ => Err(JsError::new("Only accepts 2, 3 or 4 numbers")),
+        }
+    }
+}
+
+impl NoiseGraph {
+    pub(crate) fn graph(&self) -> Graph {
+        self.handle.clone()
+    }
+}
+
+impl_sampling!(NoiseGraph);
+
+type Module<const DIM: usize> = Box<dyn NoiseFn<f64, DIM>>;
+
+/// A generator of any dimension, boxed as a leaf of the graphs.
+trait Generator: NoiseFn<f64, 2> + NoiseFn<f64, 3> + NoiseFn<f64, 4> {}
+
+impl<T> Generator for T where T: NoiseFn<f64, 2> + NoiseFn<f64, 3> + NoiseFn<f64, 4> {}
+
+/// The modules of the `noise` crate are typed by their dimension, so the description is compiled for each of them
+/// with the graph, where any error is reported. They are shared by the clones, such as the ones in `Warp`.
+#[derive(Clone)]
+pub(crate) struct Graph {
+    modules: Rc<Modules>,
+}
+
+struct Modules {
+    /// Whether a `SuperSimplex` node drops the w coordinate of the 4D points.
+    super_simplex: bool,
+    graph2: Module<2>,
+    graph3: Module<3>,
+    graph4: Module<4>,
+}
+
+impl Graph {
+    fn compile(description: &JsValue) -> Result<Graph, GraphError> {
+        let description = Json::from_js(description)?;
+        Ok(Graph {
+            modules: Rc::new(Modules {
+                super_simplex: has_type(&description, "SuperSimplex"),
+                graph2: compile::<2>(&description, "$")?,
+                graph3: compile::<3>(&description, "$")?,
+                graph4: compile::<4>(&description, "$")?,
+            }),
+        })
+    }
+}
+
+impl NoiseFn<f64, 2> for Graph {
+    fn get(&self, point: [f64; 2]) -> f64 {
+        self.modules.graph2.get(point)
+    }
+}
+
+impl NoiseFn<f64, 3> for Graph {
+    fn get(&self, point: [f64; 3]) -> f64 {
+        self.modules.graph3.get(point)
+    }
+}
+
+impl NoiseFn<f64, 4> for Graph {
+    fn get(&self, point: [f64; 4]) -> f64 {
+        self.modules.graph4.get(point)
+    }
+}
+
+impl Gradient for Graph {}
+
+impl Source4D for Graph {
+    fn check_4d(&self) -> Result<(), JsError> {
+        if self.modules.super_simplex {
+            Err(no_4d_noise())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Whether a node of the description has the `kind` type.
+fn has_type(json: &Json, kind: &str) -> bool {
+    match json {
+        Json::Object(fields) => fields.iter().any(|(key, value)| match value {
+            Json::String(value) if key == "type" => value == kind,
+            _ => has_type(value, kind),
+        }),
+        Json::Array(items) => items.iter().any(|item| has_type(item, kind)),
+        _ => false,
+    }
+}
+
+/// An invalid node of a description, at a path like `$.sources[1].control`.
+#[derive(Debug)]
+pub(crate) struct GraphError {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+const TYPES: &[&str] = &[
+    "Perlin", "Simplex", "OpenSimplex", "Value", "SuperSimplex", "Checkerboard", "Cylinders", "Spheres", "Worley",
+    "Fbm", "Billow", "RidgedMulti", "HybridMulti", "Constant", "Abs", "Negate", "Clamp", "Exponent", "ScaleBias",
+    "Curve", "Terrace", "Turbulence", "ScalePoint", "TranslatePoint", "RotatePoint", "Add", "Multiply", "Min", "Max",
+    "Power", "Select", "Blend", "Warp",
+];
+
+fn compile<const DIM: usize>(json: &Json, path: &str) -> Result<Module<DIM>, GraphError>
+where
+    Box<dyn Generator>: NoiseFn<f64, DIM>,
+    Turbulence<Module<DIM>, noise::Perlin>: NoiseFn<f64, DIM>,
+    ScalePoint<Module<DIM>>: NoiseFn<f64, DIM>,
+    TranslatePoint<Module<DIM>>: NoiseFn<f64, DIM>,
+    RotatePoint<Module<DIM>>: NoiseFn<f64, DIM>,
+{
+    let node = match json {
+        Json::Number(value) => return Ok(generator(Constant::new(*value))),
+        Json::Object(fields) => Node { path, fields },
+        _ => {
+            return Err(GraphError {
+                path: path.to_string(),
+                message: format!("expected a node object or a number, got {}", json.kind()),
+            })
+        }
+    };
+    let source = |key: &str| node.child(key).and_then(|(json, path)| compile::<DIM>(json, &path));
+    let pair = || -> Result<(Module<DIM>, Module<DIM>), GraphError> {
+        let [(json1, path1), (json2, path2)] = node.pair("sources")?;
+        Ok((compile(json1, &path1)?, compile(json2, &path2)?))
+    };
+
+    let module: Module<DIM> = match node.kind()? {
+        "Perlin" => {
+            node.check_keys(&["seed"])?;
+            generator(noise::Perlin::new(node.seed()?))
+        }
+        "Simplex" => {
+            node.check_keys(&["seed"])?;
+            generator(noise::Simplex::new(node.seed()?))
+        }
+        "OpenSimplex" => {
+            node.check_keys(&["seed"])?;
+            generator(noise::OpenSimplex::new(node.seed()?))
+        }
+        "Value" => {
+            node.check_keys(&["seed"])?;
+            generator(AnyGenerator::new(GeneratorType::Value, node.seed()?, 1.0, 0))
+        }
+        "SuperSimplex" => {
+            node.check_keys(&["seed"])?;
+            generator(AnyGenerator::new(GeneratorType::SuperSimplex, node.seed()?, 1.0, 0))
+        }
+        "Checkerboard" => {
+            node.check_keys(&["size"])?;
+            let size = node.integer("size", 0, 0, MAX_CHECKERBOARD_SIZE as usize)?;
+            generator(AnyGenerator::new(GeneratorType::Checkerboard, 0, 1.0, size as u32))
+        }
+        "Cylinders" => {
+            node.check_keys(&["frequency"])?;
+            generator(AnyGenerator::new(GeneratorType::Cylinders, 0, node.number("frequency", 1.0)?, 0))
+        }
+        "Spheres" => {
+            node.check_keys(&["frequency"])?;
+            generator(AnyGenerator::new(GeneratorType::Spheres, 0, node.number("frequency", 1.0)?, 0))
+        }
+        "Worley" => {
+            node.check_keys(&["seed", "frequency", "distanceFunction", "returnType"])?;
+            let distance_function = node.choice(
+                "distanceFunction",
+                &[
+                    ("Euclidean", DistanceFunction::Euclidean),
+                    ("EuclideanSquared", DistanceFunction::EuclideanSquared),
+                    ("Manhattan", DistanceFunction::Manhattan),
+                    ("Chebyshev", DistanceFunction::Chebyshev),
+                    ("Quadratic", DistanceFunction::Quadratic),
+                ],
+                DistanceFunction::Euclidean,
+            )?;
+            let return_type = node.choice(
+                "returnType",
+                &[("Distance", WorleyReturnType::Distance), ("Value", WorleyReturnType::Value)],
+                WorleyReturnType::Value,
+            )?;
+            let frequency = node.number("frequency", noise::Worley::DEFAULT_FREQUENCY)?;
+            if frequency == 0.0 {
+                return Err(node.error_at("frequency", "expected a number other than 0"));
+            }
+            generator(
+                noise::Worley::new(node.seed()?)
+                    .set_frequency(frequency)
+                    .set_distance_function(distance_function.function())
+                    .set_return_type(return_type.into()),
+            )
+        }
+        "Fbm" => {
+            let (seed, source, configure) = node.fractal(false)?;
+            generator(Fbm::with_params(seed, source, configure).into_sampler())
+        }
+        "Billow" => {
+            let (seed, source, configure) = node.fractal(false)?;
+            generator(Billow::with_params(seed, source, configure).into_sampler())
+        }
+        "RidgedMulti" => {
+            let (seed, source, configure) = node.fractal(true)?;
+            generator(RidgedMulti::with_params(seed, source, configure).into_sampler())
+        }
+        "HybridMulti" => {
+            let (seed, source, configure) = node.fractal(false)?;
+            generator(HybridMulti::with_params(seed, source, configure).into_sampler())
+        }
+        "Constant" => {
+            node.check_keys(&["value"])?;
+            generator(Constant::new(node.required_number("value")?))
+        }
+        "Abs" => {
+            node.check_keys(&["source"])?;
+            Box::new(Abs::new(source("source")?))
+        }
+        "Negate" => {
+            node.check_keys(&["source"])?;
+            Box::new(Negate::new(source("source")?))
+        }
+        "Clamp" => {
+            node.check_keys(&["source", "bounds"])?;
+            let [lower, upper] = node.bounds("bounds", [-1.0, 1.0])?;
+            Box::new(Clamp::new(source("source")?).set_bounds(lower, upper))
+        }
+        "Exponent" => {
+            node.check_keys(&["source", "exponent"])?;
+            Box::new(Exponent::new(source("source")?).set_exponent(node.number("exponent", 1.0)?))
+        }
+        "ScaleBias" => {
+            node.check_keys(&["source", "scale", "bias"])?;
+            Box::new(
+                ScaleBias::new(source("source")?)
+                    .set_scale(node.number("scale", 1.0)?)
+                    .set_bias(node.number("bias", 0.0)?),
+            )
+        }
+        "Curve" => {
+            node.check_keys(&["source", "controlPoints"])?;
+            let points = node.control_points(4, |json, path| match json {
+                Json::Array(pair) => match pair.as_slice() {
+                    [Json::Number(input), Json::Number(output)] => Ok((*input, *output)),
+                    _ => Err(GraphError {
+                        path: path.to_string(),
+                        message: "expected an [input, output] pair of numbers".to_string(),
+                    }),
+                },
+                _ => Err(GraphError {
+                    path: path.to_string(),
+                    message: format!("expected an [input, output] pair of numbers, got {}", json.kind()),
+                }),
+            })?;
+            let inputs: Vec<f64> = points.iter().map(|(input, _)| *input).collect();
+            node.check_distinct("controlPoints", &inputs, 4)?;
+            let curve = points
+                .into_iter()
+                .fold(Curve::new(source("source")?), |curve, (input, output)| {
+                    curve.add_control_point(input, output)
+                });
+            Box::new(curve)
+        }
+        "Terrace" => {
+            node.check_keys(&["source", "controlPoints", "inverted"])?;
+            let points = node.control_points(2, |json, path| match json {
+                Json::Number(value) => Ok(*value),
+                _ => Err(GraphError {
+                    path: path.to_string(),
+                    message: format!("expected a number, got {}", json.kind()),
+                }),
+            })?;
+            node.check_distinct("controlPoints", &points, 2)?;
+            let terrace = points
+                .into_iter()
+                .fold(Terrace::new(source("source")?), |terrace, point| terrace.add_control_point(point));
+            Box::new(terrace.invert_terraces(node.boolean("inverted", false)?))
+        }
+        "Turbulence" => {
+            node.check_keys(&["source", "seed", "frequency", "power", "roughness"])?;
+            type Defaults = Turbulence<(), noise::Perlin>;
+            let max_roughness = noise::Fbm::<noise::Perlin>::MAX_OCTAVES;
+            let roughness = node.integer("roughness", Defaults::DEFAULT_ROUGHNESS, 1, max_roughness)?;
+            // The displacements are seeded `seed` to `seed + 3 + roughness` by the `noise` crate, which doesn't wrap.
+            let seed = node.seed()? % (u32::MAX - 3 - max_roughness as u32);
+            Box::new(
+                noise::Seedable::set_seed(Turbulence::<_, noise::Perlin>::new(source("source")?), seed)
+                    .set_frequency(node.number("frequency", Defaults::DEFAULT_FREQUENCY)?)
+                    .set_power(node.number("power", Defaults::DEFAULT_POWER)?)
+                    .set_roughness(roughness),
+            )
+        }
+        "ScalePoint" => {
+            node.check_keys(&["source", "scale"])?;
+            let [x, y, z, w] = node.axes("scale", 1.0)?;
+            Box::new(ScalePoint::new(source("source")?).set_all_scales(x, y, z, w))
+        }
+        "TranslatePoint" => {
+            node.check_keys(&["source", "translation"])?;
+            let [x, y, z, w] = node.axes("translation", 0.0)?;
+            Box::new(TranslatePoint::new(source("source")?).set_all_translations(x, y, z, w))
+        }
+        "RotatePoint" => {
+            node.check_keys(&["source", "angles"])?;
+            if let Some(Json::Number(_)) = node.field("angles") {
+                return Err(node.error_at("angles", "expected the [x, y, z, w] angles"));
+            }
+            let [x, y, z, w] = node.axes("angles", 0.0)?;
+            Box::new(RotatePoint::new(source("source")?).set_angles(x, y, z, w))
+        }
+        "Add" => {
+            node.check_keys(&["sources"])?;
+            let (source1, source2) = pair()?;
+            Box::new(Add::new(source1, source2))
+        }
+        "Multiply" => {
+            node.check_keys(&["sources"])?;
+            let (source1, source2) = pair()?;
+            Box::new(Multiply::new(source1, source2))
+        }
+        "Min" => {
+            node.check_keys(&["sources"])?;
+            let (source1, source2) = pair()?;
+            Box::new(Min::new(source1, source2))
+        }
+        "Max" => {
+            node.check_keys(&["sources"])?;
+            let (source1, source2) = pair()?;
+            Box::new(Max::new(source1, source2))
+        }
+        "Power" => {
+            node.check_keys(&["sources"])?;
+            let (source1, source2) = pair()?;
+            Box::new(Power::new(source1, source2))
+        }
+        "Select" => {
+            node.check_keys(&["sources", "control", "bounds", "falloff"])?;
+            let (source1, source2) = pair()?;
+            let [lower, upper] = node.bounds("bounds", [0.0, 1.0])?;
+            Box::new(
+                Select::new(source1, source2, source("control")?)
+                    .set_bounds(lower, upper)
+                    .set_falloff(node.number("falloff", 0.0)?),
+            )
+        }
+        "Blend" => {
+            node.check_keys(&["sources", "control"])?;
+            let (source1, source2) = pair()?;
+            Box::new(Blend::new(source1, source2, source("control")?))
+        }
+        "Warp" => {
+            node.check_keys(&["source", "displacements", "strength", "iterations"])?;
+            let displacements = node
+                .nodes("displacements")?
+                .into_iter()
+                .map(|(json, path)| compile::<DIM>(json, &path))
+                .collect::<Result<Vec<_>, _>>()?;
+            Box::new(DomainWarp::new(
+                source("source")?,
+                displacements,
+                node.number("strength", 1.0)?,
+                node.integer("iterations", 1, 0, MAX_WARP_ITERATIONS)?,
+            ))
+        }
+        kind => {
+            return Err(node.error_at(
+                "type",
+                format!("unknown type \"{}\", expected one of {}", kind, TYPES.join(", ")),
+            ))
+        }
+    };
+    Ok(module)
+}
+
+fn generator<const DIM: usize>(generator: impl Generator + 'static) -> Module<DIM>
+where
+    Box<dyn Generator>: NoiseFn<f64, DIM>,
+{
+    let generator: Box<dyn Generator> = Box::new(generator);
+    Box::new(generator)
+}
+
+/// Reads the parameters of a node object, the errors point at the invalid field.
+struct Node<'a> {
+    path: &'a str,
+    fields: &'a [(String, Json)],
+}
+
+impl<'a> Node<'a> {
+    fn error_at(&self, key: &str, message: impl Into<String>) -> GraphError {
+        GraphError {
+            path: format!("{}.{}", self.path, key),
+            message: message.into(),
+        }
+    }
+
+    fn field(&self, key: &str) -> Option<&'a Json> {
+        self.fields.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    fn kind(&self) -> Result<&'a str, GraphError> {
+        match self.field("type") {
+            Some(Json::String(kind)) => Ok(kind),
+            Some(json) => Err(self.error_at("type", format!("expected a string, got {}", json.kind()))),
+            None => Err(GraphError {
+                path: self.path.to_string(),
+                message: format!("missing the \"type\" of the node, one of {}", TYPES.join(", ")),
+            }),
+        }
+    }
+
+    /// Rejects the keys which the node doesn't read, which are likely misspelled.
+    fn check_keys(&self, keys: &[&str]) -> Result<(), GraphError> {
+        match self.fields.iter().find(|(k, _)| k != "type" && !keys.contains(&k.as_str())) {
+            Some((key, _)) => Err(self.error_at(
+                key,
+                format!("unknown parameter, expected one of {}", keys.join(", ")),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn child(&self, key: &str) -> Result<(&'a Json, String), GraphError> {
+        match self.field(key) {
+            Some(json) => Ok((json, format!("{}.{}", self.path, key))),
+            None => Err(GraphError {
+                path: self.path.to_string(),
+                message: format!("missing the \"{}\" node", key),
+            }),
+        }
+    }
+
+    fn pair(&self, key: &str) -> Result<[(&'a Json, String); 2], GraphError> {
+        let (json, path) = self.child(key)?;
+        match json {
+            Json::Array(items) if items.len() == 2 => Ok([
+                (&items[0], format!("{}[0]", path)),
+                (&items[1], format!("{}[1]", path)),
+            ]),
+            Json::Array(items) => Err(GraphError {
+                path,
+                message: format!("expected 2 nodes, got {}", items.len()),
+            }),
+            _ => Err(GraphError {
+                path,
+                message: format!("expected an array of 2 nodes, got {}", json.kind()),
+            }),
+        }
+    }
+
+    /// A non-empty array of nodes.
+    fn nodes(&self, key: &str) -> Result<Vec<(&'a Json, String)>, GraphError> {
+        let (json, path) = self.child(key)?;
+        match json {
+            Json::Array(items) if !items.is_empty() => Ok(items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| (item, format!("{}[{}]", path, i)))
+                .collect()),
+            Json::Array(_) => Err(GraphError {
+                path,
+                message: "expected at least 1 node".to_string(),
+            }),
+            _ => Err(GraphError {
+                path,
+                message: format!("expected an array of nodes, got {}", json.kind()),
+            }),
+        }
+    }
+
+    fn optional_number(&self, key: &str) -> Result<Option<f64>, GraphError> {
+        match self.field(key) {
+            Some(Json::Number(value)) => Ok(Some(*value)),
+            Some(json) => Err(self.error_at(key, format!("expected a number, got {}", json.kind()))),
+            None => Ok(None),
+        }
+    }
+
+    fn number(&self, key: &str, default: f64) -> Result<f64, GraphError> {
+        Ok(self.optional_number(key)?.unwrap_or(default))
+    }
+
+    fn required_number(&self, key: &str) -> Result<f64, GraphError> {
+        self.optional_number(key)?.ok_or_else(|| GraphError {
+            path: self.path.to_string(),
+            message: format!("missing the \"{}\" number", key),
+        })
+    }
+
+    fn integer(&self, key: &str, default: usize, min: usize, max: usize) -> Result<usize, GraphError> {
+        match self.optional_number(key)? {
+            Some(value) if value.fract() == 0.0 && value >= min as f64 && value <= max as f64 => Ok(value as usize),
+            Some(_) => Err(self.error_at(key, format!("expected an integer from {} to {}", min, max))),
+            None => Ok(default),
+        }
+    }
+
+    fn boolean(&self, key: &str, default: bool) -> Result<bool, GraphError> {
+        match self.field(key) {
+            Some(Json::Bool(value)) => Ok(*value),
+            Some(json) => Err(self.error_at(key, format!("expected a boolean, got {}", json.kind()))),
+            None => Ok(default),
+        }
+    }
+
+    /// A number or a string seed hashed like `hashSeed()`, 0 by default like the `noise` crate.
+    fn seed(&self) -> Result<u32, GraphError> {
+        match self.field("seed") {
+            Some(Json::Number(value)) => Ok(seed_from_number(*value)),
+            Some(Json::String(value)) => Ok(seed_from_str(value)),
+            Some(json) => Err(self.error_at("seed", format!("expected a number or a string, got {}", json.kind()))),
+            None => Ok(0),
+        }
+    }
+
+    fn choice<T: Copy>(&self, key: &str, options: &[(&str, T)], default: T) -> Result<T, GraphError> {
+        let names = || options.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ");
+        match self.field(key) {
+            Some(Json::String(name)) => options
+                .iter()
+                .find(|(option, _)| option == name)
+                .map(|(_, value)| *value)
+                .ok_or_else(|| self.error_at(key, format!("unknown value \"{}\", expected one of {}", name, names()))),
+            Some(json) => Err(self.error_at(key, format!("expected one of {}, got {}", names(), json.kind()))),
+            None => Ok(default),
+        }
+    }
+
+    /// A `[lower, upper]` pair.
+    fn bounds(&self, key: &str, default: [f64; 2]) -> Result<[f64; 2], GraphError> {
+        match self.field(key) {
+            Some(Json::Array(items)) => match items.as_slice() {
+                [Json::Number(lower), Json::Number(upper)] if lower <= upper => Ok([*lower, *upper]),
+                [Json::Number(_), Json::Number(_)] => Err(self.error_at(key, "the lower bound is above the upper one")),
+                _ => Err(self.error_at(key, "expected a [lower, upper] pair of numbers")),
+            },
+            Some(json) => Err(self.error_at(key, format!("expected a [lower, upper] pair, got {}", json.kind()))),
+            None => Ok(default),
+        }
+    }
+
+    /// A number for all the axes, or up to 4 numbers for the x, y, z and w axes, the missing ones are `default`.
+    fn axes(&self, key: &str, default: f64) -> Result<[f64; 4], GraphError> {
+        match self.field(key) {
+            Some(Json::Array(items)) if !items.is_empty() && items.len() <= 4 => {
+                let mut axes = [default; 4];
+                for (i, item) in items.iter().enumerate() {
+                    match item {
+                        Json::Number(value) => axes[i] = *value,
+                        _ => {
+                            return Err(GraphError {
+                                path: format!("{}.{}[{}]", self.path, key, i),
+                                message: format!("expected a number, got {}", item.kind()),
+                            })
+                        }
+                    }
+                }
+                Ok(axes)
+            }
+            Some(Json::Array(_)) => Err(self.error_at(key, "expected 1 to 4 numbers for the x, y, z and w axes")),
+            Some(_) => Ok([self.number(key, default)?; 4]),
+            None => Ok([default; 4]),
+        }
+    }
+
+    fn control_points<T>(
+        &self,
+        min: usize,
+        point: impl Fn(&Json, &str) -> Result<T, GraphError>,
+    ) -> Result<Vec<T>, GraphError> {
+        let (json, path) = self.child("controlPoints")?;
+        match json {
+            Json::Array(items) if items.len() >= min => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| point(item, &format!("{}[{}]", path, i)))
+                .collect(),
+            Json::Array(items) => Err(GraphError {
+                path,
+                message: format!("expected at least {} control points, got {}", min, items.len()),
+            }),
+            _ => Err(GraphError {
+                path,
+                message: format!("expected an array, got {}", json.kind()),
+            }),
+        }
+    }
+
+    /// The `noise` crate drops the control points with the same input, so they must be distinct to count.
+    fn check_distinct(&self, key: &str, inputs: &[f64], min: usize) -> Result<(), GraphError> {
+        let mut sorted = inputs.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        sorted.dedup_by(|a, b| (*a - *b).abs() < f64::EPSILON);
+        if sorted.len() < min {
+            Err(self.error_at(key, format!("expected at least {} distinct inputs, got {}", min, sorted.len())))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The seed, source and the `configure` function of the parameters of a fractal node.
+    fn fractal(
+        &self,
+        attenuation: bool,
+    ) -> Result<(u32, NoiseSource, impl FnOnce(&mut FractalParams)), GraphError> {
+        let mut keys = vec!["seed", "source", "octaves", "frequency", "lacunarity", "persistence"];
+        if attenuation {
+            keys.push("attenuation");
+        }
+        self.check_keys(&keys)?;
+        let source = self.choice(
+            "source",
+            &[
+                ("Perlin", NoiseSource::Perlin),
+                ("Simplex", NoiseSource::Simplex),
+                ("OpenSimplex", NoiseSource::OpenSimplex),
+            ],
+            NoiseSource::Perlin,
+        )?;
+        let octaves = match self.field("octaves") {
+            Some(_) => Some(self.integer("octaves", 0, 1, noise::Fbm::<noise::Perlin>::MAX_OCTAVES)?),
+            None => None,
+        };
+        let frequency = self.optional_number("frequency")?;
+        let lacunarity = self.optional_number("lacunarity")?;
+        let persistence = self.optional_number("persistence")?;
+        let attenuation = self.optional_number("attenuation")?;
+        let configure = move |params: &mut FractalParams| {
+            params.octaves = octaves.unwrap_or(params.octaves);
+            params.frequency = frequency.unwrap_or(params.frequency);
+            params.lacunarity = lacunarity.unwrap_or(params.lacunarity);
+            params.persistence = persistence.unwrap_or(params.persistence);
+            params.attenuation = attenuation.unwrap_or(params.attenuation);
+        };
+        Ok((self.seed()?, source, configure))
+    }
+}
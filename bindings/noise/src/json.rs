@@ -0,0 +1,102 @@
+use js_sys::{Array, Object, Reflect, JSON};
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::graph::GraphError;
+
+/// A JSON value of a noise graph description, read from the JS object once so that it can be compiled for each
+/// dimension. The objects keep the order of their keys.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+/// How deeply the arrays and objects can nest, the graphs are compiled recursively and the JS objects can be cyclic.
+const MAX_DEPTH: usize = 128;
+
+impl Json {
+    /// Reads a description object, or its JSON text which is parsed by `JSON.parse()`.
+    pub(crate) fn from_js(value: &JsValue) -> Result<Json, GraphError> {
+        match value.as_string() {
+            Some(text) => {
+                let parsed = JSON::parse(&text).map_err(|err| GraphError {
+                    path: "$".to_string(),
+                    message: format!("invalid JSON, {}", error_message(&err)),
+                })?;
+                read(&parsed, "$", 0)
+            }
+            None => read(value, "$", 0),
+        }
+    }
+
+    /// The kind of the value, for the error messages.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Json::Null => "null",
+            Json::Bool(_) => "a boolean",
+            Json::Number(_) => "a number",
+            Json::String(_) => "a string",
+            Json::Array(_) => "an array",
+            Json::Object(_) => "an object",
+        }
+    }
+}
+
+fn error_message(err: &JsValue) -> String {
+    match err.dyn_ref::<js_sys::Error>() {
+        Some(err) => err.message().into(),
+        None => format!("{:?}", err),
+    }
+}
+
+/// Reads the values which `JSON.stringify()` keeps, the `undefined` fields are skipped like it does.
+fn read(value: &JsValue, path: &str, depth: usize) -> Result<Json, GraphError> {
+    let error = |message: String| GraphError {
+        path: path.to_string(),
+        message,
+    };
+    if depth > MAX_DEPTH {
+        return Err(error("too deeply nested or cyclic".to_string()));
+    }
+    if value.is_null() || value.is_undefined() {
+        Ok(Json::Null)
+    } else if let Some(value) = value.as_bool() {
+        Ok(Json::Bool(value))
+    } else if let Some(number) = value.as_f64() {
+        if number.is_finite() {
+            Ok(Json::Number(number))
+        } else {
+            Err(error(format!("expected a finite number, got {}", number)))
+        }
+    } else if let Some(string) = value.as_string() {
+        Ok(Json::String(string))
+    } else if Array::is_array(value) {
+        Array::from(value)
+            .iter()
+            .enumerate()
+            .map(|(i, item)| read(&item, &format!("{}[{}]", path, i), depth + 1))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Json::Array)
+    } else if value.is_object() && !value.is_function() {
+        let mut fields = Vec::new();
+        for key in Object::keys(value.unchecked_ref()).iter() {
+            let key = key.as_string().unwrap_or_default();
+            let field_path = format!("{}.{}", path, key);
+            let field = Reflect::get(value, &JsValue::from_str(&key)).map_err(|err| GraphError {
+                path: field_path.clone(),
+                message: error_message(&err),
+            })?;
+            if !field.is_undefined() {
+                fields.push((key, read(&field, &field_path, depth + 1)?));
+            }
+        }
+        Ok(Json::Object(fields))
+    } else {
+        let kind = value.js_typeof().as_string().unwrap_or_default();
+        Err(error(format!("expected a JSON value, got a {}", kind)))
+    }
+}
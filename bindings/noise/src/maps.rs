@@ -0,0 +1,84 @@
+use noise::utils::{CylinderMapBuilder, NoiseMap, NoiseMapBuilder, PlaneMapBuilder, SphereMapBuilder};
+use noise::NoiseFn;
+use wasm_bindgen::prelude::*;
+
+use crate::sampling::{axes, check_output, dims_of};
+
+/// The largest width or height of a map, `noise::utils::NoiseMap` panics from 32767.
+const MAX_SIZE: usize = 32766;
+
+/// Fills `out` with a map of the `[x0, x1]` and `[y0, y1]` bounds of the plane, which tiles when `seamless` is set.
+pub(crate) fn fill_plane_map<F: NoiseFn<f64, 2>>(
+    source: &F,
+    out: &mut [f32],
+    x_bounds: &[f64],
+    y_bounds: &[f64],
+    dims: &[u32],
+    seamless: bool,
+) -> Result<(), JsError> {
+    let ([x0, x1], [y0, y1]) = (axes::<2>(x_bounds, "x bounds")?, axes::<2>(y_bounds, "y bounds")?);
+    let [width, height] = map_size(out, dims)?;
+    let map = PlaneMapBuilder::<_, 2>::new(source)
+        .set_size(width, height)
+        .set_x_bounds(x0, x1)
+        .set_y_bounds(y0, y1)
+        .set_is_seamless(seamless)
+        .build();
+    copy_map(&map, out);
+    Ok(())
+}
+
+/// Fills `out` with a map around the y axis of a cylinder of radius 1, the angles are in degrees.
+pub(crate) fn fill_cylinder_map<F: NoiseFn<f64, 3>>(
+    source: &F,
+    out: &mut [f32],
+    angle_bounds: &[f64],
+    height_bounds: &[f64],
+    dims: &[u32],
+) -> Result<(), JsError> {
+    let [angle0, angle1] = axes::<2>(angle_bounds, "angle bounds")?;
+    let [height0, height1] = axes::<2>(height_bounds, "height bounds")?;
+    let [width, height] = map_size(out, dims)?;
+    let map = CylinderMapBuilder::new(source)
+        .set_size(width, height)
+        .set_angle_bounds(angle0, angle1)
+        .set_height_bounds(height0, height1)
+        .build();
+    copy_map(&map, out);
+    Ok(())
+}
+
+/// Fills `out` with a map of the surface of a sphere of radius 1, the rows go from the first latitude to the second
+/// one and the columns from the first longitude to the second one, in degrees.
+pub(crate) fn fill_sphere_map<F: NoiseFn<f64, 3>>(
+    source: &F,
+    out: &mut [f32],
+    latitude_bounds: &[f64],
+    longitude_bounds: &[f64],
+    dims: &[u32],
+) -> Result<(), JsError> {
+    let [latitude0, latitude1] = axes::<2>(latitude_bounds, "latitude bounds")?;
+    let [longitude0, longitude1] = axes::<2>(longitude_bounds, "longitude bounds")?;
+    let [width, height] = map_size(out, dims)?;
+    let map = SphereMapBuilder::new(source)
+        .set_size(width, height)
+        .set_bounds(latitude0, latitude1, longitude0, longitude1)
+        .build();
+    copy_map(&map, out);
+    Ok(())
+}
+
+fn map_size(out: &[f32], dims: &[u32]) -> Result<[usize; 2], JsError> {
+    let [width, height] = dims_of::<2>(dims)?;
+    if width > MAX_SIZE || height > MAX_SIZE {
+        return Err(JsError::new(&format!("The maps are at most {} wide and high", MAX_SIZE)));
+    }
+    check_output(out, &[width, height])?;
+    Ok([width, height])
+}
+
+fn copy_map(map: &NoiseMap, out: &mut [f32]) {
+    for (value, sample) in out.iter_mut().zip(map.iter()) {
+        *value = *sample as f32;
+    }
+}
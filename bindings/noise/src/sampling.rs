@@ -0,0 +1,289 @@
+use std::convert::TryInto;
+
+use noise::NoiseFn;
+use wasm_bindgen::prelude::*;
+
+/// Adds the batched sampling methods to the generators, which sample many points in one call instead of crossing
+/// the wasm boundary for each of them.
+macro_rules! impl_sampling {
+    ($($generator:ident),* $(,)?) => {
+        $(
+            #[wasm_bindgen]
+            impl $generator {
+                /// Fills `out` with the noise of a `[width, height]` grid row by row, the first sample is at `origin`
+                /// and the next ones are `step` apart on each axis.
+                #[wasm_bindgen(js_name = fillGrid2D)]
+                pub fn fill_grid_2d(
+                    &self,
+                    out: &mut [f32],
+                    origin: Vec<f64>,
+                    step: Vec<f64>,
+                    dims: Vec<u32>,
+                ) -> Result<(), JsError> {
+                    crate::sampling::fill_grid_2d(&self.handle, out, &origin, &step, &dims)
+                }
+
+                /// Fills `out` with the noise of a `[width, height, depth]` grid, the x axis varies fastest and the z
+                /// axis slowest.
+                #[wasm_bindgen(js_name = fillGrid3D)]
+                pub fn fill_grid_3d(
+                    &self,
+                    out: &mut [f32],
+                    origin: Vec<f64>,
+                    step: Vec<f64>,
+                    dims: Vec<u32>,
+                ) -> Result<(), JsError> {
+                    crate::sampling::fill_grid_3d(&self.handle, out, &origin, &step, &dims)
+                }
+
+                /// Fills `out` with a `[width, height]` map of the plane within `xBounds` and `yBounds`, the map
+                /// tiles with itself on both axes when `seamless` is set.
+                #[wasm_bindgen(js_name = fillPlaneMap)]
+                pub fn fill_plane_map(
+                    &self,
+                    out: &mut [f32],
+                    x_bounds: Vec<f64>,
+                    y_bounds: Vec<f64>,
+                    dims: Vec<u32>,
+                    seamless: Option<bool>,
+                ) -> Result<(), JsError> {
+                    crate::maps::fill_plane_map(
+                        &self.handle,
+                        out,
+                        &x_bounds,
+                        &y_bounds,
+                        &dims,
+                        seamless.unwrap_or(false),
+                    )
+                }
+
+                /// Fills `out` with a `[width, height]` map wrapped around a cylinder, the columns span the
+                /// `angleBounds` in degrees and the rows the `heightBounds`. The whole cylinder is `[-180, 180]`.
+                #[wasm_bindgen(js_name = fillCylinderMap)]
+                pub fn fill_cylinder_map(
+                    &self,
+                    out: &mut [f32],
+                    angle_bounds: Vec<f64>,
+                    height_bounds: Vec<f64>,
+                    dims: Vec<u32>,
+                ) -> Result<(), JsError> {
+                    crate::maps::fill_cylinder_map(&self.handle, out, &angle_bounds, &height_bounds, &dims)
+                }
+
+                /// Fills `out` with a `[width, height]` map of a sphere, the rows span the `latitudeBounds` and the
+                /// columns the `longitudeBounds` in degrees. The whole sphere is `[-90, 90]` and `[-180, 180]`.
+                #[wasm_bindgen(js_name = fillSphereMap)]
+                pub fn fill_sphere_map(
+                    &self,
+                    out: &mut [f32],
+                    latitude_bounds: Vec<f64>,
+                    longitude_bounds: Vec<f64>,
+                    dims: Vec<u32>,
+                ) -> Result<(), JsError> {
+                    crate::maps::fill_sphere_map(&self.handle, out, &latitude_bounds, &longitude_bounds, &dims)
+                }
+
+                /// Renders the noise of a `[width, height]` grid like `fillGrid2D()` into RGBA pixels, which can be
+                /// uploaded as a raw texture.
+                #[wasm_bindgen(js_name = renderTexture)]
+                pub fn render_texture(
+                    &self,
+                    renderer: &crate::texture::TextureRenderer,
+                    origin: Vec<f64>,
+                    step: Vec<f64>,
+                    dims: Vec<u32>,
+                ) -> Result<Vec<u8>, JsError> {
+                    crate::texture::render_grid(&self.handle, renderer, &origin, &step, &dims)
+                }
+
+                /// Moves the vertices along their normals by `amplitude` times the noise at `[x, y, z, time]`, in
+                /// place. The positions are displaced from where they are, so they are reset to the rest positions
+                /// for each frame. The normals are recomputed smooth when the triangle `indices` are given.
+                #[wasm_bindgen(js_name = displaceVertices)]
+                pub fn displace_vertices(
+                    &self,
+                    positions: &mut [f32],
+                    normals: &mut [f32],
+                    amplitude: f64,
+                    time: f64,
+                    indices: Option<Vec<u32>>,
+                ) -> Result<(), JsError> {
+                    crate::displace::displace_vertices(
+                        &self.handle,
+                        positions,
+                        normals,
+                        amplitude,
+                        time,
+                        indices.as_deref(),
+                    )
+                }
+
+                /// Samples the velocities of a curl noise flow at the packed points, which have 2 coordinates each
+                /// unless `dimensions` is 3.
+                #[wasm_bindgen(js_name = sampleCurl)]
+                pub fn sample_curl(
+                    &self,
+                    flow: &crate::curl::CurlFlow,
+                    points: &[f64],
+                    dimensions: Option<u32>,
+                ) -> Result<Vec<f64>, JsError> {
+                    crate::curl::sample_curl(&self.handle, flow, points, dimensions.unwrap_or(2))
+                }
+
+                /// Moves the packed particles by `dt` seconds along a curl noise flow in place, and updates their
+                /// velocities. The particles have 2 coordinates each unless `dimensions` is 3.
+                #[wasm_bindgen(js_name = advanceParticles)]
+                pub fn advance_particles(
+                    &self,
+                    flow: &crate::curl::CurlFlow,
+                    positions: &mut [f32],
+                    velocities: &mut [f32],
+                    dt: f64,
+                    dimensions: Option<u32>,
+                ) -> Result<(), JsError> {
+                    crate::curl::advance_particles(
+                        &self.handle,
+                        flow,
+                        positions,
+                        velocities,
+                        dt,
+                        dimensions.unwrap_or(2),
+                    )
+                }
+
+                /// Samples the packed points, which have 2 coordinates each unless `dimensions` is 3 or 4.
+                #[wasm_bindgen(js_name = sampleMany)]
+                pub fn sample_many(&self, points: &[f64], dimensions: Option<u32>) -> Result<Vec<f64>, JsError> {
+                    crate::sampling::sample_many(&self.handle, points, dimensions.unwrap_or(2))
+                }
+
+                /// Samples the values and the gradients of the packed points, which have 2 coordinates each unless
+                /// `dimensions` is 3. The results are packed as `[value, dx, dy(, dz)]` per point.
+                #[wasm_bindgen(js_name = sampleGradients)]
+                pub fn sample_gradients(&self, points: &[f64], dimensions: Option<u32>) -> Result<Vec<f64>, JsError> {
+                    crate::gradient::sample_gradients(&self.handle, points, dimensions.unwrap_or(2))
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_sampling;
+
+pub(crate) fn fill_grid_2d<F: NoiseFn<f64, 2>>(
+    source: &F,
+    out: &mut [f32],
+    origin: &[f64],
+    step: &[f64],
+    dims: &[u32],
+) -> Result<(), JsError> {
+    let (origin, step, dims) = (axes::<2>(origin, "origin")?, axes::<2>(step, "step")?, dims_of::<2>(dims)?);
+    check_output(out, &dims)?;
+    let [width, height] = dims;
+    for j in 0..height {
+        let y = origin[1] + step[1] * j as f64;
+        let row = &mut out[j * width..(j + 1) * width];
+        for (i, value) in row.iter_mut().enumerate() {
+            *value = source.get([origin[0] + step[0] * i as f64, y]) as f32;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn fill_grid_3d<F: NoiseFn<f64, 3>>(
+    source: &F,
+    out: &mut [f32],
+    origin: &[f64],
+    step: &[f64],
+    dims: &[u32],
+) -> Result<(), JsError> {
+    let (origin, step, dims) = (axes::<3>(origin, "origin")?, axes::<3>(step, "step")?, dims_of::<3>(dims)?);
+    check_output(out, &dims)?;
+    let [width, height, depth] = dims;
+    for k in 0..depth {
+        let z = origin[2] + step[2] * k as f64;
+        for j in 0..height {
+            let y = origin[1] + step[1] * j as f64;
+            let start = (k * height + j) * width;
+            for (i, value) in out[start..start + width].iter_mut().enumerate() {
+                *value = source.get([origin[0] + step[0] * i as f64, y, z]) as f32;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The sources which sample the 4D points, `SuperSimplex` has no 4D noise so the sources made of it reject them
+/// instead of dropping their w coordinate, e.g. the time of `displaceVertices()`.
+pub(crate) trait Source4D {
+    fn check_4d(&self) -> Result<(), JsError> {
+        Ok(())
+    }
+}
+
+impl Source4D for noise::OpenSimplex {}
+impl Source4D for noise::Perlin {}
+impl Source4D for noise::Simplex {}
+impl Source4D for noise::Worley {}
+
+/// The error of the sources which have no 4D noise.
+pub(crate) fn no_4d_noise() -> JsError {
+    JsError::new("SuperSimplex has no 4D noise, sample it in 2D or 3D")
+}
+
+pub(crate) fn sample_many<F>(source: &F, points: &[f64], dimensions: u32) -> Result<Vec<f64>, JsError>
+where
+    F: NoiseFn<f64, 2> + NoiseFn<f64, 3> + NoiseFn<f64, 4> + Source4D,
+{
+    let dimensions = dimensions as usize;
+    if !(2..=4).contains(&dimensions) {
+        return Err(JsError::new("Only accepts 2, 3 or 4 dimensions"));
+    }
+    if points.len() % dimensions != 0 {
+        return Err(JsError::new(&format!(
+            "The number of coordinates {} is not a multiple of {}",
+            points.len(),
+            dimensions
+        )));
+    }
+    if dimensions == 4 {
+        source.check_4d()?;
+    }
+    Ok(points
+        .chunks_exact(dimensions)
+        .map(|point| match point {
+            [x, y] => source.get([*x, *y]),
+            [x, y, z] => source.get([*x, *y, *z]),
+            [x, y, z, w] => source.get([*x, *y, *z, *w]),
+            _ => unreachable!(),
+        })
+        .collect())
+}
+
+pub(crate) fn axes<const N: usize>(values: &[f64], name: &str) -> Result<[f64; N], JsError> {
+    values
+        .try_into()
+        .map_err(|_| JsError::new(&format!("The {} must have {} numbers", name, N)))
+}
+
+pub(crate) fn dims_of<const N: usize>(values: &[u32]) -> Result<[usize; N], JsError> {
+    let dims: [u32; N] = values
+        .try_into()
+        .map_err(|_| JsError::new(&format!("The dims must have {} numbers", N)))?;
+    Ok(dims.map(|n| n as usize))
+}
+
+pub(crate) fn check_output(out: &[f32], dims: &[usize]) -> Result<(), JsError> {
+    let count = dims
+        .iter()
+        .try_fold(1usize, |count, &n| count.checked_mul(n))
+        .ok_or_else(|| JsError::new(&format!("The grid of {:?} values is too large", dims)))?;
+    if out.len() < count {
+        return Err(JsError::new(&format!(
+            "The output holds {} values but the grid has {}",
+            out.len(),
+            count
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use wasm_bindgen::prelude::*;
+
+/// Hashes a JS number or string into a generator seed, which is the same across runs and platforms.
+///
+/// An integer in the `u32` range is the seed itself, so that `hashSeed(42)` seeds like `new Perlin(42)`. The other
+/// numbers, e.g. the fractions of `Math.random()` which would all truncate to 0, and the strings are hashed, so
+/// close values give unrelated seeds.
+#[wasm_bindgen(js_name = hashSeed)]
+pub fn hash_seed(value: &JsValue) -> Result<u32, JsError> {
+    if let Some(number) = value.as_f64() {
+        Ok(seed_from_number(number))
+    } else if let Some(string) = value.as_string() {
+        Ok(seed_from_str(&string))
+    } else {
+        Err(JsError::new("The seed must be a number or a string"))
+    }
+}
+
+pub(crate) fn seed_from_number(number: f64) -> u32 {
+    if number.fract() == 0.0 && number >= 0.0 && number <= u32::MAX as f64 {
+        return number as u32;
+    }
+    let bits = if number.is_nan() { f64::NAN.to_bits() } else { number.to_bits() };
+    hash(b'n', &bits.to_le_bytes())
+}
+
+pub(crate) fn seed_from_str(string: &str) -> u32 {
+    hash(b's', string.as_bytes())
+}
+
+/// 32-bit FNV-1a of the tagged bytes, followed by the MurmurHash3 finalizer to spread the low bits.
+pub(crate) fn hash(tag: u8, bytes: &[u8]) -> u32 {
+    let mut hash = 0x811c_9dc5_u32;
+    for byte in std::iter::once(&tag).chain(bytes) {
+        hash ^= *byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^ (hash >> 16)
+}
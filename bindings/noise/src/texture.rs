@@ -0,0 +1,289 @@
+use std::f64::consts::SQRT_2;
+
+use noise::utils::Color;
+use noise::NoiseFn;
+use wasm_bindgen::prelude::*;
+
+use crate::sampling;
+
+/// The color gradients of `TextureRenderer`, the ones of the `noise` crate and its texture examples besides `Clouds`
+/// and `Marble`.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GradientPreset {
+    Grayscale,
+    Terrain,
+    Rainbow,
+    /// White clouds fading to transparent, to be layered over a sky.
+    Clouds,
+    Marble,
+    Wood,
+    Jade,
+    Granite,
+    Slime,
+}
+
+#[rustfmt::skip]
+fn gradient_points(preset: GradientPreset) -> &'static [(f64, Color)] {
+    match preset {
+        GradientPreset::Grayscale => &[
+            (-1.0, [  0,   0,   0, 255]),
+            ( 1.0, [255, 255, 255, 255]),
+        ],
+        GradientPreset::Terrain => &[
+            (-1.00,            [  0,   0,   0, 255]),
+            (-256.0 / 16384.0, [  6,  58, 127, 255]),
+            (-1.0 / 16384.0,   [ 14, 112, 192, 255]),
+            (0.0,              [ 70, 120,  60, 255]),
+            (1024.0 / 16384.0, [110, 140,  75, 255]),
+            (2048.0 / 16384.0, [160, 140, 111, 255]),
+            (3072.0 / 16384.0, [184, 163, 141, 255]),
+            (4096.0 / 16384.0, [128, 128, 128, 255]),
+            (5632.0 / 16384.0, [128, 128, 128, 255]),
+            (6144.0 / 16384.0, [250, 250, 250, 255]),
+            (1.0,              [255, 255, 255, 255]),
+        ],
+        GradientPreset::Rainbow => &[
+            (-1.0, [255,   0,   0, 255]),
+            (-0.7, [255, 255,   0, 255]),
+            (-0.4, [  0, 255,   0, 255]),
+            ( 0.0, [  0, 255, 255, 255]),
+            ( 0.3, [  0,   0, 255, 255]),
+            ( 0.6, [255,   0, 255, 255]),
+            ( 1.0, [255,   0,   0, 255]),
+        ],
+        GradientPreset::Clouds => &[
+            (-1.00, [255, 255, 255,   0]),
+            (-0.25, [255, 255, 255,   0]),
+            ( 0.40, [246, 248, 255, 224]),
+            ( 1.00, [255, 255, 255, 255]),
+        ],
+        GradientPreset::Marble => &[
+            (-1.00, [ 44,  42,  48, 255]),
+            (-0.80, [120, 118, 124, 255]),
+            (-0.55, [224, 222, 217, 255]),
+            ( 0.00, [240, 238, 232, 255]),
+            ( 1.00, [252, 251, 248, 255]),
+        ],
+        GradientPreset::Wood => &[
+            (-1.0, [189,  94,   4, 255]),
+            ( 0.5, [144,  48,   6, 255]),
+            ( 1.0, [ 60,  10,   8, 255]),
+        ],
+        GradientPreset::Jade => &[
+            (-1.000, [ 24, 146, 102, 255]),
+            ( 0.000, [ 78, 154, 115, 255]),
+            ( 0.250, [128, 204, 165, 255]),
+            ( 0.375, [ 78, 154, 115, 255]),
+            ( 1.000, [ 29, 135, 102, 255]),
+        ],
+        GradientPreset::Granite => &[
+            (-1.0000, [  0,   0,   0, 255]),
+            (-0.9375, [  0,   0,   0, 255]),
+            (-0.8750, [216, 216, 242, 255]),
+            ( 0.0000, [191, 191, 191, 255]),
+            ( 0.5000, [210, 116, 125, 255]),
+            ( 0.7500, [210, 113,  98, 255]),
+            ( 1.0000, [255, 176, 192, 255]),
+        ],
+        GradientPreset::Slime => &[
+            (-1.0, [160,  64,  42, 255]),
+            ( 0.0, [ 64, 192,  64, 255]),
+            ( 1.0, [128, 255, 128, 255]),
+        ],
+    }
+}
+
+/// A color gradient like `noise::utils::ColorGradient`, which returns transparent black for the value of its last
+/// point instead of its color.
+#[derive(Clone, Debug, Default)]
+struct Gradient {
+    /// Sorted by position, without duplicates.
+    points: Vec<(f64, Color)>,
+}
+
+impl Gradient {
+    fn from_preset(preset: GradientPreset) -> Self {
+        Self {
+            points: gradient_points(preset).to_vec(),
+        }
+    }
+
+    /// Adds a point, or replaces the color of the point at the same position.
+    fn add_point(&mut self, position: f64, color: Color) {
+        match self.points.iter().position(|(p, _)| *p >= position) {
+            Some(i) if self.points[i].0 == position => self.points[i].1 = color,
+            Some(i) => self.points.insert(i, (position, color)),
+            None => self.points.push((position, color)),
+        }
+    }
+
+    fn color(&self, value: f64) -> Color {
+        let next = self.points.iter().position(|(position, _)| *position > value);
+        match next {
+            _ if self.points.is_empty() => [0; 4],
+            Some(0) => self.points[0].1,
+            None => self.points[self.points.len() - 1].1,
+            Some(i) => {
+                let ((p0, c0), (p1, c1)) = (self.points[i - 1], self.points[i]);
+                let alpha = (value - p0) / (p1 - p0);
+                let mut color = [0; 4];
+                for channel in 0..4 {
+                    let (c0, c1) = (c0[channel] as f64 / 255.0, c1[channel] as f64 / 255.0);
+                    color[channel] = ((c1 - c0).mul_add(alpha, c0) * 255.0) as u8;
+                }
+                color
+            }
+        }
+    }
+}
+
+/// Renders noise fields into RGBA pixels through a color gradient, with the optional light of
+/// `noise::utils::ImageRenderer` which shades the field as a height map.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct TextureRenderer {
+    gradient: Gradient,
+    light_color: Color,
+
+    /// Shades the field as a height map lit by a distant light.
+    #[wasm_bindgen(js_name = lightEnabled)]
+    pub light_enabled: bool,
+
+    /// The direction of the light in degrees, counterclockwise from the x axis, 45 by default.
+    #[wasm_bindgen(js_name = lightAzimuth)]
+    pub light_azimuth: f64,
+
+    /// The angle of the light above the field in degrees, 45 by default.
+    #[wasm_bindgen(js_name = lightElevation)]
+    pub light_elevation: f64,
+
+    /// How much the slopes change the light, 1 by default, the field values are the heights.
+    #[wasm_bindgen(js_name = lightContrast)]
+    pub light_contrast: f64,
+
+    #[wasm_bindgen(js_name = lightBrightness)]
+    pub light_brightness: f64,
+
+    /// Lights the edges with the values of the opposite edges, for the textures which tile.
+    pub wrap: bool,
+}
+
+#[wasm_bindgen]
+impl TextureRenderer {
+    /// Creates a renderer of the gradient `preset`, grayscale by default, without light.
+    #[wasm_bindgen(constructor)]
+    pub fn new(preset: Option<GradientPreset>) -> Self {
+        Self {
+            gradient: Gradient::from_preset(preset.unwrap_or(GradientPreset::Grayscale)),
+            light_color: [255; 4],
+            light_enabled: false,
+            light_azimuth: 45.0,
+            light_elevation: 45.0,
+            light_contrast: 1.0,
+            light_brightness: 1.0,
+            wrap: false,
+        }
+    }
+
+    #[wasm_bindgen(js_name = setGradient)]
+    pub fn set_gradient(&mut self, preset: GradientPreset) {
+        self.gradient = Gradient::from_preset(preset);
+    }
+
+    /// Removes the points of the gradient, to add custom ones.
+    #[wasm_bindgen(js_name = clearGradient)]
+    pub fn clear_gradient(&mut self) {
+        self.gradient.points.clear();
+    }
+
+    /// Adds a color at a noise value, or replaces the color at that value. The values between the points blend the
+    /// colors and the ones out of the points take the nearest color. The alpha is 255 by default.
+    #[wasm_bindgen(js_name = addGradientPoint)]
+    pub fn add_gradient_point(&mut self, position: f64, red: u8, green: u8, blue: u8, alpha: Option<u8>) {
+        self.gradient.add_point(position, [red, green, blue, alpha.unwrap_or(255)]);
+    }
+
+    #[wasm_bindgen(js_name = setLightColor)]
+    pub fn set_light_color(&mut self, red: u8, green: u8, blue: u8) {
+        self.light_color = [red, green, blue, 255];
+    }
+
+    /// Renders the `[width, height]` field of noise values row by row, e.g. from `fillGrid2D()`, into RGBA pixels.
+    #[wasm_bindgen]
+    pub fn render(&self, values: &[f32], width: u32, height: u32) -> Result<Vec<u8>, JsError> {
+        let count = field_size(width as usize, height as usize)?;
+        if values.len() != count {
+            return Err(JsError::new(&format!(
+                "The field holds {} values but the grid has {}",
+                values.len(),
+                count
+            )));
+        }
+        Ok(self.render_field(values, width as usize, height as usize))
+    }
+}
+
+impl TextureRenderer {
+    /// The light model of `noise::utils::ImageRenderer`, which is only built with the `image` crate.
+    fn render_field(&self, values: &[f32], width: usize, height: usize) -> Vec<u8> {
+        let (azimuth, elevation) = (self.light_azimuth.to_radians(), self.light_elevation.to_radians());
+        let ambient = SQRT_2 * elevation.sin() / 2.0;
+        let slope = (1.0 - ambient) * self.light_contrast.max(0.0) * SQRT_2 * elevation.cos();
+        let (slope_x, slope_y) = (slope * azimuth.cos(), slope * azimuth.sin());
+        let value = |x: usize, y: usize| values[y * width + x] as f64;
+
+        let mut pixels = vec![0; width * height * 4];
+        for y in 0..height {
+            for x in 0..width {
+                let mut color = self.gradient.color(value(x, y));
+                if self.light_enabled {
+                    let (left, right) = self.neighbors(x, width);
+                    let (down, up) = self.neighbors(y, height);
+                    let intensity = slope_x * (value(left, y) - value(right, y))
+                        + slope_y * (value(x, down) - value(x, up))
+                        + ambient;
+                    let intensity = intensity.max(0.0) * self.light_brightness;
+                    for (channel, light_channel) in color.iter_mut().zip(self.light_color).take(3) {
+                        let light = intensity * light_channel as f64 / 255.0;
+                        let lit = *channel as f64 / 255.0 * light;
+                        *channel = (lit.clamp(0.0, 1.0) * 255.0) as u8;
+                    }
+                }
+                pixels[(y * width + x) * 4..][..4].copy_from_slice(&color);
+            }
+        }
+        pixels
+    }
+
+    fn neighbors(&self, i: usize, len: usize) -> (usize, usize) {
+        if self.wrap {
+            ((i + len - 1) % len, (i + 1) % len)
+        } else {
+            (i.saturating_sub(1), (i + 1).min(len - 1))
+        }
+    }
+}
+
+/// The number of values of a `[width, height]` field, checked so that its RGBA pixels can be counted in a `usize`.
+fn field_size(width: usize, height: usize) -> Result<usize, JsError> {
+    width
+        .checked_mul(height)
+        .filter(|count| count.checked_mul(4).is_some())
+        .ok_or_else(|| JsError::new(&format!("The texture of {}x{} pixels is too large", width, height)))
+}
+
+/// Renders the noise of a `[width, height]` grid like `fillGrid2D()` into RGBA pixels.
+pub(crate) fn render_grid<F: NoiseFn<f64, 2>>(
+    source: &F,
+    renderer: &TextureRenderer,
+    origin: &[f64],
+    step: &[f64],
+    dims: &[u32],
+) -> Result<Vec<u8>, JsError> {
+    let [width, height] = sampling::dims_of::<2>(dims)?;
+    let mut values = vec![0.0; field_size(width, height)?];
+    sampling::fill_grid_2d(source, &mut values, origin, step, dims)?;
+    Ok(renderer.render_field(&values, width, height))
+}
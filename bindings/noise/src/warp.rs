@@ -0,0 +1,155 @@
+use noise::NoiseFn;
+use wasm_bindgen::prelude::*;
+
+use crate::gradient::Gradient;
+use crate::graph::{Graph, NoiseGraph};
+use crate::sampling::{impl_sampling, Source4D};
+
+/// The offsets of the displacement samples of each axis, so that one displacement noise moves the axes differently.
+const AXIS_OFFSETS: [[f64; 4]; 4] = [
+    [0.0, 0.0, 0.0, 0.0],
+    [5.2, 1.3, 2.8, 7.1],
+    [1.7, 9.2, 4.6, 3.9],
+    [8.3, 2.8, 6.1, 0.4],
+];
+
+/// The iterations of a warp, each one samples all the displacements again so they are bounded to keep the sampling
+/// fast.
+pub(crate) const MAX_WARP_ITERATIONS: usize = 8;
+
+fn check_iterations(iterations: u32) -> Result<usize, JsError> {
+    if iterations as usize > MAX_WARP_ITERATIONS {
+        return Err(JsError::new(&format!(
+            "The iterations {} are above the maximum of {}",
+            iterations, MAX_WARP_ITERATIONS
+        )));
+    }
+    Ok(iterations as usize)
+}
+
+/// Domain warping, which samples the source at the point moved by the displacement noise, `iterations` times
+/// recursively: `source(p + strength * d(p + strength * d(p)))` for 2 iterations.
+pub(crate) struct DomainWarp<S> {
+    source: S,
+    /// The displacement of each axis, they are reused in turn when there are fewer than the axes.
+    displacements: Vec<S>,
+    pub(crate) strength: f64,
+    pub(crate) iterations: usize,
+}
+
+impl<S> DomainWarp<S> {
+    pub(crate) fn new(source: S, displacements: Vec<S>, strength: f64, iterations: usize) -> Self {
+        debug_assert!(!displacements.is_empty());
+        Self {
+            source,
+            displacements,
+            strength,
+            iterations,
+        }
+    }
+}
+
+impl<S, const DIM: usize> NoiseFn<f64, DIM> for DomainWarp<S>
+where
+    S: NoiseFn<f64, DIM>,
+{
+    fn get(&self, point: [f64; DIM]) -> f64 {
+        let mut warped = point;
+        for _ in 0..self.iterations {
+            let mut next = point;
+            for (axis, value) in next.iter_mut().enumerate() {
+                let displacement = &self.displacements[axis % self.displacements.len()];
+                let mut sample = warped;
+                for (coordinate, offset) in sample.iter_mut().zip(AXIS_OFFSETS[axis]) {
+                    *coordinate += offset;
+                }
+                *value += self.strength * displacement.get(sample);
+            }
+            warped = next;
+        }
+        self.source.get(warped)
+    }
+}
+
+impl Gradient for DomainWarp<Graph> {}
+
+impl Source4D for DomainWarp<Graph> {
+    fn check_4d(&self) -> Result<(), JsError> {
+        self.source.check_4d()?;
+        self.displacements.iter().try_for_each(|displacement| displacement.check_4d())
+    }
+}
+
+/// Domain warping of a noise graph by one or more displacement graphs, for the organic look of marble and eroded
+/// terrain. A graph can also warp with the `Warp` node.
+#[wasm_bindgen]
+pub struct Warp {
+    handle: DomainWarp<Graph>,
+}
+
+#[wasm_bindgen]
+impl Warp {
+    /// Creates the warp of `source` by `displacement`, with a strength of 1 and 1 iteration by default, up to 8
+    /// iterations. The graphs are shared, so they can be freed afterwards.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        source: &NoiseGraph,
+        displacement: &NoiseGraph,
+        strength: Option<f64>,
+        iterations: Option<u32>,
+    ) -> Result<Warp, JsError> {
+        Ok(Self {
+            handle: DomainWarp::new(
+                source.graph(),
+                vec![displacement.graph()],
+                strength.unwrap_or(1.0),
+                check_iterations(iterations.unwrap_or(1))?,
+            ),
+        })
+    }
+
+    /// Adds the displacement of the next axis, the first displacement moves all the axes until there is one per axis.
+    #[wasm_bindgen(js_name = addDisplacement)]
+    pub fn add_displacement(&mut self, displacement: &NoiseGraph) {
+        self.handle.displacements.push(displacement.graph());
+    }
+
+    /// How far the points move for a displacement noise of 1.
+    #[wasm_bindgen(getter)]
+    pub fn strength(&self) -> f64 {
+        self.handle.strength
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_strength(&mut self, v: f64) {
+        self.handle.strength = v;
+    }
+
+    /// How many times the displacement is applied to the displaced point, from 0 which samples the source unwarped
+    /// to 8.
+    #[wasm_bindgen(getter)]
+    pub fn iterations(&self) -> u32 {
+        self.handle.iterations as u32
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_iterations(&mut self, v: u32) -> Result<(), JsError> {
+        self.handle.iterations = check_iterations(v)?;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn get(&mut self, input: Vec<f64>) -> Result<f64, JsError> {
+        match input.len() {
+            2 => Ok(self.handle.get([ input[0], input[1] ])),
+            3 => Ok(self.handle.get([ input[0], input[1], input[2] ])),
+            4 => {
+                self.handle.check_4d()?;
+                Ok(self.handle.get([ input[0], input[1], input[2], input[3] ]))
+            }
+            _ => Err(JsError::new("Only accepts 2, 3 or 4 numbers")),
+        }
+    }
+}
+
+impl_sampling!(Warp);
@@ -0,0 +1,285 @@
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use noise::core::worley::{distance_functions, ReturnType};
+use noise::permutationtable::{NoiseHasher, PermutationTable};
+use noise::{NoiseFn, Seedable};
+use wasm_bindgen::prelude::*;
+
+use crate::sampling::impl_sampling;
+use crate::seed;
+
+/// The distance functions of the `noise` crate, which shape the borders of the cells.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistanceFunction {
+    Euclidean,
+    EuclideanSquared,
+    Manhattan,
+    Chebyshev,
+    Quadratic,
+}
+
+impl DistanceFunction {
+    pub(crate) fn function(self) -> fn(&[f64], &[f64]) -> f64 {
+        match self {
+            DistanceFunction::Euclidean => distance_functions::euclidean,
+            DistanceFunction::EuclideanSquared => distance_functions::euclidean_squared,
+            DistanceFunction::Manhattan => distance_functions::manhattan,
+            DistanceFunction::Chebyshev => distance_functions::chebyshev,
+            DistanceFunction::Quadratic => distance_functions::quadratic,
+        }
+    }
+}
+
+/// What `Worley` returns, the distance to the nearest feature point or a value per cell.
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorleyReturnType {
+    Distance,
+    Value,
+}
+
+impl From<WorleyReturnType> for ReturnType {
+    fn from(return_type: WorleyReturnType) -> Self {
+        match return_type {
+            WorleyReturnType::Distance => ReturnType::Distance,
+            WorleyReturnType::Value => ReturnType::Value,
+        }
+    }
+}
+
+/// Worley (Voronoi) cellular noise, from the feature points scattered one per unit cell.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Worley {
+    handle: noise::Worley,
+    /// The hasher of the feature points, the one in `handle` is private.
+    perm_table: PermutationTable,
+    distance_function: DistanceFunction,
+    return_type: WorleyReturnType,
+}
+
+#[wasm_bindgen]
+impl Worley {
+    /// Creates the noise with the euclidean distance returning the cell values, like the `noise` crate.
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u32) -> Self {
+        Self {
+            handle: noise::Worley::new(seed),
+            perm_table: PermutationTable::new(seed),
+            distance_function: DistanceFunction::Euclidean,
+            return_type: WorleyReturnType::Value,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn seed(&self) -> u32 {
+        self.handle.seed()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_seed(&mut self, v: u32) {
+        self.handle = self.handle.clone().set_seed(v);
+        self.perm_table = PermutationTable::new(v);
+    }
+
+    /// The number of feature points per unit length on each axis, it can't be 0 since the cells would be infinite.
+    #[wasm_bindgen(getter)]
+    pub fn frequency(&self) -> f64 {
+        self.handle.frequency
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_frequency(&mut self, v: f64) -> Result<(), JsError> {
+        if v == 0.0 {
+            return Err(JsError::new("The frequency of Worley noise can't be 0"));
+        }
+        self.handle.frequency = v;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter, js_name = distanceFunction)]
+    pub fn distance_function(&self) -> DistanceFunction {
+        self.distance_function
+    }
+
+    #[wasm_bindgen(setter, js_name = distanceFunction)]
+    pub fn set_distance_function(&mut self, v: DistanceFunction) {
+        self.distance_function = v;
+        self.handle = self.handle.clone().set_distance_function(v.function());
+    }
+
+    #[wasm_bindgen(getter, js_name = returnType)]
+    pub fn return_type(&self) -> WorleyReturnType {
+        self.return_type
+    }
+
+    #[wasm_bindgen(setter, js_name = returnType)]
+    pub fn set_return_type(&mut self, v: WorleyReturnType) {
+        self.return_type = v;
+        self.handle.return_type = v.into();
+    }
+
+    #[wasm_bindgen]
+    pub fn get(&mut self, input: Vec<f64>) -> Result<f64, JsError> {
+        match input.len() {
+            2 => Ok(self.handle.get([ input[0], input[1] ])),
+            3 => Ok(self.handle.get([ input[0], input[1], input[2] ])),
+            4 => Ok(self.handle.get([ input[0], input[1], input[2], input[3] ])),
+            _ => Err(JsError::new("Only accepts 2, 3 or 4 numbers")),
+        }
+    }
+
+    /// Returns the cell of the nearest feature point of a 2D point, e.g. to scatter an object per cell.
+    #[wasm_bindgen]
+    pub fn cell2(&self, x: f64, y: f64) -> WorleyCell {
+        self.nearest_cell([x, y], |index| {
+            let [x, y] = get_vec2(index);
+            vec![x, y]
+        })
+    }
+
+    /// Returns the cell of the nearest feature point of a 3D point.
+    #[wasm_bindgen]
+    pub fn cell3(&self, x: f64, y: f64, z: f64) -> WorleyCell {
+        self.nearest_cell([x, y, z], |index| get_vec3(index).to_vec())
+    }
+}
+
+impl Worley {
+    /// Finds the nearest feature point the way the `noise` crate does, so that the cells match the ones of `get()`:
+    /// the cell of the lattice point nearest to the point first, then the neighbors on the far side of each axis,
+    /// which are skipped when the point is far enough from that side.
+    fn nearest_cell<const N: usize>(&self, point: [f64; N], offset: impl Fn(usize) -> Vec<f64>) -> WorleyCell {
+        let frequency = self.handle.frequency;
+        let distance_function = self.distance_function.function();
+        let point = point.map(|v| v * frequency);
+        let whole = point.map(|v| v.floor() as isize);
+        let frac: Vec<f64> = point.iter().zip(whole.iter()).map(|(v, w)| v - *w as f64).collect();
+        let near: Vec<isize> = whole.iter().zip(frac.iter()).map(|(w, f)| w + (*f > 0.5) as isize).collect();
+        let far: Vec<isize> = whole.iter().zip(frac.iter()).map(|(w, f)| w + (*f <= 0.5) as isize).collect();
+        let range: Vec<f64> = frac.iter().map(|f| (0.5 - f).powi(2)).collect();
+
+        let feature_point = |cell: &[isize]| -> Vec<f64> {
+            let index = self.perm_table.hash(cell);
+            offset(index)
+                .iter()
+                .zip(cell)
+                .map(|(offset, cell)| offset + *cell as f64)
+                .collect()
+        };
+        let mut nearest = near.clone();
+        let mut center = feature_point(&nearest);
+        let mut distance = distance_function(&point, &center);
+
+        // The far sides are tested by the number of axes and then in the axes order, like the `noise` crate.
+        let mut masks: Vec<usize> = (1..1 << N).collect();
+        masks.sort_by_key(|mask| mask.count_ones());
+        for mask in masks {
+            if (0..N).any(|axis| mask & (1 << axis) != 0 && range[axis] >= distance) {
+                continue;
+            }
+            let cell: Vec<isize> = (0..N)
+                .map(|axis| if mask & (1 << axis) != 0 { far[axis] } else { near[axis] })
+                .collect();
+            let candidate = feature_point(&cell);
+            let candidate_distance = distance_function(&point, &candidate);
+            if candidate_distance < distance {
+                distance = candidate_distance;
+                nearest = cell;
+                center = candidate;
+            }
+        }
+
+        let mut key = self.handle.seed().to_le_bytes().to_vec();
+        for coordinate in nearest.iter() {
+            key.extend_from_slice(&(*coordinate as i64).to_le_bytes());
+        }
+        WorleyCell {
+            id: seed::hash(b'c', &key),
+            value: self.perm_table.hash(&nearest) as f64 / 255.0 * 2.0 - 1.0,
+            distance,
+            center: center.iter().map(|v| v / frequency).collect(),
+        }
+    }
+}
+
+/// The nearest feature point of a point in `Worley` noise.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct WorleyCell {
+    /// An id of the cell, which is the same for all the points in the cell and for the same seed.
+    #[wasm_bindgen(readonly)]
+    pub id: u32,
+
+    /// The value of the cell from -1 to 1, which `get()` returns with the `Value` return type.
+    #[wasm_bindgen(readonly)]
+    pub value: f64,
+
+    /// The distance to the feature point with the distance function, in the units of the frequency.
+    #[wasm_bindgen(readonly)]
+    pub distance: f64,
+
+    center: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl WorleyCell {
+    /// The feature point of the cell in the coordinates of the input.
+    #[wasm_bindgen(getter)]
+    pub fn center(&self) -> Vec<f64> {
+        self.center.clone()
+    }
+}
+
+impl_sampling!(Worley);
+
+// The offsets of the feature points from the lattice points, copied from the `noise` crate which keeps them private.
+
+#[rustfmt::skip]
+fn get_vec2(index: usize) -> [f64; 2] {
+    let length = ((index & 0xF8) >> 3) as f64 * 0.5 / 31.0;
+    let diag = length * FRAC_1_SQRT_2;
+
+    match index & 0x07 {
+        0 => [   diag,    diag],
+        1 => [   diag,   -diag],
+        2 => [  -diag,    diag],
+        3 => [  -diag,   -diag],
+        4 => [ length,     0.0],
+        5 => [-length,     0.0],
+        6 => [    0.0,  length],
+        7 => [    0.0, -length],
+        _ => unreachable!(),
+    }
+}
+
+#[rustfmt::skip]
+fn get_vec3(index: usize) -> [f64; 3] {
+    let length = ((index & 0xE0) >> 5) as f64 * 0.5 / 7.0;
+    let diag = length * FRAC_1_SQRT_2;
+
+    match index % 18 {
+        0  => [   diag,    diag,     0.0],
+        1  => [   diag,   -diag,     0.0],
+        2  => [  -diag,    diag,     0.0],
+        3  => [  -diag,   -diag,     0.0],
+        4  => [   diag,     0.0,    diag],
+        5  => [   diag,     0.0,   -diag],
+        6  => [  -diag,     0.0,    diag],
+        7  => [  -diag,     0.0,   -diag],
+        8  => [    0.0,    diag,    diag],
+        9  => [    0.0,    diag,   -diag],
+        10 => [    0.0,   -diag,    diag],
+        11 => [    0.0,   -diag,   -diag],
+        12 => [ length,     0.0,     0.0],
+        13 => [    0.0,  length,     0.0],
+        14 => [    0.0,     0.0,  length],
+        15 => [-length,     0.0,     0.0],
+        16 => [    0.0, -length,     0.0],
+        17 => [    0.0,     0.0, -length],
+        _ => unreachable!(),
+    }
+}
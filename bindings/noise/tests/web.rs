@@ -0,0 +1,519 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Compiles the object of a JSON description, the way `Noise.graph()` passes it.
+fn compile_graph(description: &str) -> Result<noise_binding::NoiseGraph, wasm_bindgen::JsError> {
+    noise_binding::NoiseGraph::new(&js_sys::JSON::parse(description).unwrap())
+}
+
+fn perlin_field(generator: &noise_binding::Perlin) -> Vec<f32> {
+    let mut field = vec![0.0; 64];
+    assert!(generator
+        .fill_grid_2d(&mut field, vec![0.5, 0.5], vec![0.37, 0.37], vec![8, 8])
+        .is_ok());
+    field
+}
+
+#[wasm_bindgen_test]
+fn seed_setter_reseeds_the_generator() {
+    use noise_binding::{OpenSimplex, Perlin, Simplex};
+
+    let mut perlin = Perlin::new(1);
+    let before = perlin_field(&perlin);
+    perlin.set_seed(2);
+    assert_eq!(perlin.seed(), 2);
+    assert_ne!(perlin_field(&perlin), before);
+    assert_eq!(perlin_field(&perlin), perlin_field(&Perlin::new(2)));
+
+    let mut simplex = Simplex::new(1);
+    simplex.set_seed(2);
+    assert_eq!(simplex.seed(), 2);
+    let mut open_simplex = OpenSimplex::new(1);
+    open_simplex.set_seed(2);
+    assert_eq!(open_simplex.seed(), 2);
+}
+
+#[wasm_bindgen_test]
+fn different_seeds_produce_different_fields() {
+    use noise_binding::{hash_seed, OpenSimplex, Perlin, Simplex};
+    use wasm_bindgen::JsValue;
+
+    let points = [0.1, 0.2, 1.3, 0.4, 2.5, 3.6, 4.7, 0.8];
+    let seeds = [0.0, 0.25, 0.75]
+        .iter()
+        .map(|seed| hash_seed(&JsValue::from(*seed)).ok().unwrap())
+        .collect::<Vec<_>>();
+    assert_ne!(seeds[1], 0);
+    assert_ne!(seeds[1], seeds[2]);
+    for pair in seeds.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        assert_ne!(
+            Perlin::new(a).sample_many(&points, None).ok(),
+            Perlin::new(b).sample_many(&points, None).ok()
+        );
+        assert_ne!(
+            Simplex::new(a).sample_many(&points, None).ok(),
+            Simplex::new(b).sample_many(&points, None).ok()
+        );
+        assert_ne!(
+            OpenSimplex::new(a).sample_many(&points, None).ok(),
+            OpenSimplex::new(b).sample_many(&points, None).ok()
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+fn seeds_are_hashed_stably() {
+    use noise_binding::hash_seed;
+    use wasm_bindgen::JsValue;
+
+    let hash = |value: JsValue| hash_seed(&value).ok();
+    assert_eq!(hash(JsValue::from(42)), Some(42));
+    assert_eq!(hash(JsValue::from(-0.0)), Some(0));
+    assert_eq!(hash(JsValue::from("terrain")), Some(1732349474));
+    assert_eq!(hash(JsValue::from(0.5)), Some(3280086200));
+    assert_ne!(hash(JsValue::from(0.25)), hash(JsValue::from(0.2500000001)));
+    assert!(hash(JsValue::NULL).is_none());
+}
+
+#[wasm_bindgen_test]
+fn fractal_parameters_rebuild_the_generator() {
+    use noise_binding::{Billow, Fbm, HybridMulti, NoiseSource, RidgedMulti};
+
+    let points = [0.1, 0.2, 1.3, 0.4, 2.5, 3.6];
+    let mut fbm = Fbm::new(7, None);
+    assert_eq!(fbm.source(), NoiseSource::Perlin);
+    let before = fbm.sample_many(&points, None).ok();
+    fbm.set_octaves(2);
+    assert_eq!(fbm.octaves(), 2);
+    assert_ne!(fbm.sample_many(&points, None).ok(), before);
+    fbm.set_octaves(0);
+    assert_eq!(fbm.octaves(), 1);
+
+    let mut ridged = RidgedMulti::new(u32::MAX, Some(NoiseSource::Simplex));
+    let before = ridged.sample_many(&points, None).ok();
+    ridged.set_attenuation(1.0);
+    assert_ne!(ridged.sample_many(&points, None).ok(), before);
+
+    let billow = Billow::new(7, Some(NoiseSource::OpenSimplex));
+    let hybrid = HybridMulti::new(7, None);
+    assert_ne!(
+        billow.sample_many(&points, None).ok(),
+        hybrid.sample_many(&points, None).ok()
+    );
+}
+
+#[wasm_bindgen_test]
+fn worley_cells_match_the_noise() {
+    use noise_binding::{DistanceFunction, Worley, WorleyReturnType};
+
+    let mut worley = Worley::new(3);
+    assert!(worley.set_frequency(2.0).is_ok());
+    assert!(worley.set_frequency(0.0).is_err());
+    assert_eq!(worley.frequency(), 2.0);
+    worley.set_distance_function(DistanceFunction::Manhattan);
+    assert_eq!(worley.distance_function(), DistanceFunction::Manhattan);
+    assert_eq!(worley.return_type(), WorleyReturnType::Value);
+
+    let cell = worley.cell2(0.3, 1.7);
+    assert_eq!(worley.get(vec![0.3, 1.7]).ok(), Some(cell.value));
+    assert_eq!(worley.cell2(0.3, 1.7).id, cell.id);
+    assert_eq!(cell.center().len(), 2);
+
+    worley.set_return_type(WorleyReturnType::Distance);
+    let cell = worley.cell3(0.3, 1.7, -2.2);
+    assert_eq!(worley.get(vec![0.3, 1.7, -2.2]).ok(), Some(cell.distance * 2.0 - 1.0));
+    assert_eq!(cell.center().len(), 3);
+}
+
+#[wasm_bindgen_test]
+fn worley_cells_are_the_ones_of_the_noise_crate() {
+    use noise::core::worley::{distance_functions, ReturnType};
+    use noise::NoiseFn;
+    use noise_binding::{DistanceFunction, Worley};
+
+    let functions: [(DistanceFunction, fn(&[f64], &[f64]) -> f64); 5] = [
+        (DistanceFunction::Euclidean, distance_functions::euclidean),
+        (DistanceFunction::EuclideanSquared, distance_functions::euclidean_squared),
+        (DistanceFunction::Manhattan, distance_functions::manhattan),
+        (DistanceFunction::Chebyshev, distance_functions::chebyshev),
+        (DistanceFunction::Quadratic, distance_functions::quadratic),
+    ];
+    for seed in [0, 1, 7, 1234, u32::MAX] {
+        for (index, &(function, distance_function)) in functions.iter().enumerate() {
+            let frequency = [1.0, 2.5, 0.3][index % 3];
+            let mut worley = Worley::new(seed);
+            assert!(worley.set_frequency(frequency).is_ok());
+            worley.set_distance_function(function);
+            let expected = noise::Worley::new(seed)
+                .set_frequency(frequency)
+                .set_distance_function(distance_function);
+            let distances = expected.clone().set_return_type(ReturnType::Distance);
+
+            for step in 0..50 {
+                let t = step as f64 * 0.37 - 9.0;
+                let (x, y, z) = (t, t * 0.61 + 0.5, -t * 1.3 + 0.25);
+                let cell = worley.cell2(x, y);
+                assert_eq!(cell.value, expected.get([x, y]));
+                assert_eq!(cell.distance * 2.0 - 1.0, distances.get([x, y]));
+                let cell = worley.cell3(x, y, z);
+                assert_eq!(cell.value, expected.get([x, y, z]));
+                assert_eq!(cell.distance * 2.0 - 1.0, distances.get([x, y, z]));
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn noise_graph_compiles_the_description() {
+    use noise_binding::{NoiseGraph, Perlin};
+    use wasm_bindgen::JsValue;
+
+    let points = [0.1, 0.2, 1.3, 0.4, 2.5, 3.6];
+    let graph = compile_graph(
+        r#"{ "type": "ScaleBias", "scale": 0.5, "bias": 0.25, "source": { "type": "Perlin", "seed": 3 } }"#,
+    )
+    .ok()
+    .unwrap();
+    let expected = Perlin::new(3)
+        .sample_many(&points, None)
+        .ok()
+        .unwrap()
+        .iter()
+        .map(|v| v * 0.5 + 0.25)
+        .collect::<Vec<_>>();
+    assert_eq!(graph.sample_many(&points, None).ok(), Some(expected));
+
+    let select = r#"{
+        "type": "Select",
+        "sources": [{ "type": "Perlin" }, { "type": "Fbm", "seed": "rocks", "octaves": 3 }],
+        "control": { "type": "Worley", "frequency": 4 },
+        "bounds": [0, 1]
+    }"#;
+    assert!(compile_graph(select).is_ok());
+    assert!(compile_graph(&select.replace("Worley", "Wörley")).is_err());
+    assert!(compile_graph(&select.replace("\"frequency\": 4", "\"frequency\": 0")).is_err());
+    assert!(compile_graph(&select.replace("[0, 1]", "[1, 0]")).is_err());
+    assert!(compile_graph(&select.replace("\"bounds\"", "\"bonds\"")).is_err());
+    assert!(compile_graph(r#"{ "type": "Curve", "source": 1, "controlPoints": [[0, 0]] }"#).is_err());
+
+    // The descriptions can also be JSON texts, and the JS values which aren't JSON are rejected.
+    let text = NoiseGraph::new(&JsValue::from_str(select)).ok().unwrap();
+    let object = compile_graph(select).ok().unwrap();
+    assert_eq!(text.sample_many(&points, Some(3)).ok(), object.sample_many(&points, Some(3)).ok());
+    assert!(NoiseGraph::new(&JsValue::from_str("{ \"type\": ")).is_err());
+    let description = js_sys::JSON::parse(r#"{ "type": "Perlin" }"#).unwrap();
+    assert!(js_sys::Reflect::set(&description, &"seed".into(), &js_sys::Function::new_no_args("")).unwrap());
+    assert!(NoiseGraph::new(&description).is_err());
+}
+
+#[wasm_bindgen_test]
+fn textures_are_rendered_through_the_gradient() {
+    use noise_binding::{GradientPreset, Perlin, TextureRenderer};
+
+    let perlin = Perlin::new(5);
+    let mut renderer = TextureRenderer::new(Some(GradientPreset::Wood));
+    let pixels = perlin
+        .render_texture(&renderer, vec![0.0, 0.0], vec![0.1, 0.1], vec![16, 8])
+        .ok()
+        .unwrap();
+    assert_eq!(pixels.len(), 16 * 8 * 4);
+    let mut field = vec![0.0; 16 * 8];
+    assert!(perlin
+        .fill_grid_2d(&mut field, vec![0.0, 0.0], vec![0.1, 0.1], vec![16, 8])
+        .is_ok());
+    assert_eq!(renderer.render(&field, 16, 8).ok(), Some(pixels.clone()));
+    assert!(renderer.render(&field, 4, 4).is_err());
+    // The sizes of the huge textures don't wrap around in the 32-bit usize of wasm.
+    assert!(renderer.render(&field, u32::MAX, u32::MAX).is_err());
+    assert!(perlin
+        .render_texture(&renderer, vec![0.0, 0.0], vec![0.1, 0.1], vec![65536, 65536])
+        .is_err());
+
+    renderer.light_enabled = true;
+    assert_ne!(renderer.render(&field, 16, 8).ok(), Some(pixels));
+
+    renderer.clear_gradient();
+    renderer.add_gradient_point(-1.0, 0, 0, 0, None);
+    renderer.add_gradient_point(1.0, 200, 100, 50, Some(128));
+    renderer.light_enabled = false;
+    assert_eq!(renderer.render(&[-2.0, 1.0], 2, 1).ok(), Some(vec![0, 0, 0, 255, 200, 100, 50, 128]));
+}
+
+#[wasm_bindgen_test]
+fn maps_wrap_the_noise_around_planes_cylinders_and_spheres() {
+    use noise_binding::Perlin;
+
+    let perlin = Perlin::new(3);
+    let mut poles = vec![0.0; 8 * 4];
+    assert!(perlin
+        .fill_sphere_map(&mut poles, vec![90.0, 90.0], vec![-180.0, 180.0], vec![8, 4])
+        .is_ok());
+    assert!(poles.iter().all(|v| (v - poles[0]).abs() < 1e-6));
+
+    let mut cylinder = vec![0.0; 8 * 4];
+    assert!(perlin
+        .fill_cylinder_map(&mut cylinder, vec![-180.0, 180.0], vec![0.0, 2.0], vec![8, 4])
+        .is_ok());
+    let angle = (-180.0 + 45.0 * 3.0_f64).to_radians();
+    let expected = perlin.clone().get(vec![angle.cos(), 1.0, angle.sin()]).ok().unwrap() as f32;
+    assert!((cylinder[2 * 8 + 3] - expected).abs() < 1e-6);
+
+    let mut tile = vec![0.0; 16 * 16];
+    let mut plane = vec![0.0; 16 * 16];
+    assert!(perlin
+        .fill_plane_map(&mut tile, vec![0.0, 4.0], vec![0.0, 4.0], vec![16, 16], Some(true))
+        .is_ok());
+    assert!(perlin
+        .fill_plane_map(&mut plane, vec![0.0, 4.0], vec![0.0, 4.0], vec![16, 16], None)
+        .is_ok());
+    assert_ne!(tile, plane);
+    assert!(perlin
+        .fill_plane_map(&mut tile, vec![0.0], vec![0.0, 4.0], vec![16, 16], None)
+        .is_err());
+    assert!(perlin
+        .fill_plane_map(&mut tile, vec![0.0, 4.0], vec![0.0, 4.0], vec![32, 32], None)
+        .is_err());
+    // The number of values of a grid doesn't wrap around in the 32-bit usize of wasm.
+    assert!(perlin
+        .fill_grid_3d(&mut tile, vec![0.0; 3], vec![1.0; 3], vec![65536, 65536, 2])
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+fn gradients_match_the_slopes_of_the_noise() {
+    use noise_binding::{normal_map, Perlin, Simplex};
+
+    let points = [0.3, 0.7, 0.1, 1.7, -2.2, 4.0];
+    let mut simplex = Simplex::new(9);
+    let mut perlin = Perlin::new(9);
+    let simplex_gradients = simplex.sample_gradients(&points, Some(3)).ok().unwrap();
+    let perlin_gradients = perlin.sample_gradients(&points, Some(3)).ok().unwrap();
+    assert_eq!(simplex_gradients.len(), 8);
+    for (i, point) in points.chunks(3).enumerate() {
+        let mut after = point.to_vec();
+        after[2] += 1e-6;
+
+        let value = simplex.get(point.to_vec()).ok().unwrap();
+        let slope = (simplex.get(after.clone()).ok().unwrap() - value) / 1e-6;
+        assert_eq!(simplex_gradients[i * 4], value);
+        assert!((simplex_gradients[i * 4 + 3] - slope).abs() < 1e-4);
+
+        let value = perlin.get(point.to_vec()).ok().unwrap();
+        let slope = (perlin.get(after).ok().unwrap() - value) / 1e-6;
+        assert_eq!(perlin_gradients[i * 4], value);
+        assert!((perlin_gradients[i * 4 + 3] - slope).abs() < 1e-4);
+    }
+    assert!(simplex.sample_gradients(&points, Some(4)).is_err());
+    assert!(simplex.sample_gradients(&points[..5], Some(3)).is_err());
+
+    let flat = normal_map(&[0.5; 4], 2, 2, 1.0, 1.0, None).ok().unwrap();
+    assert_eq!(&flat[..4], &[128, 128, 255, 255]);
+    let slope = normal_map(&[0.0, 1.0, 2.0, 3.0], 4, 1, 1.0, 1.0, None).ok().unwrap();
+    assert_eq!(&slope[..4], &[37, 128, 218, 255]);
+    // The same slope over twice the spacing is half as steep, on each axis.
+    let slope = normal_map(&[0.0, 1.0, 2.0, 3.0], 4, 1, 2.0, 0.5, None).ok().unwrap();
+    assert_eq!(&slope[..4], &[70, 128, 242, 255]);
+    let slope = normal_map(&[0.0, 1.0, 2.0, 3.0], 1, 4, 0.5, 2.0, None).ok().unwrap();
+    assert_eq!(&slope[..4], &[128, 70, 242, 255]);
+    assert!(normal_map(&[0.0; 3], 2, 2, 1.0, 1.0, None).is_err());
+    assert!(normal_map(&[0.0; 4], 2, 2, 0.0, 1.0, None).is_err());
+}
+
+#[wasm_bindgen_test]
+fn vertices_are_displaced_along_their_normals() {
+    use noise_binding::Perlin;
+
+    let mut positions = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    let mut normals = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0];
+    let constant = compile_graph("0.5").ok().unwrap();
+    assert!(constant
+        .displace_vertices(&mut positions, &mut normals, 2.0, 0.0, Some(vec![0, 1, 2]))
+        .is_ok());
+    assert_eq!(positions, vec![0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 1.0, 0.0]);
+    let mut expected = vec![0.0; 9];
+    // The edges p1 - p2 and p3 - p2 of the displaced triangle, crossed like Babylon.js.
+    let (a, b) = ([-1.0f32, 0.0, 0.0], [0.0f32, 1.0, -1.0]);
+    let face = [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]];
+    let length = face.iter().map(|n| n * n).sum::<f32>().sqrt();
+    for normal in expected.chunks_mut(3) {
+        normal.copy_from_slice(&face.map(|n| n / length));
+    }
+    assert_eq!(normals, expected);
+
+    let mut perlin = Perlin::new(4);
+    let mut positions = vec![0.2, 0.3, 0.4];
+    let mut normals = vec![0.0, 1.0, 0.0];
+    assert!(perlin
+        .displace_vertices(&mut positions, &mut normals, 0.5, 1.5, None)
+        .is_ok());
+    let offset = perlin.get(vec![0.2f32 as f64, 0.3f32 as f64, 0.4f32 as f64, 1.5]).ok().unwrap() * 0.5;
+    assert_eq!(positions, vec![0.2, (0.3f32 as f64 + offset) as f32, 0.4]);
+    assert_eq!(normals, vec![0.0, 1.0, 0.0]);
+
+    assert!(perlin
+        .displace_vertices(&mut positions, &mut normals[..2], 1.0, 0.0, None)
+        .is_err());
+    assert!(perlin
+        .displace_vertices(&mut positions, &mut normals, 1.0, 0.0, Some(vec![0, 0, 1]))
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+fn curl_flows_have_no_divergence() {
+    use noise_binding::{CurlFlow, Perlin, Simplex};
+
+    let mut flow = CurlFlow::new();
+    flow.scale = 0.5;
+    flow.speed = 2.0;
+    flow.time = 0.3;
+    let h = 1e-3;
+    let divergence = |velocities: Vec<f64>, dimensions: usize| {
+        // The points are before and after the center on each axis in turn.
+        (0..dimensions)
+            .map(|axis| {
+                let (before, after) = (axis * 2 * dimensions + axis, (axis * 2 + 1) * dimensions + axis);
+                (velocities[after] - velocities[before]) / (2.0 * h)
+            })
+            .sum::<f64>()
+    };
+
+    let (x, y, z) = (0.7, -1.3, 2.1);
+    let points2 = [x - h, y, x + h, y, x, y - h, x, y + h];
+    let velocities = Simplex::new(1).sample_curl(&flow, &points2, None).ok().unwrap();
+    assert!(divergence(velocities.clone(), 2).abs() < 1e-3);
+    assert!(velocities.iter().any(|v| v.abs() > 0.1));
+
+    let points3 = [x - h, y, z, x + h, y, z, x, y - h, z, x, y + h, z, x, y, z - h, x, y, z + h];
+    let velocities = Perlin::new(1).sample_curl(&flow, &points3, Some(3)).ok().unwrap();
+    assert!(divergence(velocities.clone(), 3).abs() < 1e-3);
+    assert!(velocities.iter().any(|v| v.abs() > 0.1));
+
+    let simplex = Simplex::new(1);
+    let mut positions = vec![x as f32, y as f32];
+    let mut velocities = vec![0.0; 2];
+    let expected = simplex.sample_curl(&flow, &[x as f32 as f64, y as f32 as f64], None).ok().unwrap();
+    assert!(simplex
+        .advance_particles(&flow, &mut positions, &mut velocities, 0.1, None)
+        .is_ok());
+    assert_eq!(velocities, expected.iter().map(|v| *v as f32).collect::<Vec<_>>());
+    assert_eq!(positions[0], (x as f32 as f64 + expected[0] * 0.1) as f32);
+
+    flow.inertia = 1.0;
+    let mut velocities = vec![0.0; 2];
+    assert!(simplex
+        .advance_particles(&flow, &mut positions, &mut velocities, 0.1, None)
+        .is_ok());
+    assert!(velocities[0].abs() < expected[0].abs() as f32 * 0.2);
+    assert!(simplex
+        .advance_particles(&flow, &mut positions, &mut velocities[..1], 0.1, None)
+        .is_err());
+    assert!(simplex.sample_curl(&flow, &points3, Some(4)).is_err());
+}
+
+#[wasm_bindgen_test]
+fn warps_displace_the_points_of_the_source() {
+    use noise_binding::Warp;
+
+    let mut source = compile_graph(r#"{ "type": "Perlin", "seed": 1 }"#).ok().unwrap();
+    let mut displacement = compile_graph(r#"{ "type": "Simplex", "seed": 2 }"#).ok().unwrap();
+    let mut warp = Warp::new(&source, &displacement, Some(0.5), None).ok().unwrap();
+
+    let (x, y) = (0.3, 0.8);
+    let dx = displacement.get(vec![x, y]).ok().unwrap();
+    let dy = displacement.get(vec![x + 5.2, y + 1.3]).ok().unwrap();
+    let expected = source.get(vec![x + 0.5 * dx, y + 0.5 * dy]).ok().unwrap();
+    assert_eq!(warp.get(vec![x, y]).ok(), Some(expected));
+
+    assert!(warp.set_iterations(0).is_ok());
+    assert_eq!(warp.get(vec![x, y]).ok(), source.get(vec![x, y]).ok());
+
+    let mut graph = compile_graph(
+        r#"{
+            "type": "Warp",
+            "source": { "type": "Perlin", "seed": 1 },
+            "displacements": [{ "type": "Simplex", "seed": 2 }, { "type": "Perlin", "seed": 3 }],
+            "strength": 0.5,
+            "iterations": 2
+        }"#,
+    )
+    .ok()
+    .unwrap();
+    assert!(warp.set_iterations(2).is_ok());
+    warp.add_displacement(&compile_graph(r#"{ "type": "Perlin", "seed": 3 }"#).ok().unwrap());
+    let points = [0.1, 0.2, 1.5, -0.4, 2.0, 3.0];
+    assert_eq!(warp.sample_many(&points, None).ok(), graph.sample_many(&points, None).ok());
+    let mut grid = vec![0.0; 4];
+    assert!(warp
+        .fill_grid_2d(&mut grid, vec![0.1, 0.2], vec![1.4, 0.0], vec![2, 2])
+        .is_ok());
+    assert_eq!(grid[1] as f64, graph.get(vec![1.5, 0.2]).ok().unwrap() as f32 as f64);
+
+    assert!(warp.set_iterations(9).is_err());
+    assert_eq!(warp.iterations(), 2);
+    assert!(Warp::new(&source, &displacement, None, Some(u32::MAX)).is_err());
+    assert!(compile_graph(r#"{ "type": "Warp", "source": 1, "displacements": [] }"#).is_err());
+    assert!(compile_graph(r#"{ "type": "Warp", "source": 1, "displacements": [1], "iterations": 9 }"#).is_err());
+}
+
+#[wasm_bindgen_test]
+fn generators_are_chosen_by_type() {
+    use noise::NoiseFn;
+    use noise_binding::{CurlFlow, Generator, GeneratorType};
+
+    let points = [0.1, 0.2, 1.3, 0.4, 2.5, 3.6];
+    let mut value = Generator::new(GeneratorType::Value, Some(3));
+    let expected = noise::Value::new(3);
+    assert_eq!(value.get(vec![0.3, 1.7]).ok(), Some(expected.get([0.3, 1.7])));
+    assert_eq!(
+        value.sample_many(&points, Some(3)).ok(),
+        Some(vec![expected.get([0.1, 0.2, 1.3]), expected.get([0.4, 2.5, 3.6])])
+    );
+    value.set_seed(4);
+    assert_eq!(value.get(vec![0.3, 1.7]).ok(), Some(noise::Value::new(4).get([0.3, 1.7])));
+
+    // SuperSimplex has no 4D noise, so the 4D points and the sampling in 4D with the time throw.
+    let mut super_simplex = Generator::new(GeneratorType::SuperSimplex, None);
+    assert!(super_simplex.get(vec![0.3, 1.7, 0.2]).is_ok());
+    assert!(super_simplex.get(vec![0.3, 1.7, 0.2, 9.0]).is_err());
+    assert!(super_simplex.sample_many(&points[..4], Some(4)).is_err());
+    let (mut positions, mut normals) = (vec![0.0; 3], vec![0.0, 0.0, 1.0]);
+    assert!(super_simplex
+        .displace_vertices(&mut positions, &mut normals, 1.0, 0.5, None)
+        .is_err());
+    let mut velocities = vec![0.0; 3];
+    let flow = CurlFlow::new();
+    assert!(super_simplex
+        .advance_particles(&flow, &mut positions, &mut velocities, 0.1, Some(3))
+        .is_err());
+    let mut graph = compile_graph(r#"{ "type": "Abs", "source": { "type": "SuperSimplex" } }"#).ok().unwrap();
+    assert!(graph.get(vec![0.3, 1.7, 0.2]).is_ok());
+    assert!(graph.get(vec![0.3, 1.7, 0.2, 9.0]).is_err());
+
+    let mut spheres = Generator::new(GeneratorType::Spheres, None);
+    spheres.set_frequency(2.0);
+    assert_eq!(spheres.get(vec![0.0, 0.5, 0.0]).ok(), Some(1.0));
+    assert_eq!(spheres.get(vec![0.0, 0.0, 0.75]).ok(), Some(-1.0));
+    let mut cylinders = Generator::new(GeneratorType::Cylinders, None);
+    assert_eq!(cylinders.get(vec![0.0, 1.0, 5.0]).ok(), Some(1.0));
+
+    let mut checkerboard = Generator::new(GeneratorType::Checkerboard, None);
+    checkerboard.set_size(40);
+    assert_eq!(checkerboard.size(), 30);
+    checkerboard.set_size(1);
+    assert_eq!(checkerboard.get(vec![0.5, 0.5]).ok(), checkerboard.get(vec![1.5, 1.5]).ok());
+    assert_ne!(checkerboard.get(vec![0.5, 0.5]).ok(), checkerboard.get(vec![2.5, 0.5]).ok());
+
+    let graph = compile_graph(r#"{ "type": "Value", "seed": 4 }"#).ok().unwrap();
+    assert_eq!(graph.sample_many(&points, None).ok(), value.sample_many(&points, None).ok());
+    assert!(compile_graph(r#"{ "type": "Spheres", "frequency": 2 }"#).is_ok());
+    assert!(compile_graph(r#"{ "type": "Checkerboard", "size": 30 }"#).is_ok());
+    assert!(compile_graph(r#"{ "type": "Checkerboard", "size": 31 }"#).is_err());
+    assert!(value.get(vec![0.3]).is_err());
+}
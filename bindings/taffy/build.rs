@@ -0,0 +1,5 @@
+fn main() {
+    // Sets up the linker flags of the Node-API addon, the wasm binding doesn't need any.
+    #[cfg(feature = "napi")]
+    napi_build::setup();
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use taffy::geometry::Size;
+use taffy::style::{
+    AlignItems, AvailableSpace, Display, FlexDirection, FlexWrap, LengthPercentage, LengthPercentageAuto, Position,
+};
+use taffy::tree::LayoutTree;
+
+/// The baseline metrics returned by the measure callback of a leaf, in the content box.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LeafBaseline {
+    /// The distance from the top of the content to the first baseline.
+    pub ascent: f32,
+    /// The distance from the last baseline to the bottom of the content, the last baseline is the first one when
+    /// it's not given.
+    pub descent: Option<f32>,
+}
+
+/// The leaf baselines by node, which are written by the measure functions, so they need to be `Send + Sync`.
+pub(crate) type LeafBaselines = Arc<Mutex<HashMap<taffy::node::Node, LeafBaseline>>>;
+
+/// The first and last baselines of the nodes of an allocator, resolved after each layout.
+///
+/// Taffy 0.3 doesn't take baselines from measure functions, so the leaves' ascents are propagated up the tree here,
+/// and the items of the single-line flex rows which are aligned to the baseline are shifted to line up their first
+/// baselines. The shifts are laid out again as the top margins of the items, so that the rows grow to contain them,
+/// and the shifts which are left are added to the locations returned by `getLayout()`.
+#[derive(Debug, Default)]
+pub(crate) struct Baselines {
+    leaves: LeafBaselines,
+    first: HashMap<taffy::node::Node, f32>,
+    last: HashMap<taffy::node::Node, f32>,
+    shifts: HashMap<taffy::node::Node, f32>,
+}
+
+impl Baselines {
+    pub fn leaves(&self) -> LeafBaselines {
+        self.leaves.clone()
+    }
+
+    pub fn remove(&mut self, node: taffy::node::Node) {
+        self.leaves.lock().unwrap().remove(&node);
+        self.first.remove(&node);
+        self.last.remove(&node);
+        self.shifts.remove(&node);
+    }
+
+    /// The first and last baselines of a node from the top of its border box.
+    pub fn get(&self, node: taffy::node::Node) -> (Option<f32>, Option<f32>) {
+        (self.first.get(&node).copied(), self.last.get(&node).copied())
+    }
+
+    /// The vertical shift of a node from its location computed by taffy.
+    pub fn shift(&self, node: taffy::node::Node) -> f32 {
+        self.shifts.get(&node).copied().unwrap_or(0.0)
+    }
+
+    /// Resolves the baselines of the tree at `root` from its leaves, returns the items which are shifted.
+    pub fn resolve(&mut self, taffy: &taffy::Taffy, root: taffy::node::Node) -> Vec<taffy::node::Node> {
+        let leaves = self.leaves.lock().unwrap().clone();
+        let mut shifted = Vec::new();
+        self.resolve_node(taffy, &leaves, root, &mut shifted);
+        shifted
+    }
+
+    /// Resolves the baselines of the tree at `root` which is just computed, and computes its layout again when
+    /// items are shifted to line up their baselines. The shifted items are placed by their top margins in the second
+    /// layout, which grows the rows with an auto height and moves the siblings after them, then their styles are
+    /// restored.
+    pub fn relayout(
+        &mut self,
+        taffy: &mut taffy::Taffy,
+        root: taffy::node::Node,
+        available_space: Size<AvailableSpace>,
+    ) {
+        let shifted = self.resolve(taffy, root);
+        if shifted.is_empty() {
+            return;
+        }
+        let mut saved = Vec::with_capacity(shifted.len());
+        for item in shifted {
+            let parent = match LayoutTree::parent(taffy, item) {
+                Some(parent) => parent,
+                None => continue,
+            };
+            let width = taffy.layout(parent).unwrap().size.width;
+            let parent_style = taffy.style(parent).unwrap();
+            let content_top = resolve(parent_style.padding.top, width) + resolve(parent_style.border.top, width);
+            let y = taffy.layout(item).unwrap().location.y + self.shift(item);
+            let style = taffy.style(item).unwrap().clone();
+            let mut shifted_style = style.clone();
+            shifted_style.margin.top = LengthPercentageAuto::Points(y - content_top);
+            shifted_style.align_self = Some(AlignItems::FlexStart);
+            taffy.set_style(item, shifted_style).unwrap();
+            saved.push((item, style));
+        }
+        taffy.compute_layout(root, available_space).unwrap();
+        for (item, style) in saved {
+            taffy.set_style(item, style).unwrap();
+        }
+        self.resolve(taffy, root);
+    }
+
+    fn resolve_node(
+        &mut self,
+        taffy: &taffy::Taffy,
+        leaves: &HashMap<taffy::node::Node, LeafBaseline>,
+        node: taffy::node::Node,
+        shifted: &mut Vec<taffy::node::Node>,
+    ) {
+        self.first.remove(&node);
+        self.last.remove(&node);
+        let layout = taffy.layout(node).unwrap();
+        let style = taffy.style(node).unwrap();
+
+        let children = LayoutTree::children(taffy, node)
+            .copied()
+            .filter(|child| {
+                let style = taffy.style(*child).unwrap();
+                style.display != Display::None && style.position != Position::Absolute
+            })
+            .collect::<Vec<_>>();
+        if children.is_empty() {
+            if let Some(leaf) = leaves.get(&node) {
+                let width = layout.size.width;
+                let top = resolve(style.padding.top, width) + resolve(style.border.top, width);
+                let bottom = resolve(style.padding.bottom, width) + resolve(style.border.bottom, width);
+                let first = top + leaf.ascent;
+                self.first.insert(node, first);
+                self.last.insert(
+                    node,
+                    leaf.descent
+                        .map_or(first, |descent| layout.size.height - bottom - descent),
+                );
+            }
+            return;
+        }
+
+        for child in LayoutTree::children(taffy, node) {
+            self.shifts.remove(child);
+        }
+        for child in children.iter() {
+            self.resolve_node(taffy, leaves, *child, shifted);
+        }
+        let location = |child: taffy::node::Node| taffy.layout(child).unwrap().location.y;
+
+        // Lines up the first baselines of the items aligned to the baseline in a single-line row.
+        let mut aligned_baseline = None;
+        let is_row = style.display == Display::Flex
+            && matches!(style.flex_direction, FlexDirection::Row | FlexDirection::RowReverse)
+            && style.flex_wrap == FlexWrap::NoWrap;
+        if is_row {
+            let aligned = children
+                .iter()
+                .copied()
+                .filter(|child| {
+                    let align = taffy.style(*child).unwrap().align_self.or(style.align_items);
+                    align == Some(AlignItems::Baseline) && self.first.contains_key(child)
+                })
+                .collect::<Vec<_>>();
+            let target = aligned
+                .iter()
+                .map(|child| location(*child) + self.first[child])
+                .fold(None, |max: Option<f32>, baseline| Some(max.map_or(baseline, |max| max.max(baseline))));
+            if let Some(target) = target {
+                for child in aligned {
+                    let shift = target - location(child) - self.first[&child];
+                    if shift != 0.0 {
+                        self.shifts.insert(child, shift);
+                        shifted.push(child);
+                    }
+                }
+                aligned_baseline = Some(target);
+            }
+        }
+
+        let baseline_of = |this: &Self, child: taffy::node::Node, baselines: &HashMap<taffy::node::Node, f32>| {
+            baselines
+                .get(&child)
+                .map(|baseline| location(child) + this.shift(child) + baseline)
+        };
+        let first = aligned_baseline.or_else(|| {
+            children
+                .iter()
+                .find_map(|child| baseline_of(self, *child, &self.first))
+        });
+        let last = children
+            .iter()
+            .rev()
+            .find_map(|child| baseline_of(self, *child, &self.last));
+        if let Some(first) = first {
+            self.first.insert(node, first);
+        }
+        if let Some(last) = last {
+            self.last.insert(node, last);
+        }
+    }
+}
+
+fn resolve(length: LengthPercentage, basis: f32) -> f32 {
+    match length {
+        LengthPercentage::Points(points) => points,
+        LengthPercentage::Percent(percent) => percent * basis,
+    }
+}
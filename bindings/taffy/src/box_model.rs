@@ -0,0 +1,186 @@
+use taffy::geometry::{Point, Rect};
+use taffy::style::{Display, LengthPercentage, LengthPercentageAuto};
+use taffy::tree::LayoutTree;
+use wasm_bindgen::prelude::*;
+
+use crate::baseline::Baselines;
+use crate::positioned::PositionedLayouts;
+
+/// The content, padding, border and margin boxes of a node in the coordinates of its layout root, as in the
+/// `DOM.BoxModel` of the devtools protocol.
+///
+/// Each box is read as a quad of the corners `[x1, y1, x2, y2, x3, y3, x4, y4]` clockwise from the top-left. The
+/// auto margins are zero, as taffy 0.3 doesn't return the resolved margins.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct BoxModel {
+    /// The width of the border box.
+    #[wasm_bindgen(readonly)]
+    pub width: f32,
+
+    /// The height of the border box.
+    #[wasm_bindgen(readonly)]
+    pub height: f32,
+
+    content: Rect<f32>,
+    padding: Rect<f32>,
+    border: Rect<f32>,
+    margin: Rect<f32>,
+}
+
+#[wasm_bindgen]
+impl BoxModel {
+    #[wasm_bindgen(getter)]
+    pub fn content(&self) -> Vec<f32> {
+        quad(self.content)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn padding(&self) -> Vec<f32> {
+        quad(self.padding)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn border(&self) -> Vec<f32> {
+        quad(self.border)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn margin(&self) -> Vec<f32> {
+        quad(self.margin)
+    }
+}
+
+/// Locates the nodes of an allocator on its layout roots from the last layout, with the sticky and fixed shifts,
+/// the baseline shifts and the scroll offsets which are added when rendering.
+pub(crate) struct BoxGeometry<'a> {
+    pub taffy: &'a taffy::Taffy,
+    pub positioned: &'a PositionedLayouts,
+    pub baselines: &'a Baselines,
+}
+
+impl BoxGeometry<'_> {
+    /// The boxes of a node, the percentages of its paddings, borders and margins resolve against the width of its
+    /// parent.
+    pub fn box_model(&self, node: taffy::node::Node) -> BoxModel {
+        let layout = self.taffy.layout(node).unwrap();
+        let style = self.taffy.style(node).unwrap();
+        let basis = LayoutTree::parent(self.taffy, node)
+            .map_or(layout.size.width, |parent| self.taffy.layout(parent).unwrap().size.width);
+        let location = self.location(node);
+        let border = Rect {
+            left: location.x,
+            right: location.x + layout.size.width,
+            top: location.y,
+            bottom: location.y + layout.size.height,
+        };
+        let padding = inset(border, style.border.map(|length| resolve(length, basis)));
+        let content = inset(padding, style.padding.map(|length| resolve(length, basis)));
+        let margin = inset(border, style.margin.map(|length| -resolve_auto(length, basis)));
+
+        BoxModel {
+            width: layout.size.width,
+            height: layout.size.height,
+            content,
+            padding,
+            border,
+            margin,
+        }
+    }
+
+    /// Returns the deepest node of the tree at `root` whose border box contains the point, the later siblings are
+    /// painted over the earlier ones so they are hit first.
+    pub fn node_at(&self, root: taffy::node::Node, x: f32, y: f32) -> Option<taffy::node::Node> {
+        let origin = self.location(root);
+        self.hit_test(root, origin, x, y)
+    }
+
+    fn hit_test(
+        &self,
+        node: taffy::node::Node,
+        location: Point<f32>,
+        x: f32,
+        y: f32,
+    ) -> Option<taffy::node::Node> {
+        if self.taffy.style(node).unwrap().display == Display::None {
+            return None;
+        }
+        let scroll = self.positioned.scroll_offset(node);
+        let hit = LayoutTree::children(self.taffy, node).rev().find_map(|child| {
+            let offset = self.offset(*child);
+            let location = Point {
+                x: location.x + offset.x - scroll.x,
+                y: location.y + offset.y - scroll.y,
+            };
+            self.hit_test(*child, location, x, y)
+        });
+        if hit.is_some() {
+            return hit;
+        }
+        let size = self.taffy.layout(node).unwrap().size;
+        let inside = x >= location.x && x < location.x + size.width && y >= location.y && y < location.y + size.height;
+        inside.then_some(node)
+    }
+
+    /// The location of the border box of a node in its layout root.
+    fn location(&self, node: taffy::node::Node) -> Point<f32> {
+        let mut location = Point { x: 0.0, y: 0.0 };
+        let mut current = node;
+        while let Some(parent) = LayoutTree::parent(self.taffy, current) {
+            let offset = self.offset(current);
+            let scroll = self.positioned.scroll_offset(parent);
+            location.x += offset.x - scroll.x;
+            location.y += offset.y - scroll.y;
+            current = parent;
+        }
+        location
+    }
+
+    /// The location of a node in its parent as returned by `getLayout()`.
+    fn offset(&self, node: taffy::node::Node) -> Point<f32> {
+        let location = self.taffy.layout(node).unwrap().location;
+        let offset = self.positioned.offset(node);
+        Point {
+            x: location.x + offset.x,
+            y: location.y + offset.y + self.baselines.shift(node),
+        }
+    }
+}
+
+/// Shrinks a box by the widths of its edges.
+fn inset(rect: Rect<f32>, edges: Rect<f32>) -> Rect<f32> {
+    Rect {
+        left: rect.left + edges.left,
+        right: rect.right - edges.right,
+        top: rect.top + edges.top,
+        bottom: rect.bottom - edges.bottom,
+    }
+}
+
+fn quad(rect: Rect<f32>) -> Vec<f32> {
+    vec![
+        rect.left,
+        rect.top,
+        rect.right,
+        rect.top,
+        rect.right,
+        rect.bottom,
+        rect.left,
+        rect.bottom,
+    ]
+}
+
+fn resolve(length: LengthPercentage, basis: f32) -> f32 {
+    match length {
+        LengthPercentage::Points(points) => points,
+        LengthPercentage::Percent(percent) => percent * basis,
+    }
+}
+
+fn resolve_auto(length: LengthPercentageAuto, basis: f32) -> f32 {
+    match length {
+        LengthPercentageAuto::Points(points) => points,
+        LengthPercentageAuto::Percent(percent) => percent * basis,
+        LengthPercentageAuto::Auto => 0.0,
+    }
+}
@@ -0,0 +1,637 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use js_sys::{Float32Array, Uint32Array};
+use taffy::tree::LayoutTree;
+use wasm_bindgen::prelude::*;
+
+use crate::Allocator;
+use crate::style_source::{StyleMap, StyleValue};
+use crate::LayoutSimple;
+
+/// The operations of the command buffer passed to `Allocator.applyCommands()`, each one is the opcode followed by
+/// its operands in the `Uint32Array`.
+#[cfg_attr(feature = "napi", napi_derive::napi)]
+#[wasm_bindgen]
+#[repr(u8)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
+pub enum LayoutCommand {
+    /// `Create, handle`: creates a node with the default style under a handle from `reserveHandles()`, the node
+    /// is owned by the allocator until it's destroyed.
+    Create,
+    /// `Destroy, handle`: removes a node which was created by a command.
+    Destroy,
+    /// `ResetStyle, handle`: resets all the style properties.
+    ResetStyle,
+    /// `SetStyle, handle, property, unit`: sets a style property to the next value of the `Float32Array`.
+    SetStyle,
+    /// `AddChild, parent, child`: appends a child.
+    AddChild,
+    /// `InsertChild, parent, index, child`: inserts a child at an index.
+    InsertChild,
+    /// `RemoveChild, parent, child`: removes a child.
+    RemoveChild,
+    /// `MarkDirty, handle`: marks a node as dirty.
+    MarkDirty,
+    /// `SetIntrinsicSize, handle`: sets the intrinsic size to the next two values of the `Float32Array`, the width
+    /// and the height.
+    SetIntrinsicSize,
+    /// `SetScrollOffset, handle`: makes a node a scroll container scrolled by the next two values of the
+    /// `Float32Array`, the x and y offsets.
+    SetScrollOffset,
+}
+
+impl LayoutCommand {
+    fn from_u32(n: u32) -> Option<Self> {
+        Some(match n {
+            0 => LayoutCommand::Create,
+            1 => LayoutCommand::Destroy,
+            2 => LayoutCommand::ResetStyle,
+            3 => LayoutCommand::SetStyle,
+            4 => LayoutCommand::AddChild,
+            5 => LayoutCommand::InsertChild,
+            6 => LayoutCommand::RemoveChild,
+            7 => LayoutCommand::MarkDirty,
+            8 => LayoutCommand::SetIntrinsicSize,
+            9 => LayoutCommand::SetScrollOffset,
+            _ => return None,
+        })
+    }
+
+    fn operand_count(self) -> usize {
+        match self {
+            LayoutCommand::Create
+            | LayoutCommand::Destroy
+            | LayoutCommand::ResetStyle
+            | LayoutCommand::MarkDirty
+            | LayoutCommand::SetIntrinsicSize
+            | LayoutCommand::SetScrollOffset => 1,
+            LayoutCommand::AddChild | LayoutCommand::RemoveChild => 2,
+            LayoutCommand::SetStyle | LayoutCommand::InsertChild => 3,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            LayoutCommand::Create => "create",
+            LayoutCommand::Destroy => "destroy",
+            LayoutCommand::ResetStyle => "resetStyle",
+            LayoutCommand::SetStyle => "setStyle",
+            LayoutCommand::AddChild => "addChild",
+            LayoutCommand::InsertChild => "insertChild",
+            LayoutCommand::RemoveChild => "removeChild",
+            LayoutCommand::MarkDirty => "markDirty",
+            LayoutCommand::SetIntrinsicSize => "setIntrinsicSize",
+            LayoutCommand::SetScrollOffset => "setScrollOffset",
+        }
+    }
+}
+
+/// Declares the `StyleProperty` enum and the `STYLE_KEYS` table from one list, so the keys can't go out of sync
+/// with the properties.
+macro_rules! style_properties {
+    ($($property:ident => $key:literal,)*) => {
+        /// The style properties which can be set by the `SetStyle` command, `styleKeys()` returns their keys in the
+        /// `setStyle()` object.
+        #[cfg_attr(feature = "napi", napi_derive::napi)]
+        #[wasm_bindgen]
+        #[repr(u8)]
+        #[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+        #[derive(Debug, PartialEq, Eq)]
+        pub enum StyleProperty {
+            $($property,)*
+        }
+
+        pub(crate) const STYLE_KEYS: &[&str] = &[$($key,)*];
+    };
+}
+
+style_properties! {
+    Display => "display",
+    Position => "position",
+    FlexDirection => "flexDirection",
+    FlexWrap => "flexWrap",
+    AlignItems => "alignItems",
+    AlignSelf => "alignSelf",
+    AlignContent => "alignContent",
+    JustifyContent => "justifyContent",
+    JustifySelf => "justifySelf",
+    JustifyItems => "justifyItems",
+    GridAutoFlow => "gridAutoFlow",
+    InsetLeft => "insetLeft",
+    InsetRight => "insetRight",
+    InsetTop => "insetTop",
+    InsetBottom => "insetBottom",
+    MarginLeft => "marginLeft",
+    MarginRight => "marginRight",
+    MarginTop => "marginTop",
+    MarginBottom => "marginBottom",
+    PaddingLeft => "paddingLeft",
+    PaddingRight => "paddingRight",
+    PaddingTop => "paddingTop",
+    PaddingBottom => "paddingBottom",
+    BorderLeft => "borderLeft",
+    BorderRight => "borderRight",
+    BorderTop => "borderTop",
+    BorderBottom => "borderBottom",
+    FlexGrow => "flexGrow",
+    FlexShrink => "flexShrink",
+    FlexBasis => "flexBasis",
+    GapWidth => "gapWidth",
+    GapHeight => "gapHeight",
+    Width => "width",
+    Height => "height",
+    MinWidth => "minWidth",
+    MinHeight => "minHeight",
+    MaxWidth => "maxWidth",
+    MaxHeight => "maxHeight",
+    AspectRatio => "aspectRatio",
+    ObjectFit => "objectFit",
+    TableLayout => "tableLayout",
+    BorderSpacingWidth => "borderSpacingWidth",
+    BorderSpacingHeight => "borderSpacingHeight",
+    Colspan => "colspan",
+    Rowspan => "rowspan",
+    WritingMode => "writingMode",
+}
+
+/// Returns the keys of the style properties in the `setStyle()` object, indexed by `StyleProperty`.
+#[wasm_bindgen(js_name = styleKeys)]
+pub fn style_keys() -> Vec<JsValue> {
+    STYLE_KEYS.iter().map(|key| JsValue::from_str(key)).collect()
+}
+
+/// How the value of a `SetStyle` command is interpreted.
+#[cfg_attr(feature = "napi", napi_derive::napi)]
+#[wasm_bindgen]
+#[repr(u8)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
+pub enum StyleUnit {
+    /// A number, which is an enum index or points.
+    Number,
+    Percent,
+    Auto,
+    /// Removes the property, the value is ignored.
+    Unset,
+}
+
+impl StyleUnit {
+    fn from_u32(n: u32) -> Option<Self> {
+        Some(match n {
+            0 => StyleUnit::Number,
+            1 => StyleUnit::Percent,
+            2 => StyleUnit::Auto,
+            3 => StyleUnit::Unset,
+            _ => return None,
+        })
+    }
+}
+
+/// The handles which identify the nodes of an allocator in the command buffers.
+#[derive(Debug, Default)]
+pub(crate) struct Handles {
+    next: u32,
+    nodes: HashMap<u32, taffy::node::Node>,
+    handles: HashMap<taffy::node::Node, u32>,
+    /// The handles of the nodes created by commands, the others belong to `Node` objects.
+    owned: HashSet<u32>,
+}
+
+impl Handles {
+    /// Reserves `count` consecutive handles and returns the first one, the handles are never reused.
+    pub fn reserve(&mut self, count: u32) -> Result<u32, String> {
+        let first = self.next;
+        self.next = first
+            .checked_add(count)
+            .ok_or_else(|| "the handles of the allocator are exhausted".to_string())?;
+        Ok(first)
+    }
+
+    pub fn set(&mut self, handle: u32, node: taffy::node::Node, owned: bool) {
+        self.nodes.insert(handle, node);
+        self.handles.insert(node, handle);
+        if owned {
+            self.owned.insert(handle);
+        }
+    }
+
+    pub fn remove(&mut self, handle: u32) {
+        if let Some(node) = self.nodes.remove(&handle) {
+            self.handles.remove(&node);
+        }
+        self.owned.remove(&handle);
+    }
+}
+
+enum Command {
+    Create(u32),
+    Destroy(u32),
+    ResetStyle(u32),
+    SetStyle(u32, &'static str, StyleUnit, f32),
+    AddChild(u32, u32),
+    InsertChild(u32, usize, u32),
+    RemoveChild(u32, u32),
+    MarkDirty(u32),
+    SetIntrinsicSize(u32, f32, f32),
+    SetScrollOffset(u32, f32, f32),
+}
+
+/// The tree as it will be after the validated commands, which is only tracked for the nodes the commands touch. It
+/// checks a whole buffer before anything is applied.
+struct Simulation<'a> {
+    allocator: &'a Allocator,
+    created: HashSet<u32>,
+    destroyed: HashSet<u32>,
+    parents: HashMap<u32, Option<u32>>,
+    child_counts: HashMap<u32, usize>,
+}
+
+impl<'a> Simulation<'a> {
+    fn new(allocator: &'a Allocator) -> Self {
+        Self {
+            allocator,
+            created: HashSet::new(),
+            destroyed: HashSet::new(),
+            parents: HashMap::new(),
+            child_counts: HashMap::new(),
+        }
+    }
+
+    fn check(&self, handle: u32) -> Result<(), String> {
+        let exists = !self.destroyed.contains(&handle)
+            && (self.created.contains(&handle) || self.allocator.handles.borrow().nodes.contains_key(&handle));
+        if exists {
+            Ok(())
+        } else {
+            Err(format!("node {} does not exist", handle))
+        }
+    }
+
+    fn is_owned(&self, handle: u32) -> bool {
+        self.created.contains(&handle) || self.allocator.handles.borrow().owned.contains(&handle)
+    }
+
+    fn parent(&mut self, handle: u32) -> Option<u32> {
+        if let Some(parent) = self.parents.get(&handle) {
+            return *parent;
+        }
+        let handles = self.allocator.handles.borrow();
+        // The children of a destroyed node are detached from it, and a created node has no children in the tree.
+        let parent = handles
+            .nodes
+            .get(&handle)
+            .and_then(|node| self.allocator.taffy.borrow().parent(*node))
+            .and_then(|parent| handles.handles.get(&parent).copied())
+            .filter(|parent| !self.destroyed.contains(parent) && !self.created.contains(parent));
+        drop(handles);
+        self.parents.insert(handle, parent);
+        parent
+    }
+
+    fn child_count(&mut self, handle: u32) -> usize {
+        let allocator = self.allocator;
+        *self.child_counts.entry(handle).or_insert_with(|| {
+            allocator
+                .handles
+                .borrow()
+                .nodes
+                .get(&handle)
+                .map(|node| allocator.taffy.borrow().child_count(*node).unwrap())
+                .unwrap_or(0)
+        })
+    }
+
+    fn detach(&mut self, child: u32) {
+        if let Some(parent) = self.parent(child) {
+            let count = self.child_count(parent);
+            self.child_counts.insert(parent, count - 1);
+            self.parents.insert(child, None);
+        }
+    }
+
+    /// Checks a command against the tree and updates the tree, returns the error message of an invalid command.
+    fn apply(&mut self, command: &Command) -> Result<(), String> {
+        match *command {
+            Command::Create(handle) => {
+                if self.check(handle).is_ok() {
+                    return Err(format!("node {} already exists", handle));
+                }
+                if handle >= self.allocator.handles.borrow().next {
+                    return Err(format!("handle {} is not reserved", handle));
+                }
+                self.destroyed.remove(&handle);
+                self.created.insert(handle);
+                self.parents.insert(handle, None);
+                self.child_counts.insert(handle, 0);
+            }
+            Command::Destroy(handle) => {
+                self.check(handle)?;
+                if !self.is_owned(handle) {
+                    return Err(format!("node {} belongs to a Node object", handle));
+                }
+                self.detach(handle);
+                self.created.remove(&handle);
+                self.destroyed.insert(handle);
+                self.parents.remove(&handle);
+                self.child_counts.remove(&handle);
+                for parent in self.parents.values_mut() {
+                    if *parent == Some(handle) {
+                        *parent = None;
+                    }
+                }
+            }
+            Command::ResetStyle(handle)
+            | Command::SetStyle(handle, ..)
+            | Command::MarkDirty(handle)
+            | Command::SetIntrinsicSize(handle, ..)
+            | Command::SetScrollOffset(handle, ..) => self.check(handle)?,
+            Command::AddChild(parent, child) | Command::InsertChild(parent, _, child) => {
+                self.check(parent)?;
+                self.check(child)?;
+                let mut ancestor = Some(parent);
+                while let Some(handle) = ancestor {
+                    if handle == child {
+                        return Err(format!("node {} can't be a descendant of itself", child));
+                    }
+                    ancestor = self.parent(handle);
+                }
+                // A child which has a parent is moved, the same as `appendChild()` of the DOM.
+                let mut count = self.child_count(parent);
+                if self.parent(child) == Some(parent) {
+                    count -= 1;
+                }
+                if let Command::InsertChild(_, index, _) = *command {
+                    if index > count {
+                        return Err(format!(
+                            "index {} is out of the {} children of node {}",
+                            index, count, parent
+                        ));
+                    }
+                }
+                self.detach(child);
+                self.child_counts.insert(parent, count + 1);
+                self.parents.insert(child, Some(parent));
+            }
+            Command::RemoveChild(parent, child) => {
+                self.check(parent)?;
+                self.check(child)?;
+                if self.parent(child) != Some(parent) {
+                    return Err(format!("node {} is not a child of node {}", child, parent));
+                }
+                self.detach(child);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error message with the offset of the command in the buffer.
+type CommandError = (usize, String);
+
+/// Decodes the command buffer, returns the commands and the errors of the ones which can't be decoded.
+fn decode(ops: &[u32], values: &[f32]) -> (Vec<(usize, Command)>, Vec<CommandError>) {
+    let mut commands = Vec::new();
+    let mut errors = Vec::new();
+    let (mut i, mut value_index) = (0, 0);
+    while i < ops.len() {
+        let offset = i;
+        let kind = match LayoutCommand::from_u32(ops[i]) {
+            Some(kind) => kind,
+            None => {
+                // The operands can't be skipped without knowing the command, so nothing after it is applied.
+                errors.push((offset, format!("unknown command {}", ops[i])));
+                break;
+            }
+        };
+        let operands = match ops.get(i + 1..i + 1 + kind.operand_count()) {
+            Some(operands) => operands,
+            None => {
+                errors.push((offset, format!("{} is truncated", kind.name())));
+                break;
+            }
+        };
+        i += 1 + kind.operand_count();
+
+        let command = match kind {
+            LayoutCommand::Create => Command::Create(operands[0]),
+            LayoutCommand::Destroy => Command::Destroy(operands[0]),
+            LayoutCommand::ResetStyle => Command::ResetStyle(operands[0]),
+            LayoutCommand::SetStyle => {
+                let value = values.get(value_index).copied();
+                value_index += 1;
+                let key = STYLE_KEYS.get(operands[1] as usize);
+                let unit = StyleUnit::from_u32(operands[2]);
+                match (key, unit, value) {
+                    (Some(key), Some(unit), Some(value)) => Command::SetStyle(operands[0], key, unit, value),
+                    (None, ..) => {
+                        errors.push((offset, format!("unknown style property {}", operands[1])));
+                        continue;
+                    }
+                    (_, None, _) => {
+                        errors.push((offset, format!("unknown style unit {}", operands[2])));
+                        continue;
+                    }
+                    (_, _, None) => {
+                        errors.push((offset, "setStyle has no value".to_string()));
+                        continue;
+                    }
+                }
+            }
+            LayoutCommand::AddChild => Command::AddChild(operands[0], operands[1]),
+            LayoutCommand::InsertChild => {
+                Command::InsertChild(operands[0], operands[1] as usize, operands[2])
+            }
+            LayoutCommand::RemoveChild => Command::RemoveChild(operands[0], operands[1]),
+            LayoutCommand::MarkDirty => Command::MarkDirty(operands[0]),
+            LayoutCommand::SetIntrinsicSize | LayoutCommand::SetScrollOffset => {
+                let pair = values.get(value_index..value_index + 2);
+                value_index += 2;
+                match (kind, pair) {
+                    (LayoutCommand::SetIntrinsicSize, Some(size)) => {
+                        Command::SetIntrinsicSize(operands[0], size[0], size[1])
+                    }
+                    (_, Some(offset)) => Command::SetScrollOffset(operands[0], offset[0], offset[1]),
+                    (_, None) => {
+                        errors.push((offset, format!("{} has no values", kind.name())));
+                        continue;
+                    }
+                }
+            }
+        };
+        commands.push((offset, command));
+    }
+    (commands, errors)
+}
+
+fn command_name(command: &Command) -> &'static str {
+    match command {
+        Command::Create(..) => LayoutCommand::Create.name(),
+        Command::Destroy(..) => LayoutCommand::Destroy.name(),
+        Command::ResetStyle(..) => LayoutCommand::ResetStyle.name(),
+        Command::SetStyle(..) => LayoutCommand::SetStyle.name(),
+        Command::AddChild(..) => LayoutCommand::AddChild.name(),
+        Command::InsertChild(..) => LayoutCommand::InsertChild.name(),
+        Command::RemoveChild(..) => LayoutCommand::RemoveChild.name(),
+        Command::MarkDirty(..) => LayoutCommand::MarkDirty.name(),
+        Command::SetIntrinsicSize(..) => LayoutCommand::SetIntrinsicSize.name(),
+        Command::SetScrollOffset(..) => LayoutCommand::SetScrollOffset.name(),
+    }
+}
+
+impl Allocator {
+    pub(crate) fn command_node(&self, handle: u32) -> Result<taffy::node::Node, String> {
+        let node = self.handles.borrow().nodes.get(&handle).copied();
+        node.ok_or_else(|| format!("node {} does not exist", handle))
+    }
+
+    /// Detaches a child from its parent, so that it can be added to another one.
+    fn detach_child(&self, child: taffy::node::Node) {
+        let mut taffy = self.taffy.borrow_mut();
+        if let Some(parent) = taffy.parent(child) {
+            taffy.remove_child(parent, child).unwrap();
+        }
+    }
+
+    /// Applies a command which was checked by a `Simulation`, the style changes are collected in `styles` to be
+    /// applied once per node.
+    fn apply_command(&self, command: &Command, styles: &mut Vec<(u32, StyleMap)>) -> Result<(), String> {
+        match *command {
+            Command::Create(handle) => {
+                let node = self.insert_node(&StyleMap::default());
+                self.handles.borrow_mut().set(handle, node, true);
+            }
+            Command::Destroy(handle) => {
+                let node = self.command_node(handle)?;
+                self.handles.borrow_mut().remove(handle);
+                self.remove_node(node);
+                styles.retain(|(h, _)| *h != handle);
+            }
+            Command::ResetStyle(handle) => {
+                self.command_node(handle)?;
+                styles.retain(|(h, _)| *h != handle);
+                styles.push((handle, StyleMap::default()));
+            }
+            Command::SetStyle(handle, key, unit, value) => {
+                let node = self.command_node(handle)?;
+                let index = match styles.iter().position(|(h, _)| *h == handle) {
+                    Some(index) => index,
+                    None => {
+                        styles.push((handle, self.nodes.borrow()[&node].values.clone()));
+                        styles.len() - 1
+                    }
+                };
+                let style = &mut styles[index].1;
+                match unit {
+                    StyleUnit::Number => style.set(key, StyleValue::Number(value as f64)),
+                    StyleUnit::Percent => style.set(key, StyleValue::String(format!("{}%", value))),
+                    StyleUnit::Auto => style.set(key, StyleValue::String("auto".to_string())),
+                    StyleUnit::Unset => style.remove(key),
+                }
+            }
+            Command::AddChild(parent, child) => {
+                let parent_node = self.command_node(parent)?;
+                let child_node = self.command_node(child)?;
+                self.detach_child(child_node);
+                self.taffy.borrow_mut().add_child(parent_node, child_node).unwrap();
+            }
+            Command::InsertChild(parent, index, child) => {
+                let parent_node = self.command_node(parent)?;
+                let child_node = self.command_node(child)?;
+                self.detach_child(child_node);
+                let mut taffy = self.taffy.borrow_mut();
+                let mut children = taffy.children(parent_node).unwrap();
+                children.insert(index, child_node);
+                taffy.set_children(parent_node, &children).unwrap();
+            }
+            Command::RemoveChild(parent, child) => {
+                let parent_node = self.command_node(parent)?;
+                let child_node = self.command_node(child)?;
+                self.taffy.borrow_mut().remove_child(parent_node, child_node).unwrap();
+            }
+            Command::MarkDirty(handle) => {
+                let node = self.command_node(handle)?;
+                self.taffy.borrow_mut().mark_dirty(node).unwrap();
+            }
+            Command::SetIntrinsicSize(handle, width, height) => {
+                let node = self.command_node(handle)?;
+                self.set_intrinsic_size(node, width, height);
+            }
+            Command::SetScrollOffset(handle, x, y) => {
+                let node = self.command_node(handle)?;
+                self.set_scroll_offset(node, x, y);
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies a command buffer atomically: the whole buffer is checked by a `Simulation` first, and when any command
+    /// is invalid nothing is applied. Returns the errors of all the invalid commands, which is empty when the buffer
+    /// was applied. It's shared by the wasm and the Node-API bindings, which apply the collected styles with
+    /// `apply_style`.
+    pub(crate) fn apply_command_buffer(
+        &self,
+        ops: &[u32],
+        values: &[f32],
+        apply_style: impl Fn(taffy::node::Node, &StyleMap),
+    ) -> Vec<String> {
+        let (commands, mut errors) = decode(ops, values);
+        let mut simulation = Simulation::new(self);
+        for (offset, command) in commands.iter() {
+            if let Err(error) = simulation.apply(command) {
+                errors.push((*offset, format!("{} {}", command_name(command), error)));
+            }
+        }
+        if errors.is_empty() {
+            // The style changes are parsed once per node after the tree changes.
+            let mut styles = Vec::new();
+            for (offset, command) in commands.iter() {
+                if let Err(error) = self.apply_command(command, &mut styles) {
+                    errors.push((*offset, format!("{} {}", command_name(command), error)));
+                }
+            }
+            for (handle, style) in styles {
+                let node = self.handles.borrow().nodes[&handle];
+                apply_style(node, &style);
+            }
+        }
+        errors.sort_by_key(|(offset, _)| *offset);
+        errors
+            .into_iter()
+            .map(|(offset, error)| format!("at {}: {}", offset, error))
+            .collect()
+    }
+}
+
+#[wasm_bindgen]
+impl Allocator {
+    /// Reserves `count` consecutive handles for the `Create` commands and returns the first one.
+    #[wasm_bindgen(js_name = reserveHandles)]
+    pub fn reserve_handles(&self, count: u32) -> Result<u32, JsError> {
+        self.handles.borrow_mut().reserve(count).map_err(|err| JsError::new(&err))
+    }
+
+    /// Applies a buffer of `LayoutCommand`s atomically: the opcodes and operands are in `ops`, and the values of the
+    /// `SetStyle`, `SetIntrinsicSize` and `SetScrollOffset` commands in `values` in the same order. The whole buffer
+    /// is checked against the tree first, and if any command is invalid none of them is applied. Returns the error
+    /// messages of all the invalid commands with their offsets in `ops`, which is empty when the buffer was applied.
+    #[wasm_bindgen(js_name = applyCommands)]
+    pub fn apply_commands(&self, ops: &Uint32Array, values: &Float32Array) -> Vec<JsValue> {
+        self.apply_command_buffer(&ops.to_vec(), &values.to_vec(), |node, style| {
+            self.set_node_style(node, &style.to_js_value());
+        })
+        .into_iter()
+        .map(JsValue::from)
+        .collect()
+    }
+
+    /// Returns the layout of a node by its handle, which is how the layout of a node created by a command is read.
+    #[wasm_bindgen(js_name = getLayout)]
+    pub fn get_layout(&self, handle: u32) -> Result<LayoutSimple, JsError> {
+        match self.command_node(handle) {
+            Ok(node) => Ok(LayoutSimple::new(self, node)),
+            Err(error) => Err(JsError::new(&error)),
+        }
+    }
+}
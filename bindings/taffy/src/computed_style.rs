@@ -0,0 +1,171 @@
+use js_sys::Array;
+use js_sys::Object;
+use js_sys::Reflect;
+use taffy::style::{
+    AlignContent, AlignItems, Dimension, Display, FlexDirection, FlexWrap, GridAutoFlow,
+    LengthPercentage, LengthPercentageAuto, Position, Style,
+};
+use wasm_bindgen::prelude::*;
+
+/// Serializes the style that taffy uses back to an object of CSS-like strings, the keys are the same as the ones
+/// accepted by `Node.setStyle()` so that both can be compared side by side.
+pub(crate) fn serialize_style(style: &Style, coerced: &[String]) -> JsValue {
+    let obj = Object::new();
+    for (key, value) in style_entries(style) {
+        Reflect::set(&obj, &key.into(), &value.into()).unwrap();
+    }
+    let coerced_properties = coerced.iter().map(JsValue::from).collect::<Array>();
+    Reflect::set(&obj, &"coercedProperties".into(), &coerced_properties).unwrap();
+    obj.into()
+}
+
+/// The properties of `serialize_style()` without the `coercedProperties`, which the Node-API binding serializes
+/// the same way.
+pub(crate) fn style_entries(style: &Style) -> Vec<(&'static str, String)> {
+    let mut entries = Vec::new();
+    let mut set = |key: &'static str, value: String| entries.push((key, value));
+
+    set("display", display_to_css(style.display).to_string());
+    set("position", position_to_css(style.position).to_string());
+    set("flexDirection", flex_direction_to_css(style.flex_direction).to_string());
+    set("flexWrap", flex_wrap_to_css(style.flex_wrap).to_string());
+    set("alignItems", align_items_to_css(style.align_items));
+    set("alignSelf", align_items_to_css(style.align_self));
+    set("alignContent", align_content_to_css(style.align_content));
+    set("justifyContent", align_content_to_css(style.justify_content));
+    set("justifySelf", align_items_to_css(style.justify_self));
+    set("justifyItems", align_items_to_css(style.justify_items));
+    set("gridAutoFlow", grid_auto_flow_to_css(style.grid_auto_flow).to_string());
+
+    set("insetLeft", length_percentage_auto_to_css(style.inset.left));
+    set("insetRight", length_percentage_auto_to_css(style.inset.right));
+    set("insetTop", length_percentage_auto_to_css(style.inset.top));
+    set("insetBottom", length_percentage_auto_to_css(style.inset.bottom));
+    set("marginLeft", length_percentage_auto_to_css(style.margin.left));
+    set("marginRight", length_percentage_auto_to_css(style.margin.right));
+    set("marginTop", length_percentage_auto_to_css(style.margin.top));
+    set("marginBottom", length_percentage_auto_to_css(style.margin.bottom));
+    set("paddingLeft", length_percentage_to_css(style.padding.left));
+    set("paddingRight", length_percentage_to_css(style.padding.right));
+    set("paddingTop", length_percentage_to_css(style.padding.top));
+    set("paddingBottom", length_percentage_to_css(style.padding.bottom));
+    set("borderLeft", length_percentage_to_css(style.border.left));
+    set("borderRight", length_percentage_to_css(style.border.right));
+    set("borderTop", length_percentage_to_css(style.border.top));
+    set("borderBottom", length_percentage_to_css(style.border.bottom));
+
+    set("flexGrow", style.flex_grow.to_string());
+    set("flexShrink", style.flex_shrink.to_string());
+    set("flexBasis", dimension_to_css(style.flex_basis));
+    set("gapWidth", length_percentage_to_css(style.gap.width));
+    set("gapHeight", length_percentage_to_css(style.gap.height));
+
+    set("width", dimension_to_css(style.size.width));
+    set("height", dimension_to_css(style.size.height));
+    set("minWidth", dimension_to_css(style.min_size.width));
+    set("minHeight", dimension_to_css(style.min_size.height));
+    set("maxWidth", dimension_to_css(style.max_size.width));
+    set("maxHeight", dimension_to_css(style.max_size.height));
+    set(
+        "aspectRatio",
+        style
+            .aspect_ratio
+            .map(|ratio| ratio.to_string())
+            .unwrap_or_else(|| "auto".to_string()),
+    );
+    entries
+}
+
+fn display_to_css(display: Display) -> &'static str {
+    match display {
+        Display::Flex => "flex",
+        Display::Grid => "grid",
+        Display::None => "none",
+    }
+}
+
+fn position_to_css(position: Position) -> &'static str {
+    match position {
+        Position::Relative => "relative",
+        Position::Absolute => "absolute",
+    }
+}
+
+fn flex_direction_to_css(direction: FlexDirection) -> &'static str {
+    match direction {
+        FlexDirection::Row => "row",
+        FlexDirection::Column => "column",
+        FlexDirection::RowReverse => "row-reverse",
+        FlexDirection::ColumnReverse => "column-reverse",
+    }
+}
+
+fn flex_wrap_to_css(wrap: FlexWrap) -> &'static str {
+    match wrap {
+        FlexWrap::NoWrap => "nowrap",
+        FlexWrap::Wrap => "wrap",
+        FlexWrap::WrapReverse => "wrap-reverse",
+    }
+}
+
+fn grid_auto_flow_to_css(flow: GridAutoFlow) -> &'static str {
+    match flow {
+        GridAutoFlow::Row => "row",
+        GridAutoFlow::Column => "column",
+        GridAutoFlow::RowDense => "row dense",
+        GridAutoFlow::ColumnDense => "column dense",
+    }
+}
+
+fn align_items_to_css(align: Option<AlignItems>) -> String {
+    match align {
+        Some(AlignItems::FlexStart) => "flex-start",
+        Some(AlignItems::FlexEnd) => "flex-end",
+        Some(AlignItems::Start) => "start",
+        Some(AlignItems::End) => "end",
+        Some(AlignItems::Center) => "center",
+        Some(AlignItems::Baseline) => "baseline",
+        Some(AlignItems::Stretch) => "stretch",
+        None => "normal",
+    }
+    .to_string()
+}
+
+fn align_content_to_css(align: Option<AlignContent>) -> String {
+    match align {
+        Some(AlignContent::FlexStart) => "flex-start",
+        Some(AlignContent::FlexEnd) => "flex-end",
+        Some(AlignContent::Start) => "start",
+        Some(AlignContent::End) => "end",
+        Some(AlignContent::Center) => "center",
+        Some(AlignContent::Stretch) => "stretch",
+        Some(AlignContent::SpaceBetween) => "space-between",
+        Some(AlignContent::SpaceEvenly) => "space-evenly",
+        Some(AlignContent::SpaceAround) => "space-around",
+        None => "normal",
+    }
+    .to_string()
+}
+
+fn dimension_to_css(dimension: Dimension) -> String {
+    match dimension {
+        Dimension::Points(points) => format!("{}px", points),
+        Dimension::Percent(percent) => format!("{}%", percent * 100.0),
+        Dimension::Auto => "auto".to_string(),
+    }
+}
+
+fn length_percentage_to_css(length: LengthPercentage) -> String {
+    match length {
+        LengthPercentage::Points(points) => format!("{}px", points),
+        LengthPercentage::Percent(percent) => format!("{}%", percent * 100.0),
+    }
+}
+
+fn length_percentage_auto_to_css(length: LengthPercentageAuto) -> String {
+    match length {
+        LengthPercentageAuto::Points(points) => format!("{}px", points),
+        LengthPercentageAuto::Percent(percent) => format!("{}%", percent * 100.0),
+        LengthPercentageAuto::Auto => "auto".to_string(),
+    }
+}
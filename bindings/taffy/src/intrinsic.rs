@@ -0,0 +1,217 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use taffy::geometry::Size;
+use taffy::node::MeasureFunc;
+use taffy::style::{AvailableSpace, Dimension, Style};
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "napi", napi_derive::napi)]
+#[wasm_bindgen]
+#[repr(u8)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
+pub enum ObjectFit {
+    Fill,
+    Contain,
+    Cover,
+    None,
+    ScaleDown,
+}
+
+impl From<i32> for ObjectFit {
+    fn from(n: i32) -> Self {
+        match n {
+            0 => ObjectFit::Fill,
+            1 => ObjectFit::Contain,
+            2 => ObjectFit::Cover,
+            3 => ObjectFit::None,
+            4 => ObjectFit::ScaleDown,
+            _ => ObjectFit::Fill,
+        }
+    }
+}
+
+/// The natural size of a replaced element such as an image, and the parts of its style which size it.
+///
+/// It's shared with the measure function of the node and updated on `setStyle()`, so that the leaf is sized in
+/// the same `computeLayout` as the rest of the tree without calling back into JS.
+#[derive(Clone, Debug)]
+pub(crate) struct IntrinsicSizing {
+    natural: Size<f32>,
+    aspect_ratio: Option<f32>,
+    size: Size<Dimension>,
+    min_size: Size<Dimension>,
+    max_size: Size<Dimension>,
+}
+
+impl IntrinsicSizing {
+    pub fn new(natural: Size<f32>, style: &Style) -> Self {
+        Self {
+            natural,
+            aspect_ratio: style.aspect_ratio,
+            size: style.size,
+            min_size: style.min_size,
+            max_size: style.max_size,
+        }
+    }
+
+    pub fn natural(&self) -> Size<f32> {
+        self.natural
+    }
+
+    pub fn set_style(&mut self, style: &Style) {
+        self.aspect_ratio = style.aspect_ratio;
+        self.size = style.size;
+        self.min_size = style.min_size;
+        self.max_size = style.max_size;
+    }
+
+    /// The width / height ratio, the `aspectRatio` style takes precedence over the natural ratio.
+    fn ratio(&self) -> Option<f32> {
+        self.aspect_ratio.filter(|ratio| *ratio > 0.0).or_else(|| {
+            if self.natural.width > 0.0 && self.natural.height > 0.0 {
+                Some(self.natural.width / self.natural.height)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Sizes the box like a CSS replaced element: a specified dimension gives the other through the ratio, and when
+    /// both are auto the natural size is constrained by the min and max sizes while keeping the ratio.
+    fn measure(&self, known: Size<Option<f32>>, available: Size<AvailableSpace>) -> Size<f32> {
+        let width = known.width.or_else(|| resolve(self.size.width, available.width));
+        let height = known.height.or_else(|| resolve(self.size.height, available.height));
+        let min = Size {
+            width: resolve(self.min_size.width, available.width).unwrap_or(0.0),
+            height: resolve(self.min_size.height, available.height).unwrap_or(0.0),
+        };
+        let max = Size {
+            width: resolve(self.max_size.width, available.width)
+                .unwrap_or(f32::INFINITY)
+                .max(min.width),
+            height: resolve(self.max_size.height, available.height)
+                .unwrap_or(f32::INFINITY)
+                .max(min.height),
+        };
+
+        match (width, height, self.ratio()) {
+            (Some(width), Some(height), _) => Size { width, height },
+            (Some(width), None, Some(ratio)) => Size {
+                width,
+                height: (width / ratio).clamp(min.height, max.height),
+            },
+            (None, Some(height), Some(ratio)) => Size {
+                width: (height * ratio).clamp(min.width, max.width),
+                height,
+            },
+            (Some(width), None, None) => Size {
+                width,
+                height: self.natural.height.clamp(min.height, max.height),
+            },
+            (None, Some(height), None) => Size {
+                width: self.natural.width.clamp(min.width, max.width),
+                height,
+            },
+            (None, None, Some(ratio)) => {
+                let natural = Size {
+                    width: self.natural.width,
+                    height: self.natural.width / ratio,
+                };
+                constrain(natural, min, max)
+            }
+            (None, None, None) => Size {
+                width: self.natural.width.clamp(min.width, max.width),
+                height: self.natural.height.clamp(min.height, max.height),
+            },
+        }
+    }
+
+    pub fn to_measure_func(sizing: Arc<Mutex<IntrinsicSizing>>) -> MeasureFunc {
+        MeasureFunc::Boxed(Box::new(
+            move |known_dimensions: Size<Option<f32>>, available_space: Size<AvailableSpace>| {
+                sizing.lock().unwrap().measure(known_dimensions, available_space)
+            },
+        ))
+    }
+}
+
+/// Resolves a size style against the available space, which taffy sets to the resolved size of the node for the
+/// dimensions it knows.
+fn resolve(dimension: Dimension, available: AvailableSpace) -> Option<f32> {
+    match (dimension, available) {
+        (Dimension::Points(points), _) => Some(points),
+        (Dimension::Percent(_), AvailableSpace::Definite(value)) => Some(value),
+        _ => None,
+    }
+}
+
+/// Applies the min and max sizes to a natural size while keeping its ratio where possible, this is the
+/// constraint table of CSS 2.1 §10.4.
+fn constrain(natural: Size<f32>, min: Size<f32>, max: Size<f32>) -> Size<f32> {
+    let Size {
+        width: w,
+        height: h,
+    } = natural;
+    if w <= 0.0 || h <= 0.0 {
+        return Size {
+            width: w.clamp(min.width, max.width),
+            height: h.clamp(min.height, max.height),
+        };
+    }
+
+    let (width, height) = if w > max.width && h > max.height {
+        if max.width / w <= max.height / h {
+            (max.width, (max.width * h / w).max(min.height))
+        } else {
+            ((max.height * w / h).max(min.width), max.height)
+        }
+    } else if w < min.width && h < min.height {
+        if min.width / w <= min.height / h {
+            ((min.height * w / h).min(max.width), min.height)
+        } else {
+            (min.width, (min.width * h / w).min(max.height))
+        }
+    } else if w < min.width && h > max.height {
+        (min.width, max.height)
+    } else if w > max.width && h < min.height {
+        (max.width, min.height)
+    } else if w > max.width {
+        (max.width, (max.width * h / w).max(min.height))
+    } else if w < min.width {
+        (min.width, (min.width * h / w).min(max.height))
+    } else if h > max.height {
+        ((max.height * w / h).max(min.width), max.height)
+    } else if h < min.height {
+        ((min.height * w / h).min(max.width), min.height)
+    } else {
+        (w, h)
+    };
+    Size { width, height }
+}
+
+/// Returns the `(x, y, width, height)` where the content of the natural size is drawn in a box of the `content`
+/// size, centered like the default `object-position`.
+pub(crate) fn object_fit_rect(fit: ObjectFit, natural: Size<f32>, content: Size<f32>) -> (f32, f32, f32, f32) {
+    if natural.width <= 0.0 || natural.height <= 0.0 {
+        return (0.0, 0.0, content.width, content.height);
+    }
+
+    let contain = (content.width / natural.width).min(content.height / natural.height);
+    let scale = match fit {
+        ObjectFit::Fill => return (0.0, 0.0, content.width, content.height),
+        ObjectFit::Contain => contain,
+        ObjectFit::Cover => (content.width / natural.width).max(content.height / natural.height),
+        ObjectFit::None => 1.0,
+        ObjectFit::ScaleDown => contain.min(1.0),
+    };
+    let width = natural.width * scale;
+    let height = natural.height * scale;
+    (
+        (content.width - width) / 2.0,
+        (content.height - height) / 2.0,
+        width,
+        height,
+    )
+}
@@ -1,19 +1,60 @@
 #![allow(non_snake_case)]
 
+mod baseline;
+mod box_model;
+mod commands;
+mod computed_style;
+mod intrinsic;
+mod measure;
+#[cfg(feature = "napi")]
+mod node_api;
+mod positioned;
+mod stats;
+mod style_source;
+mod table;
+mod transition;
 mod utils;
+mod virtual_list;
+mod writing_mode;
 
+use std::cell::Cell;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
 
 use js_sys::Function;
-use js_sys::Reflect;
 use taffy::style_helpers::TaffyZero;
 use taffy::tree::LayoutTree;
 use wasm_bindgen::prelude::*;
-
+use wasm_bindgen::JsCast;
+
+use commands::Handles;
+use baseline::Baselines;
+use box_model::BoxGeometry;
+use intrinsic::IntrinsicSizing;
+use measure::MeasureCallback;
+use positioned::{PositionedItem, PositionedLayouts};
+use stats::LayoutRecorder;
+use style_source::{RecordingSource, StyleMap, StyleSource, StyleValue};
+use table::{TableItem, TableLayouts};
+use transition::LayoutHistory;
+use virtual_list::RowPlacement;
+use writing_mode::WritingModes;
+pub use box_model::BoxModel;
+pub use commands::{style_keys, LayoutCommand, StyleProperty, StyleUnit};
+pub use intrinsic::ObjectFit;
+pub use stats::LayoutStats;
+pub use transition::Easing;
+pub use virtual_list::{VirtualList, VirtualRange};
+pub use writing_mode::WritingMode;
+
+#[cfg_attr(feature = "napi", napi_derive::napi)]
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
 pub enum AlignItems {
     FlexStart,
     FlexEnd,
@@ -24,9 +65,9 @@ pub enum AlignItems {
     Stretch,
 }
 
-impl Into<taffy::style::AlignItems> for AlignItems {
-    fn into(self) -> taffy::style::AlignItems {
-        match self {
+impl From<AlignItems> for taffy::style::AlignItems {
+    fn from(value: AlignItems) -> Self {
+        match value {
             AlignItems::FlexStart => taffy::style::AlignItems::FlexStart,
             AlignItems::FlexEnd => taffy::style::AlignItems::FlexEnd,
             AlignItems::Start => taffy::style::AlignItems::Start,
@@ -53,9 +94,11 @@ impl From<i32> for AlignItems {
     }
 }
 
+#[cfg_attr(feature = "napi", napi_derive::napi)]
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
 pub enum JustifyItems {
     FlexStart,
     FlexEnd,
@@ -66,9 +109,9 @@ pub enum JustifyItems {
     Stretch,
 }
 
-impl Into<taffy::style::JustifyItems> for JustifyItems {
-    fn into(self) -> taffy::style::JustifyItems {
-        match self {
+impl From<JustifyItems> for taffy::style::JustifyItems {
+    fn from(value: JustifyItems) -> Self {
+        match value {
             JustifyItems::FlexStart => taffy::style::JustifyItems::FlexStart,
             JustifyItems::FlexEnd => taffy::style::JustifyItems::FlexEnd,
             JustifyItems::Start => taffy::style::JustifyItems::Start,
@@ -95,9 +138,11 @@ impl From<i32> for JustifyItems {
     }
 }
 
+#[cfg_attr(feature = "napi", napi_derive::napi)]
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
 pub enum JustifySelf {
     FlexStart,
     FlexEnd,
@@ -108,9 +153,9 @@ pub enum JustifySelf {
     Stretch,
 }
 
-impl Into<taffy::style::JustifySelf> for JustifySelf {
-    fn into(self) -> taffy::style::JustifySelf {
-        match self {
+impl From<JustifySelf> for taffy::style::JustifySelf {
+    fn from(value: JustifySelf) -> Self {
+        match value {
             JustifySelf::FlexStart => taffy::style::JustifySelf::FlexStart,
             JustifySelf::FlexEnd => taffy::style::JustifySelf::FlexEnd,
             JustifySelf::Start => taffy::style::JustifySelf::Start,
@@ -137,9 +182,11 @@ impl From<i32> for JustifySelf {
     }
 }
 
+#[cfg_attr(feature = "napi", napi_derive::napi)]
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
 pub enum AlignSelf {
     FlexStart,
     FlexEnd,
@@ -150,9 +197,9 @@ pub enum AlignSelf {
     Stretch,
 }
 
-impl Into<taffy::style::AlignSelf> for AlignSelf {
-    fn into(self) -> taffy::style::AlignSelf {
-        match self {
+impl From<AlignSelf> for taffy::style::AlignSelf {
+    fn from(value: AlignSelf) -> Self {
+        match value {
             AlignSelf::FlexStart => taffy::style::AlignSelf::FlexStart,
             AlignSelf::FlexEnd => taffy::style::AlignSelf::FlexEnd,
             AlignSelf::Start => taffy::style::AlignSelf::Start,
@@ -179,9 +226,11 @@ impl From<i32> for AlignSelf {
     }
 }
 
+#[cfg_attr(feature = "napi", napi_derive::napi)]
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
 pub enum AlignContent {
     FlexStart,
     FlexEnd,
@@ -193,9 +242,9 @@ pub enum AlignContent {
     SpaceAround,
 }
 
-impl Into<taffy::style::AlignContent> for AlignContent {
-    fn into(self) -> taffy::style::AlignContent {
-        match self {
+impl From<AlignContent> for taffy::style::AlignContent {
+    fn from(value: AlignContent) -> Self {
+        match value {
             AlignContent::FlexStart => taffy::style::AlignContent::FlexStart,
             AlignContent::FlexEnd => taffy::style::AlignContent::FlexEnd,
             AlignContent::Start => taffy::style::AlignContent::FlexStart,
@@ -224,21 +273,28 @@ impl From<i32> for AlignContent {
     }
 }
 
+#[cfg_attr(feature = "napi", napi_derive::napi)]
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
 pub enum Display {
     Flex,
     Grid,
     None,
+    Table,
+    TableRow,
+    TableCell,
 }
 
-impl Into<taffy::style::Display> for Display {
-    fn into(self) -> taffy::style::Display {
-        match self {
+/// The table boxes are flex boxes in taffy, which the table layout sizes and positions.
+impl From<Display> for taffy::style::Display {
+    fn from(value: Display) -> Self {
+        match value {
             Display::Flex => taffy::style::Display::Flex,
             Display::Grid => taffy::style::Display::Grid,
             Display::None => taffy::style::Display::None,
+            Display::Table | Display::TableRow | Display::TableCell => taffy::style::Display::Flex,
         }
     }
 }
@@ -249,14 +305,39 @@ impl From<i32> for Display {
             0 => Display::Flex,
             1 => Display::Grid,
             2 => Display::None,
+            3 => Display::Table,
+            4 => Display::TableRow,
+            5 => Display::TableCell,
             _ => Display::Flex,
         }
     }
 }
 
+#[cfg_attr(feature = "napi", napi_derive::napi)]
+#[wasm_bindgen]
+#[repr(u8)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
+pub enum TableLayout {
+    Auto,
+    Fixed,
+}
+
+impl From<i32> for TableLayout {
+    fn from(n: i32) -> Self {
+        match n {
+            0 => TableLayout::Auto,
+            1 => TableLayout::Fixed,
+            _ => TableLayout::Auto,
+        }
+    }
+}
+
+#[cfg_attr(feature = "napi", napi_derive::napi)]
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
 pub enum FlexDirection {
     Row,
     Column,
@@ -264,9 +345,9 @@ pub enum FlexDirection {
     ColumnReverse,
 }
 
-impl Into<taffy::style::FlexDirection> for FlexDirection {
-    fn into(self) -> taffy::style::FlexDirection {
-        match self {
+impl From<FlexDirection> for taffy::style::FlexDirection {
+    fn from(value: FlexDirection) -> Self {
+        match value {
             FlexDirection::Row => taffy::style::FlexDirection::Row,
             FlexDirection::Column => taffy::style::FlexDirection::Column,
             FlexDirection::RowReverse => taffy::style::FlexDirection::RowReverse,
@@ -287,9 +368,11 @@ impl From<i32> for FlexDirection {
     }
 }
 
+#[cfg_attr(feature = "napi", napi_derive::napi)]
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
 pub enum GridAutoFlow {
     Row,
     Column,
@@ -297,9 +380,9 @@ pub enum GridAutoFlow {
     ColumnDense,
 }
 
-impl Into<taffy::style::GridAutoFlow> for GridAutoFlow {
-    fn into(self) -> taffy::style::GridAutoFlow {
-        match self {
+impl From<GridAutoFlow> for taffy::style::GridAutoFlow {
+    fn from(value: GridAutoFlow) -> Self {
+        match value {
             GridAutoFlow::Row => taffy::style::GridAutoFlow::Row,
             GridAutoFlow::Column => taffy::style::GridAutoFlow::Column,
             GridAutoFlow::RowDense => taffy::style::GridAutoFlow::RowDense,
@@ -320,9 +403,11 @@ impl From<i32> for GridAutoFlow {
     }
 }
 
+#[cfg_attr(feature = "napi", napi_derive::napi)]
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
 pub enum JustifyContent {
     FlexStart,
     FlexEnd,
@@ -334,9 +419,9 @@ pub enum JustifyContent {
     SpaceEvenly,
 }
 
-impl Into<taffy::style::JustifyContent> for JustifyContent {
-    fn into(self) -> taffy::style::JustifyContent {
-        match self {
+impl From<JustifyContent> for taffy::style::JustifyContent {
+    fn from(value: JustifyContent) -> Self {
+        match value {
             JustifyContent::FlexStart => taffy::style::JustifyContent::FlexStart,
             JustifyContent::FlexEnd => taffy::style::JustifyContent::FlexEnd,
             JustifyContent::Start => taffy::style::JustifyContent::Start,
@@ -365,19 +450,26 @@ impl From<i32> for JustifyContent {
     }
 }
 
+#[cfg_attr(feature = "napi", napi_derive::napi)]
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
 pub enum Position {
     Relative,
     Absolute,
+    Sticky,
+    Fixed,
 }
 
-impl Into<taffy::style::Position> for Position {
-    fn into(self) -> taffy::style::Position {
-        match self {
+/// A sticky node is laid out in the flow and a fixed one out of it, then they are moved after the layout.
+impl From<Position> for taffy::style::Position {
+    fn from(value: Position) -> Self {
+        match value {
             Position::Relative => taffy::style::Position::Relative,
             Position::Absolute => taffy::style::Position::Absolute,
+            Position::Sticky => taffy::style::Position::Relative,
+            Position::Fixed => taffy::style::Position::Absolute,
         }
     }
 }
@@ -387,23 +479,27 @@ impl From<i32> for Position {
         match n {
             0 => Position::Relative,
             1 => Position::Absolute,
+            2 => Position::Sticky,
+            3 => Position::Fixed,
             _ => Position::Relative,
         }
     }
 }
 
+#[cfg_attr(feature = "napi", napi_derive::napi)]
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
 pub enum FlexWrap {
     NoWrap,
     Wrap,
     WrapReverse,
 }
 
-impl Into<taffy::style::FlexWrap> for FlexWrap {
-    fn into(self) -> taffy::style::FlexWrap {
-        match self {
+impl From<FlexWrap> for taffy::style::FlexWrap {
+    fn from(value: FlexWrap) -> Self {
+        match value {
             FlexWrap::NoWrap => taffy::style::FlexWrap::NoWrap,
             FlexWrap::Wrap => taffy::style::FlexWrap::Wrap,
             FlexWrap::WrapReverse => taffy::style::FlexWrap::WrapReverse,
@@ -440,22 +536,38 @@ pub struct Layout {
     #[wasm_bindgen(readonly)]
     pub childCount: usize,
 
+    /// The first baseline from the top of the box, `undefined` when no leaf in it gives an ascent.
+    #[wasm_bindgen(readonly)]
+    pub firstBaseline: Option<f32>,
+
+    /// The last baseline from the top of the box, `undefined` when no leaf in it gives an ascent.
+    #[wasm_bindgen(readonly)]
+    pub lastBaseline: Option<f32>,
+
     children: Vec<Layout>,
 }
 
 #[wasm_bindgen]
 impl Layout {
+    #[allow(dead_code)]
     fn new(allocator: &Allocator, node: taffy::node::Node) -> Layout {
         let taffy = allocator.taffy.borrow();
         let layout = taffy.layout(node).unwrap();
+        let offset = allocator.positioned.borrow().offset(node);
+        let baselines = allocator.baselines.borrow();
+        let (firstBaseline, lastBaseline) = baselines.get(node);
+        let shift = baselines.shift(node);
+        drop(baselines);
         let children = taffy.children(node).unwrap();
 
         Layout {
             width: layout.size.width,
             height: layout.size.height,
-            x: layout.location.x,
-            y: layout.location.y,
+            x: layout.location.x + offset.x,
+            y: layout.location.y + offset.y + shift,
             childCount: children.len(),
+            firstBaseline,
+            lastBaseline,
             children: children
                 .into_iter()
                 .map(|child| Layout::new(allocator, child))
@@ -483,6 +595,14 @@ pub struct LayoutSimple {
 
     #[wasm_bindgen(readonly)]
     pub y: f32,
+
+    /// The first baseline from the top of the box, `undefined` when no leaf in it gives an ascent.
+    #[wasm_bindgen(readonly)]
+    pub firstBaseline: Option<f32>,
+
+    /// The last baseline from the top of the box, `undefined` when no leaf in it gives an ascent.
+    #[wasm_bindgen(readonly)]
+    pub lastBaseline: Option<f32>,
 }
 
 #[wasm_bindgen]
@@ -490,12 +610,17 @@ impl LayoutSimple {
     fn new(allocator: &Allocator, node: taffy::node::Node) -> LayoutSimple {
         let taffy = allocator.taffy.borrow();
         let layout = taffy.layout(node).unwrap();
+        let offset = allocator.positioned.borrow().offset(node);
+        let baselines = allocator.baselines.borrow();
+        let (firstBaseline, lastBaseline) = baselines.get(node);
 
         LayoutSimple {
             width: layout.size.width,
             height: layout.size.height,
-            x: layout.location.x,
-            y: layout.location.y,
+            x: layout.location.x + offset.x,
+            y: layout.location.y + offset.y + baselines.shift(node),
+            firstBaseline,
+            lastBaseline,
         }
     }
 }
@@ -504,44 +629,417 @@ impl LayoutSimple {
 #[derive(Clone)]
 pub struct Allocator {
     taffy: Rc<RefCell<taffy::Taffy>>,
+    recorder: Arc<Mutex<LayoutRecorder>>,
+    history: Rc<RefCell<LayoutHistory>>,
+    tables: Rc<RefCell<TableLayouts>>,
+    positioned: Rc<RefCell<PositionedLayouts>>,
+    baselines: Rc<RefCell<Baselines>>,
+    writing_modes: WritingModes,
+    nodes: Rc<RefCell<HashMap<taffy::node::Node, NodeData>>>,
+    handles: Rc<RefCell<Handles>>,
+    roots: Rc<RefCell<Vec<Allocator>>>,
+    disposed: Rc<Cell<bool>>,
 }
 
 #[wasm_bindgen]
 impl Allocator {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
+        utils::set_panic_hook();
         Self {
             taffy: Rc::new(RefCell::new(taffy::Taffy::new())),
+            recorder: Arc::new(Mutex::new(LayoutRecorder::default())),
+            history: Rc::new(RefCell::new(LayoutHistory::default())),
+            tables: Rc::new(RefCell::new(TableLayouts::default())),
+            positioned: Rc::new(RefCell::new(PositionedLayouts::default())),
+            baselines: Rc::new(RefCell::new(Baselines::default())),
+            writing_modes: Arc::new(Mutex::new(HashMap::new())),
+            nodes: Rc::new(RefCell::new(HashMap::new())),
+            handles: Rc::new(RefCell::new(Handles::default())),
+            roots: Rc::new(RefCell::new(Vec::new())),
+            disposed: Rc::new(Cell::new(false)),
+        }
+    }
+
+    /// Creates a layout root, which is an allocator with its own tree, caches and counters, so that computing or
+    /// dirtying the nodes of one root never touches another. The root is disposed along with this allocator.
+    #[wasm_bindgen(js_name = createRoot)]
+    pub fn create_root(&self) -> Allocator {
+        let root = Allocator::new();
+        self.roots.borrow_mut().push(root.clone());
+        root
+    }
+
+    /// The number of layout roots created by this allocator which have not been disposed.
+    #[wasm_bindgen(getter)]
+    pub fn rootCount(&self) -> usize {
+        self.roots.borrow().len()
+    }
+
+    /// The number of nodes in the tree of this allocator, the roots are not included.
+    #[wasm_bindgen(getter)]
+    pub fn nodeCount(&self) -> usize {
+        self.taffy.borrow().total_node_count()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn disposed(&self) -> bool {
+        self.disposed.get()
+    }
+
+    /// Drops all the nodes of this allocator and its roots in one call, and detaches it from the allocator which
+    /// created it. The methods of the `Node` objects of a disposed allocator throw "allocator disposed", they can
+    /// only be freed afterwards.
+    #[wasm_bindgen]
+    pub fn dispose(&self) {
+        if self.disposed.replace(true) {
+            return;
+        }
+        for root in self.roots.take() {
+            root.dispose();
         }
+        self.taffy.borrow_mut().clear();
+        *self.history.borrow_mut() = LayoutHistory::default();
+        *self.tables.borrow_mut() = TableLayouts::default();
+        *self.positioned.borrow_mut() = PositionedLayouts::default();
+        *self.baselines.borrow_mut() = Baselines::default();
+        self.writing_modes.lock().unwrap().clear();
+        self.nodes.borrow_mut().clear();
+        *self.handles.borrow_mut() = Handles::default();
+        self.recorder.lock().unwrap().reset();
+    }
+
+    /// Disposes a root created by this allocator.
+    #[wasm_bindgen(js_name = disposeRoot)]
+    pub fn dispose_root(&self, root: &Allocator) {
+        self.roots
+            .borrow_mut()
+            .retain(|r| !Rc::ptr_eq(&r.taffy, &root.taffy));
+        root.dispose();
+    }
+
+    /// Returns the layout tree at `root` in between the layouts before and after the last layout change, where
+    /// `t` goes from 0 to 1 through the `easing` curve. Rendering these FLIP-style intermediate rectangles animates
+    /// the position and size changes, and a change in the middle of a transition starts from what was displayed.
+    #[wasm_bindgen(js_name = interpolateLayout)]
+    pub fn interpolate_layout(&self, root: &Node, t: f32, easing: Easing) -> Result<Layout, JsError> {
+        root.check_disposed()?;
+        let taffy = self.taffy.borrow();
+        Ok(self
+            .history
+            .borrow_mut()
+            .interpolate(&taffy, &self.baselines.borrow(), root.node, t, easing))
+    }
+
+    /// Returns the boxes of `node` from the last layout, in the coordinates of its layout root, or `undefined` when
+    /// the node isn't in the tree of this allocator. It backs `DOM.getBoxModel` of the inspector.
+    #[wasm_bindgen(js_name = getBoxModel)]
+    pub fn get_box_model(&self, node: &Node) -> Option<BoxModel> {
+        if self.disposed.get() || !self.is_same_tree(&node.allocator) {
+            return None;
+        }
+        Some(self.box_geometry(|geometry| geometry.box_model(node.node)))
+    }
+
+    /// Returns the `bindObject` of the topmost node at `(x, y)` in the coordinates of the layout root at `root`,
+    /// or `undefined` when the point is outside of it.
+    #[wasm_bindgen(js_name = getNodeForLocation)]
+    pub fn get_node_for_location(&self, root: &Node, x: f32, y: f32) -> Result<JsValue, JsError> {
+        root.check_disposed()?;
+        if !self.is_same_tree(&root.allocator) {
+            return Err(JsError::new("The node belongs to another layout root"));
+        }
+        let node = self.box_geometry(|geometry| geometry.node_at(root.node, x, y));
+        let nodes = self.nodes.borrow();
+        Ok(node
+            .and_then(|node| nodes.get(&node))
+            .map_or(JsValue::UNDEFINED, |data| data.bind_object.clone()))
+    }
+
+    /// Returns the layout counters accumulated since the allocator was created or the last `resetStats()`.
+    #[wasm_bindgen(js_name = getStats)]
+    pub fn get_stats(&self) -> LayoutStats {
+        self.recorder.lock().unwrap().stats()
+    }
+
+    /// Resets the layout counters and clears the recorded trace.
+    #[wasm_bindgen(js_name = resetStats)]
+    pub fn reset_stats(&self) {
+        self.recorder.lock().unwrap().reset();
+    }
+
+    /// Enables or disables recording a per-node timeline of the layout computations, disabling drops the trace.
+    #[wasm_bindgen(setter)]
+    pub fn set_tracing(&self, enabled: bool) {
+        self.recorder.lock().unwrap().set_tracing(enabled);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn tracing(&self) -> bool {
+        self.recorder.lock().unwrap().is_tracing()
+    }
+
+    /// Exports the recorded trace in the Chrome trace-event JSON format, which can be loaded in the performance
+    /// panel of the devtools.
+    #[wasm_bindgen(js_name = exportTrace)]
+    pub fn export_trace(&self) -> String {
+        self.recorder.lock().unwrap().trace_to_json()
     }
 }
 
+impl Default for Allocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Allocator {
+    fn create_node(&self, style: &JsValue) -> taffy::node::Node {
+        let node = self.insert_node(style);
+        self.nodes.borrow_mut().get_mut(&node).unwrap().style = style.clone();
+        node
+    }
+
+    fn set_node_style(&self, node: taffy::node::Node, style: &JsValue) {
+        self.apply_style(node, style);
+        self.nodes.borrow_mut().get_mut(&node).unwrap().style = style.clone();
+    }
+
+    /// Creates a node of the style, this and the methods below are shared by the wasm and Node-API bindings.
+    fn insert_node(&self, style: &dyn StyleSource) -> taffy::node::Node {
+        let node = self
+            .taffy
+            .borrow_mut()
+            .new_leaf(taffy::style::Style::DEFAULT)
+            .unwrap();
+        self.nodes.borrow_mut().insert(
+            node,
+            NodeData {
+                style: JsValue::UNDEFINED,
+                bind_object: JsValue::UNDEFINED,
+                coerced: Vec::new(),
+                object_fit: ObjectFit::Fill,
+                intrinsic: None,
+                placement: None,
+                values: StyleMap::default(),
+            },
+        );
+        self.apply_style(node, style);
+        node
+    }
+
+    fn apply_style(&self, node: taffy::node::Node, source: &dyn StyleSource) {
+        let recording = RecordingSource::new(source);
+        let style: &dyn StyleSource = &recording;
+        let mut coerced = Vec::new();
+        let mut base = parse_style(style, &mut coerced);
+        let writing_mode = get_enum(style, "writingMode", 3, &mut coerced)
+            .map(WritingMode::from)
+            .unwrap_or(WritingMode::HorizontalTb);
+        writing_mode.adjust_style(&mut base);
+        writing_mode::set(&self.writing_modes, node, writing_mode);
+        let table_item = TableItem::from_style(style, &base, &mut coerced);
+        let positioned_item = PositionedItem::from_style(style, &base);
+        let mut taffy_style = table_item.as_ref().map_or(base, TableItem::initial_style);
+        if let Some(item) = positioned_item.as_ref() {
+            item.adjust_style(&mut taffy_style);
+        }
+        if let Some(placement) = self.nodes.borrow_mut().get_mut(&node).and_then(|data| data.placement.as_mut()) {
+            placement.apply(&mut taffy_style);
+        }
+        let mut taffy = self.taffy.borrow_mut();
+        taffy.set_style(node, taffy_style).unwrap();
+        self.tables.borrow_mut().set(node, table_item);
+        self.positioned.borrow_mut().set(node, positioned_item);
+
+        let mut nodes = self.nodes.borrow_mut();
+        let data = nodes.get_mut(&node).unwrap();
+        if let Some(sizing) = data.intrinsic.as_ref() {
+            sizing.lock().unwrap().set_style(taffy.style(node).unwrap());
+        }
+        data.object_fit = get_object_fit(style, &mut coerced);
+        data.coerced = coerced;
+        data.values = recording.into_values();
+    }
+
+    /// Sets the measure function of a leaf, which replaces its intrinsic size.
+    fn set_measure_func(&self, node: taffy::node::Node, measure_func: Option<taffy::node::MeasureFunc>) {
+        if let Some(data) = self.nodes.borrow_mut().get_mut(&node) {
+            data.intrinsic = None;
+        }
+        self.baselines.borrow_mut().remove(node);
+        self.taffy
+            .borrow_mut()
+            .set_measure(node, measure_func)
+            .unwrap();
+    }
+
+    fn set_intrinsic_size(&self, node: taffy::node::Node, width: f32, height: f32) {
+        self.baselines.borrow_mut().remove(node);
+        let mut taffy = self.taffy.borrow_mut();
+        let sizing = if width > 0.0 && height > 0.0 {
+            let natural = taffy::geometry::Size { width, height };
+            let sizing = Arc::new(Mutex::new(IntrinsicSizing::new(natural, taffy.style(node).unwrap())));
+            taffy
+                .set_measure(node, Some(IntrinsicSizing::to_measure_func(sizing.clone())))
+                .unwrap();
+            Some(sizing)
+        } else {
+            taffy.set_measure(node, None).unwrap();
+            None
+        };
+        if let Some(data) = self.nodes.borrow_mut().get_mut(&node) {
+            data.intrinsic = sizing;
+        }
+    }
+
+    fn object_fit_rect(&self, node: taffy::node::Node) -> LayoutSimple {
+        let taffy = self.taffy.borrow();
+        let size = taffy.layout(node).unwrap().size;
+        let style = taffy.style(node).unwrap();
+        let resolve = |length: taffy::style::LengthPercentage| match length {
+            taffy::style::LengthPercentage::Points(points) => points,
+            taffy::style::LengthPercentage::Percent(percent) => percent * size.width,
+        };
+        let left = resolve(style.padding.left) + resolve(style.border.left);
+        let top = resolve(style.padding.top) + resolve(style.border.top);
+        let content = taffy::geometry::Size {
+            width: (size.width - left - resolve(style.padding.right) - resolve(style.border.right)).max(0.0),
+            height: (size.height - top - resolve(style.padding.bottom) - resolve(style.border.bottom)).max(0.0),
+        };
+        let nodes = self.nodes.borrow();
+        let data = &nodes[&node];
+        let natural = data
+            .intrinsic
+            .as_ref()
+            .map(|sizing| sizing.lock().unwrap().natural())
+            .unwrap_or(content);
+        let (x, y, width, height) = intrinsic::object_fit_rect(data.object_fit, natural, content);
+        LayoutSimple {
+            width,
+            height,
+            x: left + x,
+            y: top + y,
+            firstBaseline: None,
+            lastBaseline: None,
+        }
+    }
+
+    fn set_scroll_offset(&self, node: taffy::node::Node, x: f32, y: f32) {
+        let taffy = self.taffy.borrow();
+        let mut positioned = self.positioned.borrow_mut();
+        positioned.set_scroll_offset(node, taffy::geometry::Point { x, y });
+        positioned.resolve(&taffy, None);
+    }
+
+    /// Computes the layout of the tree at `node`, then resolves the tables, the baselines and the sticky and fixed
+    /// nodes from it.
+    fn compute_layout(
+        &self,
+        node: taffy::node::Node,
+        available_space: taffy::geometry::Size<taffy::style::AvailableSpace>,
+    ) {
+        let start = utils::now();
+        let mut taffy = self.taffy.borrow_mut();
+        self.recorder.lock().unwrap().begin_compute(&taffy, node);
+        let mut tables = self.tables.borrow_mut();
+        let mut dirty_tables = tables.dirty_tables(&taffy);
+        taffy.compute_layout(node, available_space).unwrap();
+        if !tables.is_empty() {
+            let root_width = available_space.width.into_option();
+            // A table which is laid out could change the available width of a nested one, so this runs until
+            // the tables settle, which is a single pass unless tables are nested.
+            for _ in 0..3 {
+                if !tables.relayout(&mut taffy, node, root_width, &mut dirty_tables) {
+                    break;
+                }
+                taffy.compute_layout(node, available_space).unwrap();
+            }
+        }
+        self.baselines.borrow_mut().relayout(&mut taffy, node, available_space);
+        self.positioned.borrow_mut().resolve(&taffy, Some(node));
+        self.recorder
+            .lock()
+            .unwrap()
+            .end_compute(&taffy, node, start, utils::now());
+        self.history.borrow_mut().record(&taffy, node);
+    }
+
+    fn box_geometry<T>(&self, f: impl FnOnce(&BoxGeometry) -> T) -> T {
+        f(&BoxGeometry {
+            taffy: &self.taffy.borrow(),
+            positioned: &self.positioned.borrow(),
+            baselines: &self.baselines.borrow(),
+        })
+    }
+
+    /// The nodes of a disposed allocator were removed from its tree, so their methods throw instead of panicking.
+    fn check_disposed(&self) -> Result<(), String> {
+        if self.disposed.get() {
+            Err("allocator disposed".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The nodes of different layout roots live in different trees, so they can't be linked.
+    fn is_same_tree(&self, other: &Allocator) -> bool {
+        Rc::ptr_eq(&self.taffy, &other.taffy)
+    }
+
+    fn remove_node(&self, node: taffy::node::Node) {
+        if let Ok(mut taffy) = self.taffy.try_borrow_mut() {
+            let _ = taffy.remove(node);
+            self.history.borrow_mut().forget(node);
+            self.tables.borrow_mut().remove(node);
+            self.positioned.borrow_mut().remove(node);
+            self.baselines.borrow_mut().remove(node);
+            self.writing_modes.lock().unwrap().remove(&node);
+            self.nodes.borrow_mut().remove(&node);
+        }
+    }
+}
+
+/// The state of a node besides its taffy style. It's kept by the allocator rather than in `Node`, so that the
+/// command buffer can update a node by its handle.
+struct NodeData {
+    style: JsValue,
+    bind_object: JsValue,
+    coerced: Vec<String>,
+    object_fit: ObjectFit,
+    intrinsic: Option<Arc<Mutex<IntrinsicSizing>>>,
+    /// The position of the node as an attached row of a `VirtualList`.
+    placement: Option<RowPlacement>,
+    /// The style values which were read by the last `apply_style()`.
+    values: StyleMap,
+}
+
+/// A node of the layout tree of an `Allocator`. Freeing it removes the node from the tree, which detaches it from its
+/// parent and its children from it, and releases its handle.
 #[wasm_bindgen]
 pub struct Node {
     allocator: Allocator,
     node: taffy::node::Node,
-    style: JsValue,
+    handle: u32,
+    measure: Option<MeasureCallback>,
     bindObject: JsValue,
-
-    #[wasm_bindgen(readonly)]
-    pub childCount: usize,
 }
 
 #[wasm_bindgen]
 impl Node {
     #[wasm_bindgen(constructor)]
-    pub fn new(allocator: &Allocator, bindObject: &JsValue, style: &JsValue) -> Self {
-        Self {
+    pub fn new(allocator: &Allocator, bindObject: &JsValue, style: &JsValue) -> Result<Node, JsError> {
+        let handle = allocator.handles.borrow_mut().reserve(1).map_err(|err| JsError::new(&err))?;
+        let node = allocator.create_node(style);
+        allocator.nodes.borrow_mut().get_mut(&node).unwrap().bind_object = bindObject.clone();
+        allocator.handles.borrow_mut().set(handle, node, false);
+        Ok(Self {
             allocator: allocator.clone(),
-            node: allocator
-                .taffy
-                .borrow_mut()
-                .new_leaf(parse_style(&style))
-                .unwrap(),
-            style: style.clone(),
+            node,
+            handle,
+            measure: None,
             bindObject: bindObject.clone(),
-            childCount: 0,
-        }
+        })
     }
 
     #[wasm_bindgen(getter)]
@@ -549,296 +1047,361 @@ impl Node {
         self.bindObject.clone()
     }
 
-    #[wasm_bindgen(js_name = setMeasure)]
-    pub fn set_measure(&mut self, measure: &JsValue) {
-        let _measure = Function::from(measure.clone());
+    /// The id of this node in the commands of `Allocator.applyCommands()`.
+    #[wasm_bindgen(getter)]
+    pub fn handle(&self) -> u32 {
+        self.handle
+    }
 
-        self.allocator
-            .taffy
-            .borrow_mut()
-            .set_measure(
+    /// The number of children in the layout tree, it's read from taffy because the children could also be attached
+    /// by a `VirtualList`.
+    #[wasm_bindgen(getter)]
+    pub fn childCount(&self) -> Result<usize, JsError> {
+        self.check_disposed()?;
+        Ok(self.allocator.taffy.borrow().child_count(self.node).unwrap())
+    }
+
+    /// Sets the JS function `(width, height) => { width, height, ascent?, descent? }` which measures this leaf, or
+    /// removes it when the value isn't a function. It replaces the intrinsic size. The `ascent` is the distance from
+    /// the top of the content to the first baseline and the `descent` from the last baseline to the bottom, they
+    /// give the baselines of the layout results and the baseline alignment of the flex rows. In a vertical
+    /// `writingMode` the arguments and the returned `width` and `height` are the inline and block sizes.
+    #[wasm_bindgen(js_name = setMeasure)]
+    pub fn set_measure(&mut self, measure: &JsValue) -> Result<(), JsError> {
+        self.check_disposed()?;
+        self.measure = measure
+            .dyn_ref::<Function>()
+            .map(|measure| MeasureCallback::new(measure.clone()));
+
+        let measure_func = self.measure.as_ref().map(|callback| {
+            callback.to_measure_func(
                 self.node,
-                // Some(taffy::node::MeasureFunc::Boxed(Box::new(
-                //     move |constraints| {
-                //         use taffy::number::OrElse;
-
-                //         let widthConstraint =
-                //             if let taffy::number::Number::Defined(val) = constraints.width {
-                //                 val.into()
-                //             } else {
-                //                 JsValue::UNDEFINED
-                //             };
-
-                //         let heightConstaint =
-                //             if let taffy::number::Number::Defined(val) = constraints.height {
-                //                 val.into()
-                //             } else {
-                //                 JsValue::UNDEFINED
-                //             };
-
-                //         if let Ok(result) =
-                //             measure.call2(&JsValue::UNDEFINED, &widthConstraint, &heightConstaint)
-                //         {
-                //             let width = get_f32(&result, "width");
-                //             let height = get_f32(&result, "height");
-
-                //             if width.is_some() && height.is_some() {
-                //                 return taffy::geometry::Size {
-                //                     width: width.unwrap(),
-                //                     height: height.unwrap(),
-                //                 };
-                //             }
-                //         }
-
-                //         constraints.map(|v| v.or_else(0.0))
-                //     },
-                // ))),
-                None,
+                self.allocator.recorder.clone(),
+                self.allocator.baselines.borrow().leaves(),
+                self.allocator.writing_modes.clone(),
             )
-            .unwrap();
+        });
+        self.allocator.set_measure_func(self.node, measure_func);
+        Ok(())
+    }
+
+    /// Sets the natural size of this leaf, e.g. the pixel size of an image, which sizes it like a CSS replaced
+    /// element along with the `aspectRatio`, size and min/max size styles. It replaces the measure function, and a
+    /// non-positive size removes it.
+    #[wasm_bindgen(js_name = setIntrinsicSize)]
+    pub fn set_intrinsic_size(&mut self, width: f32, height: f32) -> Result<(), JsError> {
+        self.check_disposed()?;
+        self.measure = None;
+        self.allocator.set_intrinsic_size(self.node, width, height);
+        Ok(())
+    }
+
+    /// Returns the rectangle where the content of the intrinsic size is drawn according to the `objectFit` style,
+    /// relative to this node, within its padding and border. It's the content box without an intrinsic size.
+    #[wasm_bindgen(js_name = getObjectFitRect)]
+    pub fn get_object_fit_rect(&self) -> Result<LayoutSimple, JsError> {
+        self.check_disposed()?;
+        Ok(self.allocator.object_fit_rect(self.node))
     }
 
     #[wasm_bindgen(js_name = addChild)]
-    pub fn add_child(&mut self, child: &Node) {
+    pub fn add_child(&mut self, child: &Node) -> Result<(), JsError> {
+        self.check_disposed()?;
+        self.check_same_tree(child)?;
         self.allocator
             .taffy
             .borrow_mut()
             .add_child(self.node, child.node)
             .unwrap();
-        self.childCount += 1;
+        Ok(())
     }
 
     #[wasm_bindgen(js_name = removeChild)]
-    pub fn remove_child(&mut self, child: &Node) {
+    pub fn remove_child(&mut self, child: &Node) -> Result<(), JsError> {
+        self.check_disposed()?;
         self.allocator
             .taffy
             .borrow_mut()
             .remove_child(self.node, child.node)
             .unwrap();
-        self.childCount -= 1;
+        Ok(())
     }
 
     #[wasm_bindgen(js_name = replaceChildAtIndex)]
-    pub fn replace_child_at_index(&mut self, index: usize, child: &Node) {
+    pub fn replace_child_at_index(&mut self, index: usize, child: &Node) -> Result<(), JsError> {
+        self.check_disposed()?;
+        self.check_same_tree(child)?;
         self.allocator
             .taffy
             .borrow_mut()
             .replace_child_at_index(self.node, index, child.node)
             .unwrap();
+        Ok(())
     }
 
     #[wasm_bindgen(js_name = removeChildAtIndex)]
-    pub fn remove_child_at_index(&mut self, index: usize) {
+    pub fn remove_child_at_index(&mut self, index: usize) -> Result<(), JsError> {
+        self.check_disposed()?;
         self.allocator
             .taffy
             .borrow_mut()
             .remove_child_at_index(self.node, index)
             .unwrap();
-        self.childCount -= 1;
+        Ok(())
     }
 
     #[wasm_bindgen(js_name = getStyle)]
-    pub fn get_style(&self) -> JsValue {
-        self.style.clone()
+    pub fn get_style(&self) -> Result<JsValue, JsError> {
+        self.check_disposed()?;
+        Ok(self.allocator.nodes.borrow()[&self.node].style.clone())
     }
 
     #[wasm_bindgen(js_name = setStyle)]
-    pub fn set_style(&mut self, style: &JsValue) {
-        self.allocator
-            .taffy
-            .borrow_mut()
-            .set_style(self.node, parse_style(style))
-            .unwrap();
-        self.style = style.clone();
+    pub fn set_style(&mut self, style: &JsValue) -> Result<(), JsError> {
+        self.check_disposed()?;
+        self.allocator.set_node_style(self.node, style);
+        Ok(())
+    }
+
+    /// Returns the style which taffy actually uses for this node, serialized back to CSS-like strings, with the
+    /// `coercedProperties` list of the keys from the last `setStyle()` which were ignored or coerced.
+    #[wasm_bindgen(js_name = getComputedLayoutStyle)]
+    pub fn get_computed_layout_style(&self) -> Result<JsValue, JsError> {
+        self.check_disposed()?;
+        let taffy = self.allocator.taffy.borrow();
+        let style = taffy.style(self.node).unwrap();
+        Ok(computed_style::serialize_style(style, &self.allocator.nodes.borrow()[&self.node].coerced))
+    }
+
+    /// Makes this node a scroll container scrolled by `(x, y)`, the sticky nodes inside and the fixed nodes of
+    /// the tree are resolved again without computing the layout.
+    #[wasm_bindgen(js_name = setScrollOffset)]
+    pub fn set_scroll_offset(&mut self, x: f32, y: f32) -> Result<(), JsError> {
+        self.check_disposed()?;
+        self.allocator.set_scroll_offset(self.node, x, y);
+        Ok(())
     }
 
     #[wasm_bindgen(js_name = markDirty)]
-    pub fn mark_dirty(&mut self) {
+    pub fn mark_dirty(&mut self) -> Result<(), JsError> {
+        self.check_disposed()?;
         self.allocator
             .taffy
             .borrow_mut()
             .mark_dirty(self.node)
-            .unwrap()
+            .unwrap();
+        Ok(())
     }
 
     #[wasm_bindgen(js_name = isDirty)]
-    pub fn is_dirty(&self) -> bool {
-        self.allocator.taffy.borrow().dirty(self.node).unwrap()
+    pub fn is_dirty(&self) -> Result<bool, JsError> {
+        self.check_disposed()?;
+        Ok(self.allocator.taffy.borrow().dirty(self.node).unwrap())
     }
 
     #[wasm_bindgen(js_name = isChildless)]
-    pub fn is_childless(&mut self) -> bool {
-        self.allocator.taffy.borrow_mut().is_childless(self.node)
+    pub fn is_childless(&mut self) -> Result<bool, JsError> {
+        self.check_disposed()?;
+        Ok(self.allocator.taffy.borrow_mut().is_childless(self.node))
     }
 
+    /// Computes the layout of the tree at this node. When the tree contains tables, they are laid out from the
+    /// first results and the tree is computed again with the table geometry.
     #[wasm_bindgen(js_name = computeLayout)]
-    pub fn compute_layout(&mut self, size: &JsValue) -> bool {
-        self.allocator
-            .taffy
-            .borrow_mut()
-            .compute_layout(
-                self.node,
-                taffy::geometry::Size {
-                    width: get_available_space(size, "width"),
-                    height: get_available_space(size, "height"),
-                },
-            )
-            .unwrap();
+    pub fn compute_layout(&mut self, size: &JsValue) -> Result<bool, JsError> {
+        self.check_disposed()?;
+        let available_space = taffy::geometry::Size {
+            width: get_available_space(size, "width"),
+            height: get_available_space(size, "height"),
+        };
+        self.allocator.compute_layout(self.node, available_space);
         // Layout::new(&self.allocator, self.node)
-        true
+        Ok(true)
     }
 
     #[wasm_bindgen(js_name = getLayout)]
-    pub fn get_layout(&mut self) -> LayoutSimple {
-        LayoutSimple::new(&self.allocator, self.node)
+    pub fn get_layout(&mut self) -> Result<LayoutSimple, JsError> {
+        self.check_disposed()?;
+        Ok(LayoutSimple::new(&self.allocator, self.node))
     }
 }
 
-fn parse_style(style: &JsValue) -> taffy::style::Style {
+impl Node {
+    fn check_disposed(&self) -> Result<(), JsError> {
+        self.allocator.check_disposed().map_err(|err| JsError::new(&err))
+    }
+
+    fn check_same_tree(&self, other: &Node) -> Result<(), JsError> {
+        if self.allocator.is_same_tree(&other.allocator) {
+            Ok(())
+        } else {
+            Err(JsError::new("The node belongs to another layout root"))
+        }
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        if self.allocator.disposed.get() {
+            return;
+        }
+        self.allocator.handles.borrow_mut().remove(self.handle);
+        self.allocator.remove_node(self.node);
+    }
+}
+
+/// Parses the JS style object into a taffy style, the keys of the properties which were given but could not
+/// be used as-is (ignored or coerced to another value) are appended to `coerced`.
+fn parse_style(style: &dyn StyleSource, coerced: &mut Vec<String>) -> taffy::style::Style {
     taffy::style::Style {
-        display: get_i32(style, "display")
+        display: get_enum(style, "display", 6, coerced)
             .map(|i| Display::from(i).into())
             .unwrap_or_default(),
-        position: get_i32(style, "position")
+        position: get_enum(style, "position", 4, coerced)
             .map(|i| Position::from(i).into())
             .unwrap_or_default(),
-        flex_direction: get_i32(style, "flexDirection")
+        flex_direction: get_enum(style, "flexDirection", 4, coerced)
             .map(|i| FlexDirection::from(i).into())
             .unwrap_or_default(),
-        flex_wrap: get_i32(style, "flexWrap")
+        flex_wrap: get_enum(style, "flexWrap", 3, coerced)
             .map(|i| FlexWrap::from(i).into())
             .unwrap_or_default(),
-        align_items: get_i32(style, "alignItems")
+        align_items: get_enum(style, "alignItems", 7, coerced)
             .map(|i| Some(AlignItems::from(i).into()))
             .unwrap_or_default(),
-        align_self: get_i32(style, "alignSelf")
+        align_self: get_enum(style, "alignSelf", 7, coerced)
             .map(|i| Some(AlignSelf::from(i).into()))
             .unwrap_or_default(),
-        align_content: get_i32(style, "alignContent")
-            .map(|i| Some(AlignContent::from(i).into()))
+        align_content: get_enum(style, "alignContent", 8, coerced)
+            .map(|i| {
+                // taffy 0.3 has no start and end content alignment, they are coerced to flex-start and flex-end.
+                if i == AlignContent::Start as i32 || i == AlignContent::End as i32 {
+                    coerced.push("alignContent".to_string());
+                }
+                Some(AlignContent::from(i).into())
+            })
             .unwrap_or_default(),
-        justify_content: get_i32(style, "justifyContent")
+        justify_content: get_enum(style, "justifyContent", 8, coerced)
             .map(|i| Some(JustifyContent::from(i).into()))
             .unwrap_or_default(),
-        justify_self: get_i32(style, "justifySelf")
+        justify_self: get_enum(style, "justifySelf", 7, coerced)
             .map(|i| Some(JustifySelf::from(i).into()))
             .unwrap_or_default(),
-        justify_items: get_i32(style, "justifyItems")
+        justify_items: get_enum(style, "justifyItems", 7, coerced)
             .map(|i| Some(JustifyItems::from(i).into()))
             .unwrap_or_default(),
         grid_template_rows: Default::default(),
         grid_template_columns: Default::default(),
         grid_auto_rows: Default::default(),
         grid_auto_columns: Default::default(),
-        grid_auto_flow: get_i32(style, "gridAutoFlow")
+        grid_auto_flow: get_enum(style, "gridAutoFlow", 4, coerced)
             .map(|i| GridAutoFlow::from(i).into())
             .unwrap_or_default(),
         grid_row: Default::default(),
         grid_column: Default::default(),
         inset: taffy::geometry::Rect {
-            left: get_length_percentage_auto_dimension(style, "insetLeft"),
-            right: get_length_percentage_auto_dimension(style, "insetRight"),
-            top: get_length_percentage_auto_dimension(style, "insetTop"),
-            bottom: get_length_percentage_auto_dimension(style, "insetBottom"),
+            left: get_length_percentage_auto_dimension(style, "insetLeft", coerced),
+            right: get_length_percentage_auto_dimension(style, "insetRight", coerced),
+            top: get_length_percentage_auto_dimension(style, "insetTop", coerced),
+            bottom: get_length_percentage_auto_dimension(style, "insetBottom", coerced),
         },
         margin: taffy::geometry::Rect {
-            left: get_length_percentage_auto_dimension(style, "marginLeft"),
-            right: get_length_percentage_auto_dimension(style, "marginRight"),
-            top: get_length_percentage_auto_dimension(style, "marginTop"),
-            bottom: get_length_percentage_auto_dimension(style, "marginBottom"),
+            left: get_length_percentage_auto_dimension(style, "marginLeft", coerced),
+            right: get_length_percentage_auto_dimension(style, "marginRight", coerced),
+            top: get_length_percentage_auto_dimension(style, "marginTop", coerced),
+            bottom: get_length_percentage_auto_dimension(style, "marginBottom", coerced),
         },
 
         padding: taffy::geometry::Rect {
-            left: get_length_percentage_dimension(style, "paddingLeft"),
-            right: get_length_percentage_dimension(style, "paddingRight"),
-            top: get_length_percentage_dimension(style, "paddingTop"),
-            bottom: get_length_percentage_dimension(style, "paddingBottom"),
+            left: get_length_percentage_dimension(style, "paddingLeft", coerced),
+            right: get_length_percentage_dimension(style, "paddingRight", coerced),
+            top: get_length_percentage_dimension(style, "paddingTop", coerced),
+            bottom: get_length_percentage_dimension(style, "paddingBottom", coerced),
         },
 
         border: taffy::geometry::Rect {
-            left: get_length_percentage_dimension(style, "borderLeft"),
-            right: get_length_percentage_dimension(style, "borderRight"),
-            top: get_length_percentage_dimension(style, "borderTop"),
-            bottom: get_length_percentage_dimension(style, "borderBottom"),
+            left: get_length_percentage_dimension(style, "borderLeft", coerced),
+            right: get_length_percentage_dimension(style, "borderRight", coerced),
+            top: get_length_percentage_dimension(style, "borderTop", coerced),
+            bottom: get_length_percentage_dimension(style, "borderBottom", coerced),
         },
 
-        flex_grow: get_f32(style, "flexGrow").unwrap_or(0.0),
-        flex_shrink: get_f32(style, "flexShrink").unwrap_or(1.0),
-        flex_basis: get_dimension(style, "flexBasis"),
+        flex_grow: get_number(style, "flexGrow", coerced).unwrap_or(0.0),
+        flex_shrink: get_number(style, "flexShrink", coerced).unwrap_or(1.0),
+        flex_basis: get_dimension(style, "flexBasis", coerced),
 
         gap: taffy::geometry::Size {
-            width: get_length_percentage_dimension(style, "gapWidth"),
-            height: get_length_percentage_dimension(style, "gapHeight"),
+            width: get_length_percentage_dimension(style, "gapWidth", coerced),
+            height: get_length_percentage_dimension(style, "gapHeight", coerced),
         },
 
         size: taffy::geometry::Size {
-            width: get_size_dimension(style, "width"),
-            height: get_size_dimension(style, "height"),
+            width: get_size_dimension(style, "width", coerced),
+            height: get_size_dimension(style, "height", coerced),
         },
 
         min_size: taffy::geometry::Size {
-            width: get_size_dimension(style, "minWidth"),
-            height: get_size_dimension(style, "minHeight"),
+            width: get_size_dimension(style, "minWidth", coerced),
+            height: get_size_dimension(style, "minHeight", coerced),
         },
 
         max_size: taffy::geometry::Size {
-            width: get_size_dimension(style, "maxWidth"),
-            height: get_size_dimension(style, "maxHeight"),
+            width: get_size_dimension(style, "maxWidth", coerced),
+            height: get_size_dimension(style, "maxHeight", coerced),
         },
 
-        aspect_ratio: get_f32(style, "aspectRatio"),
+        aspect_ratio: get_number(style, "aspectRatio", coerced),
     }
 }
 
-fn get_size_dimension(obj: &JsValue, key: &str) -> taffy::style::Dimension {
-    let dimension = get_dimension(obj, key);
-    match dimension {
-        taffy::style::Dimension::Auto => taffy::style::Dimension::Auto,
-        _ => dimension,
-    }
+fn get_object_fit(style: &dyn StyleSource, coerced: &mut Vec<String>) -> ObjectFit {
+    get_enum(style, "objectFit", 5, coerced)
+        .map(ObjectFit::from)
+        .unwrap_or(ObjectFit::Fill)
+}
+
+fn get_size_dimension(obj: &dyn StyleSource, key: &str, coerced: &mut Vec<String>) -> taffy::style::Dimension {
+    get_dimension(obj, key, coerced)
 }
 
-fn get_dimension(obj: &JsValue, key: &str) -> taffy::style::Dimension {
-    if has_key(obj, key) {
-        if let Ok(val) = Reflect::get(obj, &key.into()) {
-            if let Some(number) = val.as_f64() {
-                return taffy::style::Dimension::Points(number as f32);
+fn get_dimension(obj: &dyn StyleSource, key: &str, coerced: &mut Vec<String>) -> taffy::style::Dimension {
+    if let Some(value) = obj.get_style_value(key) {
+        if let StyleValue::Number(number) = value {
+            return taffy::style::Dimension::Points(number as f32);
+        }
+        if let StyleValue::String(string) = value {
+            if string == "auto" {
+                return taffy::style::Dimension::Auto;
             }
-            if let Some(string) = val.as_string() {
-                if string == "auto" {
-                    return taffy::style::Dimension::Auto;
-                }
-                if let Ok(number) = string.parse::<f32>() {
-                    return taffy::style::Dimension::Points(number);
-                }
-                if string.ends_with('%') {
-                    let len = string.len();
-                    if let Ok(number) = string[..len - 1].parse::<f32>() {
-                        return taffy::style::Dimension::Percent(number / 100.0);
-                    }
+            if let Ok(number) = string.parse::<f32>() {
+                return taffy::style::Dimension::Points(number);
+            }
+            if string.ends_with('%') {
+                let len = string.len();
+                if let Ok(number) = string[..len - 1].parse::<f32>() {
+                    return taffy::style::Dimension::Percent(number / 100.0);
                 }
             }
         }
+        coerced.push(key.to_string());
     }
     taffy::style::Dimension::Auto
 }
 
-fn get_available_space(obj: &JsValue, key: &str) -> taffy::style::AvailableSpace {
-    if has_key(obj, key) {
-        if let Ok(val) = Reflect::get(obj, &key.into()) {
-            if let Some(number) = val.as_f64() {
-                return taffy::style::AvailableSpace::Definite(number as f32);
+fn get_available_space(obj: &dyn StyleSource, key: &str) -> taffy::style::AvailableSpace {
+    if let Some(value) = obj.get_style_value(key) {
+        if let StyleValue::Number(number) = value {
+            return taffy::style::AvailableSpace::Definite(number as f32);
+        }
+        if let StyleValue::String(string) = value {
+            if string == "min" || string == "minContent" {
+                return taffy::style::AvailableSpace::MinContent;
             }
-            if let Some(string) = val.as_string() {
-                if string == "min" || string == "minContent" {
-                    return taffy::style::AvailableSpace::MinContent;
-                }
-                if string == "max" || string == "maxContent" {
-                    return taffy::style::AvailableSpace::MaxContent;
-                }
-                if let Ok(number) = string.parse::<f32>() {
-                    return taffy::style::AvailableSpace::Definite(number);
-                }
+            if string == "max" || string == "maxContent" {
+                return taffy::style::AvailableSpace::MaxContent;
+            }
+            if let Ok(number) = string.parse::<f32>() {
+                return taffy::style::AvailableSpace::Definite(number);
             }
         }
     }
@@ -846,77 +1409,82 @@ fn get_available_space(obj: &JsValue, key: &str) -> taffy::style::AvailableSpace
 }
 
 fn get_length_percentage_auto_dimension(
-    obj: &JsValue,
+    obj: &dyn StyleSource,
     key: &str,
+    coerced: &mut Vec<String>,
 ) -> taffy::style::LengthPercentageAuto {
-    if has_key(obj, key) {
-        if let Ok(val) = Reflect::get(obj, &key.into()) {
-            if let Some(number) = val.as_f64() {
-                return taffy::style::LengthPercentageAuto::Points(number as f32);
+    if let Some(value) = obj.get_style_value(key) {
+        if let StyleValue::Number(number) = value {
+            return taffy::style::LengthPercentageAuto::Points(number as f32);
+        }
+        if let StyleValue::String(string) = value {
+            if string == "auto" {
+                return taffy::style::LengthPercentageAuto::Auto;
             }
-            if let Some(string) = val.as_string() {
-                if string == "auto" {
-                    return taffy::style::LengthPercentageAuto::Auto;
-                }
-                if let Ok(number) = string.parse::<f32>() {
-                    return taffy::style::LengthPercentageAuto::Points(number);
-                }
-                if string.ends_with('%') {
-                    let len = string.len();
-                    if let Ok(number) = string[..len - 1].parse::<f32>() {
-                        return taffy::style::LengthPercentageAuto::Percent(number / 100.0);
-                    }
+            if let Ok(number) = string.parse::<f32>() {
+                return taffy::style::LengthPercentageAuto::Points(number);
+            }
+            if string.ends_with('%') {
+                let len = string.len();
+                if let Ok(number) = string[..len - 1].parse::<f32>() {
+                    return taffy::style::LengthPercentageAuto::Percent(number / 100.0);
                 }
             }
         }
+        coerced.push(key.to_string());
     }
     taffy::style::LengthPercentageAuto::ZERO
 }
 
-fn get_length_percentage_dimension(obj: &JsValue, key: &str) -> taffy::style::LengthPercentage {
-    if has_key(obj, key) {
-        if let Ok(val) = Reflect::get(obj, &key.into()) {
-            if let Some(number) = val.as_f64() {
-                return taffy::style::LengthPercentage::Points(number as f32);
+fn get_length_percentage_dimension(
+    obj: &dyn StyleSource,
+    key: &str,
+    coerced: &mut Vec<String>,
+) -> taffy::style::LengthPercentage {
+    if let Some(value) = obj.get_style_value(key) {
+        if let StyleValue::Number(number) = value {
+            return taffy::style::LengthPercentage::Points(number as f32);
+        }
+        if let StyleValue::String(string) = value {
+            if let Ok(number) = string.parse::<f32>() {
+                return taffy::style::LengthPercentage::Points(number);
             }
-            if let Some(string) = val.as_string() {
-                if let Ok(number) = string.parse::<f32>() {
-                    return taffy::style::LengthPercentage::Points(number);
-                }
-                if string.ends_with('%') {
-                    let len = string.len();
-                    if let Ok(number) = string[..len - 1].parse::<f32>() {
-                        return taffy::style::LengthPercentage::Percent(number / 100.0);
-                    }
+            if string.ends_with('%') {
+                let len = string.len();
+                if let Ok(number) = string[..len - 1].parse::<f32>() {
+                    return taffy::style::LengthPercentage::Percent(number / 100.0);
                 }
             }
         }
+        coerced.push(key.to_string());
     }
     taffy::style::LengthPercentage::ZERO
 }
 
-fn get_i32(obj: &JsValue, key: &str) -> Option<i32> {
-    if has_key(obj, key) {
-        if let Ok(val) = Reflect::get(obj, &key.into()) {
-            return val.as_f64().map(|v| v as i32);
-        }
+/// Reads the index of an enum style property, an index which is fractional or out of `0..variants` is reported
+/// as coerced because the `From<i32>` conversion maps it to a fallback variant.
+fn get_enum(obj: &dyn StyleSource, key: &str, variants: i32, coerced: &mut Vec<String>) -> Option<i32> {
+    let number = get_number(obj, key, coerced)?;
+    let index = number as i32;
+    if index as f32 != number || !(0..variants).contains(&index) {
+        coerced.push(key.to_string());
     }
-    None
+    Some(index)
 }
 
-fn get_f32(obj: &JsValue, key: &str) -> Option<f32> {
-    if has_key(obj, key) {
-        if let Ok(val) = Reflect::get(obj, &key.into()) {
-            return val.as_f64().map(|v| v as f32);
+fn get_number(obj: &dyn StyleSource, key: &str, coerced: &mut Vec<String>) -> Option<f32> {
+    if let Some(value) = obj.get_style_value(key) {
+        if let StyleValue::Number(number) = value {
+            return Some(number as f32);
         }
+        coerced.push(key.to_string());
     }
     None
 }
 
-fn has_key(obj: &JsValue, key: &str) -> bool {
-    if let Ok(exists) = Reflect::has(obj, &key.into()) {
-        exists
-    } else {
-        false
+fn get_f32(obj: &dyn StyleSource, key: &str) -> Option<f32> {
+    match obj.get_style_value(key)? {
+        StyleValue::Number(number) => Some(number as f32),
+        _ => None,
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,153 @@
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use js_sys::Function;
+use taffy::geometry::Size;
+use taffy::node::MeasureFunc;
+use taffy::style::AvailableSpace;
+use wasm_bindgen::prelude::*;
+
+use crate::baseline::{LeafBaseline, LeafBaselines};
+use crate::stats::LayoutRecorder;
+use crate::style_source::StyleSource;
+use crate::utils;
+use crate::writing_mode::{self, WritingModes};
+
+thread_local! {
+    /// The JS measure callbacks by id. Taffy requires the measure functions to be `Send + Sync` which a JS function
+    /// is not, so the closures handed to taffy capture the id only and look the callback up here, this is fine as
+    /// wasm runs on a single thread.
+    static CALLBACKS: RefCell<HashMap<u32, Function>> = RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<u32> = const { Cell::new(0) };
+}
+
+/// A registered JS measure callback, which is unregistered on drop.
+pub(crate) struct MeasureCallback {
+    id: u32,
+}
+
+impl MeasureCallback {
+    pub fn new(callback: Function) -> Self {
+        let id = NEXT_ID.with(|next| {
+            let id = next.get();
+            next.set(id.wrapping_add(1));
+            id
+        });
+        CALLBACKS.with(|callbacks| callbacks.borrow_mut().insert(id, callback));
+        Self { id }
+    }
+
+    /// Creates the taffy measure function which calls this callback, see `measure_func()`.
+    pub fn to_measure_func(
+        &self,
+        node: taffy::node::Node,
+        recorder: Arc<Mutex<LayoutRecorder>>,
+        baselines: LeafBaselines,
+        writing_modes: WritingModes,
+    ) -> MeasureFunc {
+        let id = self.id;
+        measure_func(node, recorder, baselines, writing_modes, move |width, height| {
+            CALLBACKS.with(|callbacks| {
+                let callbacks = callbacks.borrow();
+                let callback = callbacks.get(&id)?;
+                let width = width.map_or(JsValue::UNDEFINED, JsValue::from);
+                let height = height.map_or(JsValue::UNDEFINED, JsValue::from);
+                Some(
+                    callback
+                        .call2(&JsValue::UNDEFINED, &width, &height)
+                        .unwrap_or(JsValue::UNDEFINED),
+                )
+            })
+        })
+    }
+}
+
+impl Drop for MeasureCallback {
+    fn drop(&mut self) {
+        CALLBACKS.with(|callbacks| callbacks.borrow_mut().remove(&self.id));
+    }
+}
+
+/// Creates the taffy measure function of a leaf which calls `measure(width, height)`, where each argument is the
+/// known dimension or the definite available space, otherwise `None`, and which returns `None` when the callback is
+/// gone. The result has the `width` and `height`, a missing one falls back to the known dimension or zero, and the
+/// optional `ascent` and `descent` which are kept in `baselines` for the node. The axes are swapped for a node in a
+/// vertical writing mode, so that the callback measures the inline and block sizes of its text.
+pub(crate) fn measure_func<F, R>(
+    node: taffy::node::Node,
+    recorder: Arc<Mutex<LayoutRecorder>>,
+    baselines: LeafBaselines,
+    writing_modes: WritingModes,
+    measure: F,
+) -> MeasureFunc
+where
+    F: Fn(Option<f32>, Option<f32>) -> Option<R> + Send + Sync + 'static,
+    R: StyleSource,
+{
+    MeasureFunc::Boxed(Box::new(
+        move |known_dimensions: Size<Option<f32>>, available_space: Size<AvailableSpace>| {
+            let start = utils::now();
+            let vertical = writing_mode::get(&writing_modes, node).is_vertical();
+            let (known_dimensions, available_space) = if vertical {
+                (transpose(known_dimensions), transpose(available_space))
+            } else {
+                (known_dimensions, available_space)
+            };
+
+            let width = get_constraint(known_dimensions.width, available_space.width);
+            let height = get_constraint(known_dimensions.height, available_space.height);
+            let size = match measure(width, height) {
+                Some(result) => {
+                    let mut baselines = baselines.lock().unwrap();
+                    match crate::get_f32(&result, "ascent") {
+                        Some(ascent) => baselines.insert(
+                            node,
+                            LeafBaseline {
+                                ascent,
+                                descent: crate::get_f32(&result, "descent"),
+                            },
+                        ),
+                        None => baselines.remove(&node),
+                    };
+                    Size {
+                        width: known_dimensions
+                            .width
+                            .or_else(|| crate::get_f32(&result, "width"))
+                            .unwrap_or(0.0),
+                        height: known_dimensions
+                            .height
+                            .or_else(|| crate::get_f32(&result, "height"))
+                            .unwrap_or(0.0),
+                    }
+                }
+                None => known_dimensions.map(|v| v.unwrap_or(0.0)),
+            };
+            recorder
+                .lock()
+                .unwrap()
+                .record_measure(node, start, utils::now());
+            if vertical {
+                transpose(size)
+            } else {
+                size
+            }
+        },
+    ))
+}
+
+fn get_constraint(known: Option<f32>, available: AvailableSpace) -> Option<f32> {
+    match (known, available) {
+        (Some(value), _) | (None, AvailableSpace::Definite(value)) => Some(value),
+        _ => None,
+    }
+}
+
+fn transpose<T>(size: Size<T>) -> Size<T> {
+    Size {
+        width: size.height,
+        height: size.width,
+    }
+}
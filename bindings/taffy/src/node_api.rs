@@ -0,0 +1,699 @@
+//! The Node-API binding, which exposes the `Allocator`, `Node`, `Layout` and `VirtualList` API of the wasm binding
+//! to Node.js without the wasm initialization and boundary costs. It shares the allocator, the style parsing, the
+//! command buffers and the measure functions with the wasm binding, so that both return the same layouts.
+//!
+//! The inspector methods `Allocator.getBoxModel()` and `Allocator.getNodeForLocation()` are only in the wasm
+//! binding, as they look the nodes up by their bind objects which this binding keeps in the `Node` objects.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::mem::ManuallyDrop;
+
+use napi::bindgen_prelude::*;
+use napi::{sys, Env, JsFunction, JsObject, JsUnknown, Ref, ValueType};
+use napi_derive::napi;
+use taffy::tree::LayoutTree;
+
+use crate::computed_style;
+use crate::measure;
+use crate::style_source::{StyleSource, StyleValue};
+use crate::Easing;
+
+thread_local! {
+    /// The env of the running `computeLayout()`, which the measure functions call the JS callbacks with.
+    static ENV: Cell<Option<sys::napi_env>> = const { Cell::new(None) };
+    /// The JS measure callbacks by id, like the ones of the wasm binding in `measure`.
+    static CALLBACKS: RefCell<HashMap<u32, Reference>> = RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<u32> = const { Cell::new(0) };
+    /// The references which were dropped without an env, they are released on the next call which has one.
+    static RELEASED: RefCell<Vec<Reference>> = const { RefCell::new(Vec::new()) };
+}
+
+impl StyleSource for JsObject {
+    fn get_style_value(&self, key: &str) -> Option<StyleValue> {
+        if !self.has_named_property(key).unwrap_or_default() {
+            return None;
+        }
+        let value = match self.get_named_property::<JsUnknown>(key) {
+            Ok(value) => value,
+            Err(_) => return Some(StyleValue::Other),
+        };
+        Some(match value.get_type() {
+            Ok(ValueType::Number) => value
+                .coerce_to_number()
+                .and_then(|number| number.get_double())
+                .map_or(StyleValue::Other, StyleValue::Number),
+            Ok(ValueType::String) => value
+                .coerce_to_string()
+                .and_then(|string| string.into_utf8())
+                .and_then(|string| string.into_owned())
+                .map_or(StyleValue::Other, StyleValue::String),
+            _ => StyleValue::Other,
+        })
+    }
+}
+
+/// A Node-API reference, which can only be released with an env. The references which are left when the addon is
+/// unloaded are not released, as the env is gone.
+type Reference = ManuallyDrop<Ref<()>>;
+
+/// Releases the references which were dropped since the last call with an env.
+fn release(env: Env) {
+    for reference in RELEASED.with(|released| released.take()) {
+        let _ = ManuallyDrop::into_inner(reference).unref(env);
+    }
+}
+
+fn defer_release(reference: Reference) {
+    let _ = RELEASED.try_with(|released| released.borrow_mut().push(reference));
+}
+
+/// A reference to a JS value which keeps it alive, it's released on drop or on the next call with an env. The value
+/// is held in an array as Node-API only references objects.
+struct Persistent(Option<Reference>);
+
+impl Persistent {
+    fn new(env: Env, value: JsUnknown) -> Result<Self> {
+        release(env);
+        let mut holder = env.create_array_with_length(1)?;
+        holder.set_element(0, value)?;
+        Ok(Self(Some(ManuallyDrop::new(env.create_reference(holder)?))))
+    }
+
+    fn get(&self, env: Env) -> Result<JsUnknown> {
+        env.get_reference_value::<JsObject>(self.0.as_ref().unwrap())?
+            .get_element(0)
+    }
+}
+
+impl Drop for Persistent {
+    fn drop(&mut self) {
+        if let Some(reference) = self.0.take() {
+            defer_release(reference);
+        }
+    }
+}
+
+/// A registered JS measure callback, which is unregistered on drop.
+struct MeasureCallback {
+    id: u32,
+}
+
+impl MeasureCallback {
+    fn new(env: Env, callback: JsFunction) -> Result<Self> {
+        release(env);
+        let id = NEXT_ID.with(|next| {
+            let id = next.get();
+            next.set(id.wrapping_add(1));
+            id
+        });
+        let reference = ManuallyDrop::new(env.create_reference(callback)?);
+        CALLBACKS.with(|callbacks| callbacks.borrow_mut().insert(id, reference));
+        Ok(Self { id })
+    }
+
+    fn to_measure_func(&self, allocator: &crate::Allocator, node: taffy::node::Node) -> taffy::node::MeasureFunc {
+        let id = self.id;
+        measure::measure_func(
+            node,
+            allocator.recorder.clone(),
+            allocator.baselines.borrow().leaves(),
+            allocator.writing_modes.clone(),
+            move |width, height| {
+                let env = unsafe { Env::from_raw(ENV.with(Cell::get)?) };
+                let callback = CALLBACKS.with(|callbacks| {
+                    env.get_reference_value::<JsFunction>(callbacks.borrow().get(&id)?)
+                        .ok()
+                })?;
+                let constraint = |value: Option<f32>| match value {
+                    Some(value) => env.create_double(value as f64).map(|value| value.into_unknown()),
+                    None => env.get_undefined().map(|value| value.into_unknown()),
+                };
+                let result = constraint(width)
+                    .and_then(|width| Ok((width, constraint(height)?)))
+                    .and_then(|(width, height)| callback.call(None, &[width, height]));
+                match result {
+                    Ok(result) if result.get_type().ok()? == ValueType::Object => {
+                        Some(unsafe { result.cast::<JsObject>() })
+                    }
+                    Ok(_) => None,
+                    Err(_) => {
+                        // Like the wasm binding, a callback which throws measures as `undefined`.
+                        clear_exception(env);
+                        None
+                    }
+                }
+            },
+        )
+    }
+}
+
+impl Drop for MeasureCallback {
+    fn drop(&mut self) {
+        if let Ok(Some(reference)) = CALLBACKS.try_with(|callbacks| callbacks.borrow_mut().remove(&self.id)) {
+            defer_release(reference);
+        }
+    }
+}
+
+fn clear_exception(env: Env) {
+    let mut pending = false;
+    unsafe {
+        sys::napi_is_exception_pending(env.raw(), &mut pending);
+        if pending {
+            let mut exception = std::ptr::null_mut();
+            sys::napi_get_and_clear_last_exception(env.raw(), &mut exception);
+        }
+    }
+}
+
+#[napi(object)]
+pub struct LayoutSimple {
+    pub width: f64,
+    pub height: f64,
+    pub x: f64,
+    pub y: f64,
+    pub first_baseline: Option<f64>,
+    pub last_baseline: Option<f64>,
+}
+
+impl From<crate::LayoutSimple> for LayoutSimple {
+    fn from(layout: crate::LayoutSimple) -> Self {
+        Self {
+            width: layout.width as f64,
+            height: layout.height as f64,
+            x: layout.x as f64,
+            y: layout.y as f64,
+            first_baseline: layout.firstBaseline.map(f64::from),
+            last_baseline: layout.lastBaseline.map(f64::from),
+        }
+    }
+}
+
+#[napi]
+pub struct Layout {
+    #[napi(readonly)]
+    pub width: f64,
+    #[napi(readonly)]
+    pub height: f64,
+    #[napi(readonly)]
+    pub x: f64,
+    #[napi(readonly)]
+    pub y: f64,
+    #[napi(readonly)]
+    pub child_count: u32,
+    #[napi(readonly)]
+    pub first_baseline: Option<f64>,
+    #[napi(readonly)]
+    pub last_baseline: Option<f64>,
+    children: Vec<crate::Layout>,
+}
+
+impl From<crate::Layout> for Layout {
+    fn from(layout: crate::Layout) -> Self {
+        Self {
+            width: layout.width as f64,
+            height: layout.height as f64,
+            x: layout.x as f64,
+            y: layout.y as f64,
+            child_count: layout.childCount as u32,
+            first_baseline: layout.firstBaseline.map(f64::from),
+            last_baseline: layout.lastBaseline.map(f64::from),
+            children: layout.children,
+        }
+    }
+}
+
+#[napi]
+impl Layout {
+    #[napi]
+    pub fn child(&self, at: u32) -> Layout {
+        self.children[at as usize].clone().into()
+    }
+}
+
+#[napi(object)]
+pub struct LayoutStats {
+    pub compute_count: f64,
+    pub compute_time: f64,
+    pub estimated_nodes_visited: f64,
+    pub estimated_cache_hits: f64,
+    pub estimated_cache_misses: f64,
+    pub measure_calls: f64,
+    pub measure_time: f64,
+}
+
+impl From<crate::LayoutStats> for LayoutStats {
+    fn from(stats: crate::LayoutStats) -> Self {
+        Self {
+            compute_count: stats.computeCount,
+            compute_time: stats.computeTime,
+            estimated_nodes_visited: stats.estimatedNodesVisited,
+            estimated_cache_hits: stats.estimatedCacheHits,
+            estimated_cache_misses: stats.estimatedCacheMisses,
+            measure_calls: stats.measureCalls,
+            measure_time: stats.measureTime,
+        }
+    }
+}
+
+/// Returns the keys of the style properties in the `setStyle()` object, indexed by `StyleProperty`.
+// napi only registers the free functions outside of the test builds.
+#[cfg_attr(test, allow(dead_code))]
+#[napi]
+pub fn style_keys() -> Vec<&'static str> {
+    crate::commands::STYLE_KEYS.to_vec()
+}
+
+#[napi]
+pub struct Allocator {
+    inner: crate::Allocator,
+}
+
+#[napi]
+impl Allocator {
+    #[napi(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            inner: crate::Allocator::new(),
+        }
+    }
+
+    #[napi]
+    pub fn create_root(&self) -> Allocator {
+        Self {
+            inner: self.inner.create_root(),
+        }
+    }
+
+    #[napi(getter)]
+    pub fn root_count(&self) -> u32 {
+        self.inner.rootCount() as u32
+    }
+
+    #[napi(getter)]
+    pub fn node_count(&self) -> u32 {
+        self.inner.nodeCount() as u32
+    }
+
+    #[napi(getter)]
+    pub fn disposed(&self) -> bool {
+        self.inner.disposed()
+    }
+
+    #[napi]
+    pub fn dispose(&self) {
+        self.inner.dispose();
+    }
+
+    #[napi]
+    pub fn dispose_root(&self, root: &Allocator) {
+        self.inner.dispose_root(&root.inner);
+    }
+
+    #[napi]
+    pub fn interpolate_layout(&self, root: &Node, t: f64, easing: Easing) -> Result<Layout> {
+        root.check_disposed()?;
+        let taffy = self.inner.taffy.borrow();
+        Ok(self
+            .inner
+            .history
+            .borrow_mut()
+            .interpolate(&taffy, &self.inner.baselines.borrow(), root.node, t as f32, easing)
+            .into())
+    }
+
+    #[napi]
+    pub fn reserve_handles(&self, count: u32) -> Result<u32> {
+        self.inner.handles.borrow_mut().reserve(count).map_err(Error::from_reason)
+    }
+
+    #[napi]
+    pub fn apply_commands(&self, ops: Uint32Array, values: Float32Array) -> Vec<String> {
+        self.inner
+            .apply_command_buffer(&ops, &values, |node, style| self.inner.apply_style(node, style))
+    }
+
+    #[napi]
+    pub fn get_layout(&self, handle: u32) -> Result<LayoutSimple> {
+        let node = self.inner.command_node(handle).map_err(Error::from_reason)?;
+        Ok(crate::LayoutSimple::new(&self.inner, node).into())
+    }
+
+    #[napi]
+    pub fn get_stats(&self) -> LayoutStats {
+        self.inner.get_stats().into()
+    }
+
+    #[napi]
+    pub fn reset_stats(&self) {
+        self.inner.reset_stats();
+    }
+
+    #[napi(getter)]
+    pub fn tracing(&self) -> bool {
+        self.inner.tracing()
+    }
+
+    #[napi(setter, js_name = "tracing")]
+    pub fn set_tracing(&self, enabled: bool) {
+        self.inner.set_tracing(enabled);
+    }
+
+    #[napi]
+    pub fn export_trace(&self) -> String {
+        self.inner.export_trace()
+    }
+}
+
+/// A node of the layout tree of an `Allocator`, it's removed from the tree when it's garbage collected.
+#[napi]
+pub struct Node {
+    allocator: crate::Allocator,
+    node: taffy::node::Node,
+    handle: u32,
+    measure: Option<MeasureCallback>,
+    bind_object: Persistent,
+}
+
+#[napi]
+impl Node {
+    #[napi(constructor)]
+    pub fn new(env: Env, allocator: &Allocator, bind_object: JsUnknown, style: JsObject) -> Result<Self> {
+        let allocator = allocator.inner.clone();
+        let handle = allocator.handles.borrow_mut().reserve(1).map_err(Error::from_reason)?;
+        let node = allocator.insert_node(&style);
+        allocator.handles.borrow_mut().set(handle, node, false);
+        Ok(Self {
+            allocator,
+            node,
+            handle,
+            measure: None,
+            bind_object: Persistent::new(env, bind_object)?,
+        })
+    }
+
+    #[napi(getter)]
+    pub fn bind_object(&self, env: Env) -> Result<JsUnknown> {
+        self.bind_object.get(env)
+    }
+
+    #[napi(getter)]
+    pub fn handle(&self) -> u32 {
+        self.handle
+    }
+
+    #[napi(getter)]
+    pub fn child_count(&self) -> Result<u32> {
+        self.check_disposed()?;
+        Ok(self.allocator.taffy.borrow().child_count(self.node).unwrap() as u32)
+    }
+
+    #[napi]
+    pub fn set_measure(&mut self, env: Env, measure: JsUnknown) -> Result<()> {
+        self.check_disposed()?;
+        self.measure = match measure.get_type()? {
+            ValueType::Function => Some(MeasureCallback::new(env, unsafe { measure.cast() })?),
+            _ => None,
+        };
+        let measure_func = self
+            .measure
+            .as_ref()
+            .map(|callback| callback.to_measure_func(&self.allocator, self.node));
+        self.allocator.set_measure_func(self.node, measure_func);
+        Ok(())
+    }
+
+    #[napi]
+    pub fn set_intrinsic_size(&mut self, width: f64, height: f64) -> Result<()> {
+        self.check_disposed()?;
+        self.measure = None;
+        self.allocator
+            .set_intrinsic_size(self.node, width as f32, height as f32);
+        Ok(())
+    }
+
+    #[napi]
+    pub fn get_object_fit_rect(&self) -> Result<LayoutSimple> {
+        self.check_disposed()?;
+        Ok(self.allocator.object_fit_rect(self.node).into())
+    }
+
+    #[napi]
+    pub fn add_child(&mut self, child: &Node) -> Result<()> {
+        self.check_disposed()?;
+        self.check_same_tree(child)?;
+        self.allocator
+            .taffy
+            .borrow_mut()
+            .add_child(self.node, child.node)
+            .unwrap();
+        Ok(())
+    }
+
+    #[napi]
+    pub fn remove_child(&mut self, child: &Node) -> Result<()> {
+        self.check_disposed()?;
+        self.allocator
+            .taffy
+            .borrow_mut()
+            .remove_child(self.node, child.node)
+            .unwrap();
+        Ok(())
+    }
+
+    #[napi]
+    pub fn replace_child_at_index(&mut self, index: u32, child: &Node) -> Result<()> {
+        self.check_disposed()?;
+        self.check_same_tree(child)?;
+        self.allocator
+            .taffy
+            .borrow_mut()
+            .replace_child_at_index(self.node, index as usize, child.node)
+            .unwrap();
+        Ok(())
+    }
+
+    #[napi]
+    pub fn remove_child_at_index(&mut self, index: u32) -> Result<()> {
+        self.check_disposed()?;
+        self.allocator
+            .taffy
+            .borrow_mut()
+            .remove_child_at_index(self.node, index as usize)
+            .unwrap();
+        Ok(())
+    }
+
+    /// Returns the style properties which were read from the last `setStyle()`, the values which are neither
+    /// numbers nor strings are `null`.
+    #[napi]
+    pub fn get_style(&self, env: Env) -> Result<JsObject> {
+        self.check_disposed()?;
+        let mut object = env.create_object()?;
+        for (key, value) in self.allocator.nodes.borrow()[&self.node].values.iter() {
+            match value {
+                StyleValue::Number(number) => object.set_named_property(key, env.create_double(*number)?)?,
+                StyleValue::String(string) => object.set_named_property(key, env.create_string(string)?)?,
+                StyleValue::Other => object.set_named_property(key, env.get_null()?)?,
+            }
+        }
+        Ok(object)
+    }
+
+    #[napi]
+    pub fn set_style(&mut self, style: JsObject) -> Result<()> {
+        self.check_disposed()?;
+        self.allocator.apply_style(self.node, &style);
+        Ok(())
+    }
+
+    #[napi]
+    pub fn get_computed_layout_style(&self, env: Env) -> Result<JsObject> {
+        self.check_disposed()?;
+        let mut object = env.create_object()?;
+        let taffy = self.allocator.taffy.borrow();
+        for (key, value) in computed_style::style_entries(taffy.style(self.node).unwrap()) {
+            object.set_named_property(key, env.create_string(&value)?)?;
+        }
+        let coerced = &self.allocator.nodes.borrow()[&self.node].coerced;
+        let mut coerced_properties = env.create_array_with_length(coerced.len())?;
+        for (index, key) in coerced.iter().enumerate() {
+            coerced_properties.set_element(index as u32, env.create_string(key)?)?;
+        }
+        object.set_named_property("coercedProperties", coerced_properties)?;
+        Ok(object)
+    }
+
+    #[napi]
+    pub fn set_scroll_offset(&mut self, x: f64, y: f64) -> Result<()> {
+        self.check_disposed()?;
+        self.allocator
+            .set_scroll_offset(self.node, x as f32, y as f32);
+        Ok(())
+    }
+
+    #[napi]
+    pub fn mark_dirty(&mut self) -> Result<()> {
+        self.check_disposed()?;
+        self.allocator
+            .taffy
+            .borrow_mut()
+            .mark_dirty(self.node)
+            .unwrap();
+        Ok(())
+    }
+
+    #[napi]
+    pub fn is_dirty(&self) -> Result<bool> {
+        self.check_disposed()?;
+        Ok(self.allocator.taffy.borrow().dirty(self.node).unwrap())
+    }
+
+    #[napi]
+    pub fn is_childless(&mut self) -> Result<bool> {
+        self.check_disposed()?;
+        Ok(self.allocator.taffy.borrow_mut().is_childless(self.node))
+    }
+
+    #[napi]
+    pub fn compute_layout(&mut self, env: Env, size: JsObject) -> Result<bool> {
+        self.check_disposed()?;
+        release(env);
+        let available_space = taffy::geometry::Size {
+            width: crate::get_available_space(&size, "width"),
+            height: crate::get_available_space(&size, "height"),
+        };
+        let previous = ENV.with(|current| current.replace(Some(env.raw())));
+        self.allocator.compute_layout(self.node, available_space);
+        ENV.with(|current| current.set(previous));
+        Ok(true)
+    }
+
+    #[napi]
+    pub fn get_layout(&self) -> Result<LayoutSimple> {
+        self.check_disposed()?;
+        Ok(crate::LayoutSimple::new(&self.allocator, self.node).into())
+    }
+}
+
+impl Node {
+    fn check_disposed(&self) -> Result<()> {
+        self.allocator.check_disposed().map_err(Error::from_reason)
+    }
+
+    fn check_same_tree(&self, other: &Node) -> Result<()> {
+        if self.allocator.is_same_tree(&other.allocator) {
+            Ok(())
+        } else {
+            Err(Error::from_reason("The node belongs to another layout root"))
+        }
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        if self.allocator.disposed.get() {
+            return;
+        }
+        self.allocator.handles.borrow_mut().remove(self.handle);
+        self.allocator.remove_node(self.node);
+    }
+}
+
+#[napi(object)]
+pub struct VirtualRange {
+    pub start: u32,
+    pub end: u32,
+    pub missing: Vec<u32>,
+    pub removed: Vec<u32>,
+}
+
+#[napi]
+pub struct VirtualList {
+    inner: crate::VirtualList,
+}
+
+#[napi]
+impl VirtualList {
+    #[napi(constructor)]
+    pub fn new(container: &Node, row_count: u32, estimated_row_size: f64) -> Self {
+        Self {
+            inner: crate::VirtualList::with_container(
+                container.allocator.clone(),
+                container.node,
+                row_count,
+                estimated_row_size as f32,
+            ),
+        }
+    }
+
+    #[napi(getter)]
+    pub fn row_count(&self) -> u32 {
+        self.inner.rowCount()
+    }
+
+    #[napi(setter, js_name = "rowCount")]
+    pub fn set_row_count(&mut self, count: u32) {
+        self.inner.set_rowCount(count);
+    }
+
+    #[napi(getter)]
+    pub fn content_size(&self) -> f64 {
+        self.inner.contentSize() as f64
+    }
+
+    #[napi(getter)]
+    pub fn overscan(&self) -> u32 {
+        self.inner.overscan
+    }
+
+    #[napi(setter, js_name = "overscan")]
+    pub fn set_overscan(&mut self, overscan: u32) {
+        self.inner.overscan = overscan;
+    }
+
+    #[napi(getter)]
+    pub fn horizontal(&self) -> bool {
+        self.inner.horizontal
+    }
+
+    #[napi(setter, js_name = "horizontal")]
+    pub fn set_horizontal(&mut self, horizontal: bool) {
+        self.inner.horizontal = horizontal;
+    }
+
+    #[napi]
+    pub fn set_row_size(&mut self, index: u32, size: f64) {
+        self.inner.set_row_size(index, size as f32);
+    }
+
+    #[napi]
+    pub fn get_row_offset(&self, index: u32) -> f64 {
+        self.inner.get_row_offset(index) as f64
+    }
+
+    #[napi]
+    pub fn attach_row(&mut self, index: u32, row: &Node) -> Result<()> {
+        self.inner
+            .attach_node(index, &row.allocator, row.node)
+            .map_err(Error::from_reason)
+    }
+
+    #[napi]
+    pub fn detach_row(&mut self, index: u32) {
+        self.inner.detach_row(index);
+    }
+
+    #[napi]
+    pub fn update(&mut self, scroll_offset: f64, viewport_size: f64) -> VirtualRange {
+        let range = self.inner.update(scroll_offset as f32, viewport_size as f32);
+        VirtualRange {
+            start: range.start,
+            end: range.end,
+            missing: range.missing(),
+            removed: range.removed(),
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::{HashMap, HashSet};
+
+use taffy::geometry::{Point, Rect, Size};
+use taffy::style::{LengthPercentageAuto, Style};
+use taffy::tree::LayoutTree;
+
+use crate::Position;
+use crate::style_source::StyleSource;
+
+/// The insets of a sticky or fixed node, which taffy doesn't see because they are resolved after the layout.
+#[derive(Clone, Debug)]
+pub(crate) struct PositionedItem {
+    position: Position,
+    inset: Rect<LengthPercentageAuto>,
+}
+
+impl PositionedItem {
+    /// Reads the sticky or fixed position of a style, returns `None` for the other positions.
+    pub fn from_style(style: &dyn StyleSource, base: &Style) -> Option<Self> {
+        let position = Position::from(crate::get_f32(style, "position")? as i32);
+        // An inset which isn't given is auto, rather than zero like the insets parsed for taffy.
+        let inset = |key: &str, value: LengthPercentageAuto| {
+            if style.get_style_value(key).is_some() {
+                value
+            } else {
+                LengthPercentageAuto::Auto
+            }
+        };
+        match position {
+            Position::Sticky | Position::Fixed => Some(Self {
+                position,
+                inset: Rect {
+                    left: inset("insetLeft", base.inset.left),
+                    right: inset("insetRight", base.inset.right),
+                    top: inset("insetTop", base.inset.top),
+                    bottom: inset("insetBottom", base.inset.bottom),
+                },
+            }),
+            _ => None,
+        }
+    }
+
+    /// Updates the style used in taffy, a sticky node is laid out in the flow without the relative offsets.
+    pub fn adjust_style(&self, style: &mut Style) {
+        if self.position == Position::Sticky {
+            style.inset = Rect {
+                left: LengthPercentageAuto::Auto,
+                right: LengthPercentageAuto::Auto,
+                top: LengthPercentageAuto::Auto,
+                bottom: LengthPercentageAuto::Auto,
+            };
+        }
+    }
+}
+
+/// Resolves the sticky and fixed nodes of an allocator after each layout and scroll.
+///
+/// The layouts computed by taffy are not scrolled, the scroll offsets of the containers are applied when rendering.
+/// A sticky node is shifted so that it stays within the insets of the viewport of its nearest scroll container
+/// without leaving its parent, and a fixed node is placed at its insets from the layout root and compensates the
+/// scroll offsets of its ancestors. The shifts are added to the locations returned by `getLayout()`.
+#[derive(Debug, Default)]
+pub(crate) struct PositionedLayouts {
+    items: HashMap<taffy::node::Node, PositionedItem>,
+    scroll_offsets: HashMap<taffy::node::Node, Point<f32>>,
+    offsets: HashMap<taffy::node::Node, Point<f32>>,
+    /// The nodes whose layouts were computed, which are the roots the positioned nodes are resolved in.
+    roots: HashSet<taffy::node::Node>,
+}
+
+impl PositionedLayouts {
+    pub fn set(&mut self, node: taffy::node::Node, item: Option<PositionedItem>) {
+        match item {
+            Some(item) => {
+                self.items.insert(node, item);
+            }
+            None => {
+                self.items.remove(&node);
+                self.offsets.remove(&node);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, node: taffy::node::Node) {
+        self.items.remove(&node);
+        self.scroll_offsets.remove(&node);
+        self.offsets.remove(&node);
+        self.roots.remove(&node);
+    }
+
+    pub fn set_scroll_offset(&mut self, node: taffy::node::Node, offset: Point<f32>) {
+        self.scroll_offsets.insert(node, offset);
+    }
+
+    /// The scroll offset of a scroll container, which moves its children when rendering.
+    pub fn scroll_offset(&self, node: taffy::node::Node) -> Point<f32> {
+        self.scroll_offsets.get(&node).copied().unwrap_or(Point { x: 0.0, y: 0.0 })
+    }
+
+    /// The shift of a node from its location computed by taffy.
+    pub fn offset(&self, node: taffy::node::Node) -> Point<f32> {
+        self.offsets.get(&node).copied().unwrap_or(Point { x: 0.0, y: 0.0 })
+    }
+
+    /// Resolves the nodes of all the computed trees, `root` is the root whose layout was just computed, if any.
+    pub fn resolve(&mut self, taffy: &taffy::Taffy, root: Option<taffy::node::Node>) {
+        if let Some(root) = root {
+            self.roots.insert(root);
+        }
+        self.offsets.clear();
+        if self.items.is_empty() {
+            return;
+        }
+
+        // The ancestors are resolved first, as the shift of a node moves its descendants.
+        let mut nodes = self
+            .items
+            .keys()
+            .filter_map(|node| {
+                let root = self.computed_root(taffy, *node)?;
+                Some((ancestors(taffy, *node, root)?.len(), *node, root))
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|(depth, ..)| *depth);
+        for (_, node, root) in nodes {
+            let offset = match self.items[&node].position {
+                Position::Sticky => self.resolve_sticky(taffy, node, root),
+                _ => self.resolve_fixed(taffy, node, root),
+            };
+            if offset.x != 0.0 || offset.y != 0.0 {
+                self.offsets.insert(node, offset);
+            }
+        }
+    }
+
+    /// The furthest ancestor of a node whose layout was computed, which is the root of the tree it's laid out in.
+    fn computed_root(&self, taffy: &taffy::Taffy, node: taffy::node::Node) -> Option<taffy::node::Node> {
+        let mut root = None;
+        let mut current = Some(node);
+        while let Some(node) = current {
+            if self.roots.contains(&node) {
+                root = Some(node);
+            }
+            current = LayoutTree::parent(taffy, node);
+        }
+        root
+    }
+
+    /// The location of a node in the root with the shifts resolved so far.
+    fn absolute_location(&self, taffy: &taffy::Taffy, node: taffy::node::Node, root: taffy::node::Node) -> Point<f32> {
+        let mut location = Point { x: 0.0, y: 0.0 };
+        let mut current = node;
+        while current != root {
+            let layout = taffy.layout(current).unwrap();
+            let offset = self.offset(current);
+            location.x += layout.location.x + offset.x;
+            location.y += layout.location.y + offset.y;
+            current = match LayoutTree::parent(taffy, current) {
+                Some(parent) => parent,
+                None => break,
+            };
+        }
+        location
+    }
+
+    fn resolve_sticky(&self, taffy: &taffy::Taffy, node: taffy::node::Node, root: taffy::node::Node) -> Point<f32> {
+        let zero = Point { x: 0.0, y: 0.0 };
+        let ancestors = match ancestors(taffy, node, root) {
+            Some(ancestors) => ancestors,
+            None => return zero,
+        };
+        let (container, scroll) = match ancestors
+            .iter()
+            .find_map(|ancestor| Some((*ancestor, *self.scroll_offsets.get(ancestor)?)))
+        {
+            Some(found) => found,
+            None => return zero,
+        };
+        let parent = match ancestors.first() {
+            Some(parent) => *parent,
+            None => return zero,
+        };
+
+        let size = taffy.layout(node).unwrap().size;
+        let viewport_size = taffy.layout(container).unwrap().size;
+        let location = self.absolute_location(taffy, node, root);
+        let parent_location = self.absolute_location(taffy, parent, root);
+        // The parent of a node which sticks to its parent scroll container is the scrolled content.
+        let parent_size = if parent == container {
+            scroll_size(taffy, parent)
+        } else {
+            taffy.layout(parent).unwrap().size
+        };
+        let container_location = self.absolute_location(taffy, container, root);
+        let inset = &self.items[&node].inset;
+
+        // The location in the viewport of the container, and the range the node can move within its parent.
+        let x = location.x - container_location.x - scroll.x;
+        let y = location.y - container_location.y - scroll.y;
+        let shift = |start: f32,
+                     length: f32,
+                     viewport: f32,
+                     before: LengthPercentageAuto,
+                     after: LengthPercentageAuto,
+                     min: f32,
+                     max: f32| {
+            let mut shift = 0.0;
+            if let Some(after) = resolve(after, viewport) {
+                let overflow = start + length - (viewport - after);
+                if overflow > 0.0 {
+                    shift = -overflow;
+                }
+            }
+            if let Some(before) = resolve(before, viewport) {
+                if start + shift < before {
+                    shift = before - start;
+                }
+            }
+            shift.clamp(min.min(0.0), max.max(0.0))
+        };
+        Point {
+            x: shift(
+                x,
+                size.width,
+                viewport_size.width,
+                inset.left,
+                inset.right,
+                parent_location.x - location.x,
+                parent_location.x + parent_size.width - location.x - size.width,
+            ),
+            y: shift(
+                y,
+                size.height,
+                viewport_size.height,
+                inset.top,
+                inset.bottom,
+                parent_location.y - location.y,
+                parent_location.y + parent_size.height - location.y - size.height,
+            ),
+        }
+    }
+
+    fn resolve_fixed(&self, taffy: &taffy::Taffy, node: taffy::node::Node, root: taffy::node::Node) -> Point<f32> {
+        let ancestors = match ancestors(taffy, node, root) {
+            Some(ancestors) => ancestors,
+            None => return Point { x: 0.0, y: 0.0 },
+        };
+        let scroll = ancestors.iter().fold(Point { x: 0.0, y: 0.0 }, |total, ancestor| {
+            let scroll = self
+                .scroll_offsets
+                .get(ancestor)
+                .copied()
+                .unwrap_or(Point { x: 0.0, y: 0.0 });
+            Point {
+                x: total.x + scroll.x,
+                y: total.y + scroll.y,
+            }
+        });
+        let size = taffy.layout(node).unwrap().size;
+        let root_size: Size<f32> = taffy.layout(root).unwrap().size;
+        let location = self.absolute_location(taffy, node, root);
+        let inset = &self.items[&node].inset;
+
+        let place = |start: f32, length: f32, viewport: f32, before, after, scroll: f32| {
+            let target = match (resolve(before, viewport), resolve(after, viewport)) {
+                (Some(before), _) => before,
+                (None, Some(after)) => viewport - after - length,
+                (None, None) => return scroll,
+            };
+            target - start + scroll
+        };
+        Point {
+            x: place(location.x, size.width, root_size.width, inset.left, inset.right, scroll.x),
+            y: place(location.y, size.height, root_size.height, inset.top, inset.bottom, scroll.y),
+        }
+    }
+}
+
+/// The size of the content of a scroll container, which is the extent of its children.
+fn scroll_size(taffy: &taffy::Taffy, node: taffy::node::Node) -> Size<f32> {
+    LayoutTree::children(taffy, node).fold(taffy.layout(node).unwrap().size, |size, child| {
+        let layout = taffy.layout(*child).unwrap();
+        Size {
+            width: size.width.max(layout.location.x + layout.size.width),
+            height: size.height.max(layout.location.y + layout.size.height),
+        }
+    })
+}
+
+/// The ancestors of a node from its parent up to `root` included, or `None` if it's not in the tree at `root`.
+fn ancestors(taffy: &taffy::Taffy, node: taffy::node::Node, root: taffy::node::Node) -> Option<Vec<taffy::node::Node>> {
+    let mut ancestors = Vec::new();
+    let mut current = node;
+    while current != root {
+        current = LayoutTree::parent(taffy, current)?;
+        ancestors.push(current);
+    }
+    Some(ancestors)
+}
+
+fn resolve(length: LengthPercentageAuto, basis: f32) -> Option<f32> {
+    match length {
+        LengthPercentageAuto::Points(points) => Some(points),
+        LengthPercentageAuto::Percent(percent) => Some(percent * basis),
+        LengthPercentageAuto::Auto => None,
+    }
+}
@@ -0,0 +1,202 @@
+use slotmap::Key;
+use taffy::tree::LayoutTree;
+use wasm_bindgen::prelude::*;
+
+/// A snapshot of the counters accumulated by an `Allocator` since it was created or the last `resetStats()`. The counts
+/// are `f64` so that they stay exact JS numbers up to 2^53 rather than wrapping.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct LayoutStats {
+    /// The number of `computeLayout` calls.
+    #[wasm_bindgen(readonly)]
+    pub computeCount: f64,
+
+    /// The total time spent in `computeLayout` in milliseconds, measure callbacks included.
+    #[wasm_bindgen(readonly)]
+    pub computeTime: f64,
+
+    /// An estimate of the nodes visited: the number of nodes in the computed trees, summed over all computes. Taffy
+    /// doesn't report its visits, it may skip the subtrees of cached nodes or visit nodes several times.
+    #[wasm_bindgen(readonly)]
+    pub estimatedNodesVisited: f64,
+
+    /// An estimate of the cache hits: the nodes which were clean (with cached layout results) when a compute started.
+    #[wasm_bindgen(readonly)]
+    pub estimatedCacheHits: f64,
+
+    /// An estimate of the cache misses: the nodes which were dirty (no cached layout results) when a compute started.
+    #[wasm_bindgen(readonly)]
+    pub estimatedCacheMisses: f64,
+
+    /// The number of measure callback invocations.
+    #[wasm_bindgen(readonly)]
+    pub measureCalls: f64,
+
+    /// The total time spent in measure callbacks in milliseconds.
+    #[wasm_bindgen(readonly)]
+    pub measureTime: f64,
+}
+
+/// An event in the Chrome trace-event format, see https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+#[derive(Clone, Debug)]
+struct TraceEvent {
+    name: &'static str,
+    /// "X" for the complete events and "O" for the object snapshots.
+    phase: &'static str,
+    /// The timestamp in microseconds.
+    ts: f64,
+    /// The duration in microseconds, only for the complete events.
+    dur: f64,
+    /// The object id, only for the object snapshots.
+    id: u64,
+    /// The arguments as a JSON object.
+    args: String,
+}
+
+impl TraceEvent {
+    fn to_json(&self) -> String {
+        let mut json = format!(
+            "{{\"name\":\"{}\",\"cat\":\"layout\",\"ph\":\"{}\",\"pid\":1,\"tid\":1,\"ts\":{}",
+            self.name,
+            self.phase,
+            json_number(self.ts)
+        );
+        if self.phase == "X" {
+            json.push_str(&format!(",\"dur\":{}", json_number(self.dur)));
+        } else {
+            json.push_str(&format!(",\"id\":\"{:#x}\"", self.id));
+        }
+        json.push_str(&format!(",\"args\":{}}}", self.args));
+        json
+    }
+}
+
+/// Formats a number for the trace, JSON has no NaN or infinities so they're written as `null`.
+fn json_number<T: Copy + Into<f64> + ToString>(value: T) -> String {
+    if value.into().is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// Accumulates the counters and the optional trace of an `Allocator`.
+///
+/// It's shared with the measure functions which taffy requires to be `Send + Sync`, that's why it lives in an
+/// `Arc<Mutex<_>>` rather than in a `RefCell`.
+#[derive(Debug, Default)]
+pub(crate) struct LayoutRecorder {
+    stats: LayoutStats,
+    trace: Option<Vec<TraceEvent>>,
+}
+
+impl LayoutRecorder {
+    pub fn stats(&self) -> LayoutStats {
+        self.stats.clone()
+    }
+
+    pub fn reset(&mut self) {
+        self.stats = LayoutStats::default();
+        if let Some(trace) = self.trace.as_mut() {
+            trace.clear();
+        }
+    }
+
+    pub fn set_tracing(&mut self, enabled: bool) {
+        if enabled {
+            self.trace.get_or_insert_with(Vec::new);
+        } else {
+            self.trace = None;
+        }
+    }
+
+    pub fn is_tracing(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Estimates the visits and the cache use of the compute from the nodes of the tree at `root`, and whether they
+    /// are dirty before the compute.
+    pub fn begin_compute(&mut self, taffy: &taffy::Taffy, root: taffy::node::Node) {
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            self.stats.estimatedNodesVisited += 1.0;
+            if taffy.dirty(node).unwrap_or(true) {
+                self.stats.estimatedCacheMisses += 1.0;
+            } else {
+                self.stats.estimatedCacheHits += 1.0;
+            }
+            stack.extend(LayoutTree::children(taffy, node));
+        }
+    }
+
+    /// Records a finished compute, and to the trace if it's enabled, the compute with a snapshot of the layout of each
+    /// node at its end. Taffy doesn't time the nodes, only the measure callbacks have their own durations.
+    pub fn end_compute(&mut self, taffy: &taffy::Taffy, root: taffy::node::Node, start: f64, end: f64) {
+        self.stats.computeCount += 1.0;
+        self.stats.computeTime += end - start;
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceEvent {
+                name: "computeLayout",
+                phase: "X",
+                ts: start * 1000.0,
+                dur: (end - start) * 1000.0,
+                id: 0,
+                args: format!("{{\"root\":{}}}", root.data().as_ffi()),
+            });
+
+            let mut stack = vec![(root, 0)];
+            while let Some((node, depth)) = stack.pop() {
+                let layout = taffy.layout(node).unwrap();
+                trace.push(TraceEvent {
+                    name: "LayoutNode",
+                    phase: "O",
+                    ts: end * 1000.0,
+                    dur: 0.0,
+                    id: node.data().as_ffi(),
+                    args: format!(
+                        "{{\"snapshot\":{{\"node\":{},\"depth\":{},\"x\":{},\"y\":{},\"width\":{},\"height\":{}}}}}",
+                        node.data().as_ffi(),
+                        depth,
+                        json_number(layout.location.x),
+                        json_number(layout.location.y),
+                        json_number(layout.size.width),
+                        json_number(layout.size.height)
+                    ),
+                });
+                stack.extend(LayoutTree::children(taffy, node).map(|child| (*child, depth + 1)));
+            }
+        }
+    }
+
+    /// Records a call to the measure function of `node`.
+    pub fn record_measure(&mut self, node: taffy::node::Node, start: f64, end: f64) {
+        self.stats.measureCalls += 1.0;
+        self.stats.measureTime += end - start;
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceEvent {
+                name: "measure",
+                phase: "X",
+                ts: start * 1000.0,
+                dur: (end - start) * 1000.0,
+                id: 0,
+                args: format!("{{\"node\":{}}}", node.data().as_ffi()),
+            });
+        }
+    }
+
+    /// Serializes the recorded trace to the Chrome trace-event JSON object format.
+    pub fn trace_to_json(&self) -> String {
+        let events = self
+            .trace
+            .iter()
+            .flatten()
+            .map(TraceEvent::to_json)
+            .collect::<Vec<_>>();
+        format!(
+            "{{\"traceEvents\":[{}],\"displayTimeUnit\":\"ms\"}}",
+            events.join(",")
+        )
+    }
+}
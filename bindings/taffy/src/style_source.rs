@@ -0,0 +1,113 @@
+use std::cell::RefCell;
+
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+/// A property value of a style object.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum StyleValue {
+    Number(f64),
+    String(String),
+    /// Any other value, which is reported as coerced.
+    Other,
+}
+
+/// The object a style is read from, which is a JS object for both the wasm and the Node-API bindings, so that they
+/// parse styles the same way.
+pub(crate) trait StyleSource {
+    /// Returns the value of a property, or `None` if the object doesn't have it.
+    fn get_style_value(&self, key: &str) -> Option<StyleValue>;
+}
+
+impl StyleSource for JsValue {
+    fn get_style_value(&self, key: &str) -> Option<StyleValue> {
+        if !Reflect::has(self, &key.into()).unwrap_or_default() {
+            return None;
+        }
+        let value = match Reflect::get(self, &key.into()) {
+            Ok(value) => value,
+            Err(_) => return Some(StyleValue::Other),
+        };
+        Some(if let Some(number) = value.as_f64() {
+            StyleValue::Number(number)
+        } else if let Some(string) = value.as_string() {
+            StyleValue::String(string)
+        } else {
+            StyleValue::Other
+        })
+    }
+}
+
+/// A style kept on the Rust side, which is how the command buffers change a style property by property for both
+/// bindings.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct StyleMap {
+    values: Vec<(String, StyleValue)>,
+}
+
+impl StyleMap {
+    pub fn set(&mut self, key: &str, value: StyleValue) {
+        match self.values.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.values.push((key.to_string(), value)),
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.values.retain(|(k, _)| k != key);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(String, StyleValue)> {
+        self.values.iter()
+    }
+
+    /// Returns the style as a JS object, the values which are neither numbers nor strings become `null`.
+    pub fn to_js_value(&self) -> JsValue {
+        let object: JsValue = Object::new().into();
+        for (key, value) in self.iter() {
+            let value = match value {
+                StyleValue::Number(number) => JsValue::from(*number),
+                StyleValue::String(string) => JsValue::from(string),
+                StyleValue::Other => JsValue::NULL,
+            };
+            Reflect::set(&object, &key.into(), &value).unwrap();
+        }
+        object
+    }
+}
+
+impl StyleSource for StyleMap {
+    fn get_style_value(&self, key: &str) -> Option<StyleValue> {
+        self.values.iter().find(|(k, _)| k == key).map(|(_, value)| value.clone())
+    }
+}
+
+/// Reads a style and records the values it reads, so that the parsed style can be changed later without its
+/// source object.
+pub(crate) struct RecordingSource<'a> {
+    source: &'a dyn StyleSource,
+    values: RefCell<StyleMap>,
+}
+
+impl<'a> RecordingSource<'a> {
+    pub fn new(source: &'a dyn StyleSource) -> Self {
+        Self {
+            source,
+            values: RefCell::new(StyleMap::default()),
+        }
+    }
+
+    pub fn into_values(self) -> StyleMap {
+        self.values.into_inner()
+    }
+}
+
+impl StyleSource for RecordingSource<'_> {
+    fn get_style_value(&self, key: &str) -> Option<StyleValue> {
+        let value = self.source.get_style_value(key);
+        if let Some(value) = value.as_ref() {
+            self.values.borrow_mut().set(key, value.clone());
+        }
+        value
+    }
+}
@@ -0,0 +1,533 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use taffy::geometry::{Rect, Size};
+use taffy::style::{
+    AlignItems, AvailableSpace, Dimension, FlexDirection, LengthPercentage, LengthPercentageAuto,
+    Position, Style,
+};
+use taffy::tree::LayoutTree;
+
+use crate::Display;
+use crate::style_source::StyleSource;
+use crate::TableLayout;
+
+/// The largest spans of a cell, they're clamped like HTML does.
+const MAX_COLSPAN: f32 = 1000.0;
+const MAX_ROWSPAN: f32 = 65534.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TableRole {
+    Table,
+    Row,
+    Cell,
+}
+
+/// The table properties of a node whose display is `table`, `table-row` or `table-cell`.
+#[derive(Clone, Debug)]
+pub(crate) struct TableItem {
+    role: TableRole,
+    /// The style parsed from JS, the style in taffy is derived from it by the table layout.
+    base: Style,
+    layout: TableLayout,
+    spacing: Size<f32>,
+    colspan: usize,
+    rowspan: usize,
+}
+
+impl TableItem {
+    /// Reads the table properties, returns `None` when the node is not a part of a table.
+    pub fn from_style(style: &dyn StyleSource, base: &Style, coerced: &mut Vec<String>) -> Option<Self> {
+        let role = match crate::get_f32(style, "display").map(|v| v as i32) {
+            Some(n) if n == Display::Table as i32 => TableRole::Table,
+            Some(n) if n == Display::TableRow as i32 => TableRole::Row,
+            Some(n) if n == Display::TableCell as i32 => TableRole::Cell,
+            _ => return None,
+        };
+        let mut get_span = |key: &str, max: f32| {
+            let span = crate::get_number(style, key, coerced)?;
+            if !(1.0..=max).contains(&span) || span.fract() != 0.0 {
+                coerced.push(key.to_string());
+            }
+            Some(span.max(1.0).min(max) as usize)
+        };
+        let colspan = get_span("colspan", MAX_COLSPAN).unwrap_or(1);
+        let rowspan = get_span("rowspan", MAX_ROWSPAN).unwrap_or(1);
+
+        Some(Self {
+            role,
+            base: base.clone(),
+            layout: crate::get_enum(style, "tableLayout", 2, coerced)
+                .map(TableLayout::from)
+                .unwrap_or(TableLayout::Auto),
+            spacing: Size {
+                width: crate::get_number(style, "borderSpacingWidth", coerced).unwrap_or(0.0),
+                height: crate::get_number(style, "borderSpacingHeight", coerced).unwrap_or(0.0),
+            },
+            colspan,
+            rowspan,
+        })
+    }
+
+    /// The style to use in taffy until the table is laid out.
+    pub fn initial_style(&self) -> Style {
+        match self.role {
+            TableRole::Table => Style {
+                flex_direction: FlexDirection::Column,
+                align_items: Some(AlignItems::Stretch),
+                ..self.base.clone()
+            },
+            TableRole::Row => Style {
+                flex_direction: FlexDirection::Row,
+                flex_shrink: 0.0,
+                ..self.base.clone()
+            },
+            TableRole::Cell => self.base.clone(),
+        }
+    }
+}
+
+/// A cell placed in the slots of the table.
+struct PlacedCell {
+    node: taffy::node::Node,
+    row: usize,
+    column: usize,
+    rowspan: usize,
+    colspan: usize,
+}
+
+/// Lays out the `table`, `table-row` and `table-cell` nodes of an allocator.
+///
+/// Taffy has no table algorithm, so the tables are laid out in between taffy computes: the cells are measured
+/// with taffy to size the columns and rows, then the results are written as taffy styles, the table becomes a
+/// flex column of rows with fixed heights, and each cell an absolutely positioned box spanning its slots.
+#[derive(Debug, Default)]
+pub(crate) struct TableLayouts {
+    items: HashMap<taffy::node::Node, TableItem>,
+    available_widths: HashMap<taffy::node::Node, f32>,
+}
+
+impl TableLayouts {
+    pub fn set(&mut self, node: taffy::node::Node, item: Option<TableItem>) {
+        match item {
+            Some(item) => {
+                self.items.insert(node, item);
+            }
+            None => self.remove(node),
+        }
+        self.available_widths.remove(&node);
+    }
+
+    pub fn remove(&mut self, node: taffy::node::Node) {
+        self.items.remove(&node);
+        self.available_widths.remove(&node);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn role(&self, node: taffy::node::Node) -> Option<TableRole> {
+        self.items.get(&node).map(|item| item.role)
+    }
+
+    /// Returns the tables which have changed since the last compute, it must be called before computing.
+    pub fn dirty_tables(&self, taffy: &taffy::Taffy) -> HashSet<taffy::node::Node> {
+        self.items
+            .iter()
+            .filter(|(node, item)| {
+                item.role == TableRole::Table && taffy.dirty(**node).unwrap_or(false)
+            })
+            .map(|(node, _)| *node)
+            .collect()
+    }
+
+    /// Lays out the tables under `root` which are dirty or whose available width has changed since their last
+    /// layout, and returns whether any style was updated so that taffy needs to compute again.
+    pub fn relayout(
+        &mut self,
+        taffy: &mut taffy::Taffy,
+        root: taffy::node::Node,
+        root_width: Option<f32>,
+        dirty: &mut HashSet<taffy::node::Node>,
+    ) -> bool {
+        let tables = self
+            .items
+            .iter()
+            .filter(|(node, item)| item.role == TableRole::Table && is_descendant(taffy, **node, root))
+            .map(|(node, _)| *node)
+            .collect::<Vec<_>>();
+
+        let mut changed = false;
+        for table in tables {
+            let available = if table == root {
+                root_width.unwrap_or(f32::INFINITY)
+            } else {
+                content_width(taffy, LayoutTree::parent(taffy, table).unwrap())
+            };
+            if !dirty.remove(&table) && self.available_widths.get(&table) == Some(&available) {
+                continue;
+            }
+            self.available_widths.insert(table, available);
+            self.layout_table(taffy, table, available);
+            changed = true;
+        }
+        changed
+    }
+
+    fn layout_table(&self, taffy: &mut taffy::Taffy, table: taffy::node::Node, available: f32) {
+        let item = &self.items[&table];
+        let spacing = item.spacing;
+        let rows = taffy
+            .children(table)
+            .unwrap()
+            .into_iter()
+            .filter(|row| self.role(*row) == Some(TableRole::Row))
+            .collect::<Vec<_>>();
+
+        // Place the cells in the slots, a cell goes to the next column which is not occupied by a row span.
+        let mut occupied: Vec<Vec<bool>> = vec![Vec::new(); rows.len()];
+        let mut cells = Vec::new();
+        for (r, row) in rows.iter().enumerate() {
+            let mut column = 0;
+            for cell in taffy.children(*row).unwrap() {
+                let cell_item = match self.items.get(&cell) {
+                    Some(cell_item) if cell_item.role == TableRole::Cell => cell_item,
+                    _ => continue,
+                };
+                while occupied[r].get(column) == Some(&true) {
+                    column += 1;
+                }
+                let rowspan = cell_item.rowspan.min(rows.len() - r);
+                for slots in occupied[r..r + rowspan].iter_mut() {
+                    if slots.len() < column + cell_item.colspan {
+                        slots.resize(column + cell_item.colspan, false);
+                    }
+                    slots[column..column + cell_item.colspan].fill(true);
+                }
+                cells.push(PlacedCell {
+                    node: cell,
+                    row: r,
+                    column,
+                    rowspan,
+                    colspan: cell_item.colspan,
+                });
+                column += cell_item.colspan;
+            }
+        }
+        let column_count = occupied.iter().map(Vec::len).max().unwrap_or(0);
+
+        // The horizontal padding and border of the table, and the space taken by the border spacing.
+        let base = &item.base;
+        let frame = resolve(base.padding.left, available)
+            + resolve(base.padding.right, available)
+            + resolve(base.border.left, available)
+            + resolve(base.border.right, available);
+        let spacing_total = spacing.width * (column_count + 1) as f32;
+        let specified_width = match base.size.width {
+            Dimension::Points(width) => Some(width),
+            Dimension::Percent(percent) if available.is_finite() => Some(percent * available),
+            _ => None,
+        };
+
+        let columns = match (item.layout, specified_width) {
+            (TableLayout::Fixed, Some(width)) => self.fixed_columns(
+                &cells,
+                column_count,
+                (width - frame - spacing_total).max(0.0),
+            ),
+            _ => self.auto_columns(
+                taffy,
+                &cells,
+                column_count,
+                specified_width.map(|width| width - frame - spacing_total),
+                available - frame - spacing_total,
+            ),
+        };
+        let column_offsets = offsets(&columns, spacing.width);
+        let span_width = |cell: &PlacedCell| {
+            columns[cell.column..cell.column + cell.colspan].iter().sum::<f32>()
+                + spacing.width * (cell.colspan - 1) as f32
+        };
+
+        // The row heights are the tallest cells with the column widths, then the row spans are fit in.
+        let mut heights = rows
+            .iter()
+            .map(|row| match self.items[row].base.size.height {
+                Dimension::Points(height) => height,
+                _ => 0.0,
+            })
+            .collect::<Vec<_>>();
+        let mut cell_heights = Vec::with_capacity(cells.len());
+        for cell in cells.iter() {
+            let width = span_width(cell);
+            let height = measure_cell(taffy, cell.node, &self.items[&cell.node].base, Some(width)).height;
+            if cell.rowspan == 1 {
+                heights[cell.row] = heights[cell.row].max(height);
+            }
+            cell_heights.push(height);
+        }
+        for (cell, height) in cells.iter().zip(cell_heights) {
+            if cell.rowspan > 1 {
+                let last = cell.row + cell.rowspan - 1;
+                let spanned = heights[cell.row..=last].iter().sum::<f32>()
+                    + spacing.height * (cell.rowspan - 1) as f32;
+                if height > spanned {
+                    heights[last] += height - spanned;
+                }
+            }
+        }
+        let row_offsets = offsets(&heights, spacing.height);
+
+        // Write the results as taffy styles.
+        let mut table_style = item.initial_style();
+        table_style.gap = Size {
+            width: LengthPercentage::Points(0.0),
+            height: LengthPercentage::Points(spacing.height),
+        };
+        table_style.padding = Rect {
+            left: LengthPercentage::Points(resolve(base.padding.left, available) + spacing.width),
+            right: LengthPercentage::Points(resolve(base.padding.right, available) + spacing.width),
+            top: LengthPercentage::Points(resolve(base.padding.top, available) + spacing.height),
+            bottom: LengthPercentage::Points(resolve(base.padding.bottom, available) + spacing.height),
+        };
+        if specified_width.is_none() {
+            table_style.size.width =
+                Dimension::Points(columns.iter().sum::<f32>() + spacing_total + frame);
+        }
+        taffy.set_style(table, table_style).unwrap();
+
+        for (r, row) in rows.iter().enumerate() {
+            let mut row_style = self.items[row].initial_style();
+            row_style.position = Position::Relative;
+            row_style.size.height = Dimension::Points(heights[r]);
+            row_style.min_size.height = Dimension::Auto;
+            row_style.margin = Rect {
+                left: LengthPercentageAuto::Points(0.0),
+                right: LengthPercentageAuto::Points(0.0),
+                top: LengthPercentageAuto::Points(0.0),
+                bottom: LengthPercentageAuto::Points(0.0),
+            };
+            row_style.padding = Rect {
+                left: LengthPercentage::Points(0.0),
+                right: LengthPercentage::Points(0.0),
+                top: LengthPercentage::Points(0.0),
+                bottom: LengthPercentage::Points(0.0),
+            };
+            taffy.set_style(*row, row_style).unwrap();
+        }
+
+        for cell in cells.iter() {
+            let mut cell_style = self.items[&cell.node].initial_style();
+            cell_style.position = Position::Absolute;
+            cell_style.inset = Rect {
+                left: LengthPercentageAuto::Points(column_offsets[cell.column]),
+                right: LengthPercentageAuto::Auto,
+                top: LengthPercentageAuto::Points(0.0),
+                bottom: LengthPercentageAuto::Auto,
+            };
+            cell_style.size = Size {
+                width: Dimension::Points(span_width(cell)),
+                height: Dimension::Points(
+                    row_offsets[cell.row + cell.rowspan - 1] + heights[cell.row + cell.rowspan - 1]
+                        - row_offsets[cell.row],
+                ),
+            };
+            cell_style.margin = Rect {
+                left: LengthPercentageAuto::Points(0.0),
+                right: LengthPercentageAuto::Points(0.0),
+                top: LengthPercentageAuto::Points(0.0),
+                bottom: LengthPercentageAuto::Points(0.0),
+            };
+            taffy.set_style(cell.node, cell_style).unwrap();
+        }
+    }
+
+    /// The `table-layout: fixed` column widths, which come from the cells of the first row. The columns without
+    /// a specified width share the remaining width equally.
+    fn fixed_columns(&self, cells: &[PlacedCell], column_count: usize, width: f32) -> Vec<f32> {
+        let mut columns = vec![None; column_count];
+        for cell in cells.iter().filter(|cell| cell.row == 0) {
+            let cell_width = match self.items[&cell.node].base.size.width {
+                Dimension::Points(points) => points,
+                Dimension::Percent(percent) => percent * width,
+                Dimension::Auto => continue,
+            };
+            for column in columns[cell.column..cell.column + cell.colspan].iter_mut() {
+                *column = Some(cell_width / cell.colspan as f32);
+            }
+        }
+
+        let specified = columns.iter().flatten().sum::<f32>();
+        let unspecified = columns.iter().filter(|column| column.is_none()).count();
+        let share = if unspecified > 0 {
+            (width - specified).max(0.0) / unspecified as f32
+        } else {
+            0.0
+        };
+        columns
+            .into_iter()
+            .map(|column| column.unwrap_or(share))
+            .collect()
+    }
+
+    /// The `table-layout: auto` column widths, which are between the min-content and max-content widths of the
+    /// cells depending on the table width.
+    fn auto_columns(
+        &self,
+        taffy: &mut taffy::Taffy,
+        cells: &[PlacedCell],
+        column_count: usize,
+        specified: Option<f32>,
+        available: f32,
+    ) -> Vec<f32> {
+        let mut min = vec![0.0f32; column_count];
+        let mut max = vec![0.0f32; column_count];
+        let mut spanning = Vec::new();
+        for cell in cells.iter() {
+            let base = &self.items[&cell.node].base;
+            let cell_min = measure_cell(taffy, cell.node, base, None).width;
+            let cell_max = measure_cell_max_content(taffy, cell.node, base).width;
+            if cell.colspan == 1 {
+                min[cell.column] = min[cell.column].max(cell_min);
+                max[cell.column] = max[cell.column].max(cell_max);
+            } else {
+                spanning.push((cell, cell_min, cell_max));
+            }
+        }
+        // The spanning cells share what the spanned columns are missing equally.
+        for (cell, cell_min, cell_max) in spanning {
+            let range = cell.column..cell.column + cell.colspan;
+            let share = |widths: &[f32], wanted: f32| {
+                (wanted - widths[range.clone()].iter().sum::<f32>()).max(0.0) / cell.colspan as f32
+            };
+            let (min_share, max_share) = (share(&min, cell_min), share(&max, cell_max));
+            for column in range.clone() {
+                min[column] += min_share;
+                max[column] = (max[column] + max_share).max(min[column]);
+            }
+        }
+        for column in 0..column_count {
+            max[column] = max[column].max(min[column]);
+        }
+
+        let min_total = min.iter().sum::<f32>();
+        let max_total = max.iter().sum::<f32>();
+        let width = specified
+            .unwrap_or_else(|| max_total.min(available))
+            .max(min_total);
+        if width >= max_total {
+            let extra = width - max_total;
+            (0..column_count)
+                .map(|column| {
+                    if max_total > 0.0 {
+                        max[column] + extra * max[column] / max_total
+                    } else {
+                        width / column_count as f32
+                    }
+                })
+                .collect()
+        } else {
+            let ratio = (width - min_total) / (max_total - min_total);
+            (0..column_count)
+                .map(|column| min[column] + (max[column] - min[column]) * ratio)
+                .collect()
+        }
+    }
+}
+
+/// Lays out a cell with its own style as a root, with the given width or at its min-content width.
+fn measure_cell(
+    taffy: &mut taffy::Taffy,
+    cell: taffy::node::Node,
+    base: &Style,
+    width: Option<f32>,
+) -> Size<f32> {
+    let mut style = Style {
+        position: Position::Relative,
+        ..base.clone()
+    };
+    if let Some(width) = width {
+        style.size.width = Dimension::Points(width);
+    }
+    taffy.set_style(cell, style).unwrap();
+    taffy
+        .compute_layout(
+            cell,
+            Size {
+                width: AvailableSpace::MinContent,
+                height: AvailableSpace::MaxContent,
+            },
+        )
+        .unwrap();
+    taffy.layout(cell).unwrap().size
+}
+
+fn measure_cell_max_content(
+    taffy: &mut taffy::Taffy,
+    cell: taffy::node::Node,
+    base: &Style,
+) -> Size<f32> {
+    taffy
+        .set_style(
+            cell,
+            Style {
+                position: Position::Relative,
+                ..base.clone()
+            },
+        )
+        .unwrap();
+    taffy
+        .compute_layout(
+            cell,
+            Size {
+                width: AvailableSpace::MaxContent,
+                height: AvailableSpace::MaxContent,
+            },
+        )
+        .unwrap();
+    taffy.layout(cell).unwrap().size
+}
+
+/// The offsets of the items laid out one after another with the spacing in between.
+fn offsets(sizes: &[f32], spacing: f32) -> Vec<f32> {
+    let mut offset = 0.0;
+    sizes
+        .iter()
+        .map(|size| {
+            let start = offset;
+            offset += size + spacing;
+            start
+        })
+        .collect()
+}
+
+fn resolve(length: LengthPercentage, basis: f32) -> f32 {
+    match length {
+        LengthPercentage::Points(points) => points,
+        LengthPercentage::Percent(percent) if basis.is_finite() => percent * basis,
+        LengthPercentage::Percent(_) => 0.0,
+    }
+}
+
+/// The width inside the padding and border of a laid out node.
+fn content_width(taffy: &taffy::Taffy, node: taffy::node::Node) -> f32 {
+    let width = taffy.layout(node).unwrap().size.width;
+    let style = taffy.style(node).unwrap();
+    width
+        - resolve(style.padding.left, width)
+        - resolve(style.padding.right, width)
+        - resolve(style.border.left, width)
+        - resolve(style.border.right, width)
+}
+
+fn is_descendant(taffy: &taffy::Taffy, node: taffy::node::Node, root: taffy::node::Node) -> bool {
+    let mut current = Some(node);
+    while let Some(node) = current {
+        if node == root {
+            return true;
+        }
+        current = LayoutTree::parent(taffy, node);
+    }
+    false
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use taffy::tree::LayoutTree;
+use wasm_bindgen::prelude::*;
+
+use crate::baseline::Baselines;
+use crate::Layout;
+
+#[cfg_attr(feature = "napi", napi_derive::napi)]
+#[wasm_bindgen]
+#[repr(u8)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl From<i32> for Easing {
+    fn from(n: i32) -> Self {
+        match n {
+            0 => Easing::Linear,
+            1 => Easing::Ease,
+            2 => Easing::EaseIn,
+            3 => Easing::EaseOut,
+            4 => Easing::EaseInOut,
+            _ => Easing::Linear,
+        }
+    }
+}
+
+impl Easing {
+    /// Returns the eased progress of `t` in `0..=1`, the curves are the CSS `transition-timing-function` keywords.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::Ease => cubic_bezier(0.25, 0.1, 0.25, 1.0, t),
+            Easing::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            Easing::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            Easing::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+        }
+    }
+}
+
+/// Evaluates the CSS `cubic-bezier(x1, y1, x2, y2)` timing function at `x`, solving the curve parameter with a
+/// few Newton iterations and falling back to bisection.
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    let sample = |a: f32, b: f32, s: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * a + 3.0 * inv * s * s * b + s * s * s
+    };
+    let slope = |a: f32, b: f32, s: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * a + 6.0 * inv * s * (b - a) + 3.0 * s * s * (1.0 - b)
+    };
+
+    let mut s = x;
+    for _ in 0..8 {
+        let error = sample(x1, x2, s) - x;
+        if error.abs() < 1e-6 {
+            return sample(y1, y2, s);
+        }
+        let d = slope(x1, x2, s);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        s -= error / d;
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    s = x;
+    for _ in 0..32 {
+        let value = sample(x1, x2, s);
+        if (value - x).abs() < 1e-6 {
+            break;
+        }
+        if value < x {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+    sample(y1, y2, s)
+}
+
+/// A layout rectangle relative to the parent node.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct LayoutRect {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+impl LayoutRect {
+    fn lerp(&self, to: &LayoutRect, t: f32) -> LayoutRect {
+        LayoutRect {
+            x: self.x + (to.x - self.x) * t,
+            y: self.y + (to.y - self.y) * t,
+            width: self.width + (to.width - self.width) * t,
+            height: self.height + (to.height - self.height) * t,
+        }
+    }
+}
+
+impl From<&taffy::layout::Layout> for LayoutRect {
+    fn from(layout: &taffy::layout::Layout) -> Self {
+        LayoutRect {
+            x: layout.location.x,
+            y: layout.location.y,
+            width: layout.size.width,
+            height: layout.size.height,
+        }
+    }
+}
+
+/// Remembers the layouts of the nodes across computes, so that a transition can go from the previous layout of a
+/// node (the First in FLIP) to the current one (the Last).
+#[derive(Debug, Default)]
+pub(crate) struct LayoutHistory {
+    current: HashMap<taffy::node::Node, LayoutRect>,
+    previous: HashMap<taffy::node::Node, LayoutRect>,
+    /// The last interpolated rectangle of each node, a transition which is interrupted by a new layout restarts
+    /// from what was displayed rather than jumping back to the old layout.
+    displayed: HashMap<taffy::node::Node, LayoutRect>,
+}
+
+impl LayoutHistory {
+    /// Records the layouts of the tree at `root` after a compute, the nodes whose layout changed keep their old one
+    /// as the start of the transition.
+    pub fn record(&mut self, taffy: &taffy::Taffy, root: taffy::node::Node) {
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            let rect = LayoutRect::from(taffy.layout(node).unwrap());
+            match self.current.insert(node, rect) {
+                Some(old) if old != rect => {
+                    let from = self.displayed.remove(&node).unwrap_or(old);
+                    self.previous.insert(node, from);
+                }
+                Some(_) => {}
+                None => {
+                    self.previous.insert(node, rect);
+                }
+            }
+            stack.extend(LayoutTree::children(taffy, node));
+        }
+    }
+
+    /// Forgets the layouts of a node which is removed from the tree.
+    pub fn forget(&mut self, node: taffy::node::Node) {
+        self.current.remove(&node);
+        self.previous.remove(&node);
+        self.displayed.remove(&node);
+    }
+
+    /// Returns the layout tree at `root` at the eased progress `t` between the previous and current layouts.
+    pub fn interpolate(
+        &mut self,
+        taffy: &taffy::Taffy,
+        baselines: &Baselines,
+        node: taffy::node::Node,
+        t: f32,
+        easing: Easing,
+    ) -> Layout {
+        let progress = easing.apply(t);
+        let (firstBaseline, lastBaseline) = baselines.get(node);
+        let last = LayoutRect::from(taffy.layout(node).unwrap());
+        let first = self.previous.get(&node).copied().unwrap_or(last);
+        let rect = first.lerp(&last, progress);
+        if t >= 1.0 {
+            self.displayed.remove(&node);
+            self.previous.insert(node, last);
+        } else {
+            self.displayed.insert(node, rect);
+        }
+
+        let children = taffy
+            .children(node)
+            .unwrap()
+            .into_iter()
+            .map(|child| self.interpolate(taffy, baselines, child, t, easing))
+            .collect::<Vec<_>>();
+        Layout {
+            width: rect.width,
+            height: rect.height,
+            x: rect.x,
+            y: rect.y,
+            childCount: children.len(),
+            firstBaseline,
+            lastBaseline,
+            children,
+        }
+    }
+}
@@ -8,3 +8,30 @@ pub fn set_panic_hook() {
     #[cfg(feature = "console_error_panic_hook")]
     console_error_panic_hook::set_once();
 }
+
+/// Returns a timestamp in milliseconds, it uses `performance.now()` when available for the sub-millisecond
+/// precision, and falls back to `Date.now()`.
+#[cfg(target_arch = "wasm32")]
+pub fn now() -> f64 {
+    use js_sys::Reflect;
+    use wasm_bindgen::JsCast;
+
+    if let Ok(performance) = Reflect::get(&js_sys::global(), &"performance".into()) {
+        if let Ok(now) = Reflect::get(&performance, &"now".into()) {
+            if let Some(now) = now.dyn_ref::<js_sys::Function>() {
+                if let Some(ms) = now.call0(&performance).ok().and_then(|v| v.as_f64()) {
+                    return ms;
+                }
+            }
+        }
+    }
+    js_sys::Date::now()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64() * 1000.0)
+        .unwrap_or_default()
+}
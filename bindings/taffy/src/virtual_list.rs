@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use taffy::geometry::Rect;
+use taffy::style::{LengthPercentageAuto, Position, Style};
+use wasm_bindgen::prelude::*;
+
+use crate::Allocator;
+use crate::Node;
+
+/// The row sizes of a virtual list, the rows use the estimated size until they are measured.
+///
+/// The differences between the measured and estimated sizes are kept in a Fenwick tree, so that the offset of a
+/// row is computed in `O(log n)` regardless of how many rows have been measured.
+#[derive(Debug, Default)]
+struct RowSizes {
+    estimated: f32,
+    deltas: Vec<f32>,
+    tree: Vec<f32>,
+}
+
+impl RowSizes {
+    fn new(count: usize, estimated: f32) -> Self {
+        let mut sizes = Self {
+            estimated,
+            ..Default::default()
+        };
+        sizes.resize(count);
+        sizes
+    }
+
+    fn len(&self) -> usize {
+        self.deltas.len()
+    }
+
+    fn resize(&mut self, count: usize) {
+        self.deltas.resize(count, 0.0);
+        self.tree = vec![0.0; count + 1];
+        for i in 1..=count {
+            self.tree[i] += self.deltas[i - 1];
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= count {
+                let value = self.tree[i];
+                self.tree[parent] += value;
+            }
+        }
+    }
+
+    fn size(&self, index: usize) -> f32 {
+        self.estimated + self.deltas[index]
+    }
+
+    fn set_size(&mut self, index: usize, size: f32) {
+        let change = size - self.size(index);
+        if change == 0.0 {
+            return;
+        }
+        self.deltas[index] += change;
+        let mut i = index + 1;
+        while i < self.tree.len() {
+            self.tree[i] += change;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The offset of the start of the row at `index`, which is the total size for `index == len()`.
+    fn offset(&self, index: usize) -> f32 {
+        let mut delta = 0.0;
+        let mut i = index;
+        while i > 0 {
+            delta += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        index as f32 * self.estimated + delta
+    }
+
+    /// The index of the row which contains `offset`, clamped to the existing rows.
+    fn index_at(&self, offset: f32) -> usize {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = (low + high) / 2;
+            if self.offset(mid + 1) <= offset {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low.min(self.len().saturating_sub(1))
+    }
+}
+
+/// The absolute position of an attached row, which is applied over the style of the row node so that its own
+/// position and insets come back when it's detached.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RowPlacement {
+    inset: Rect<LengthPercentageAuto>,
+    position: Position,
+    own_inset: Rect<LengthPercentageAuto>,
+}
+
+impl RowPlacement {
+    /// Places the row of `style`, which is its own style, and keeps its position and insets to restore them.
+    pub(crate) fn apply(&mut self, style: &mut Style) {
+        self.position = style.position;
+        self.own_inset = style.inset;
+        style.position = Position::Absolute;
+        style.inset = self.inset;
+    }
+
+    fn restore(&self, style: &mut Style) {
+        style.position = self.position;
+        style.inset = self.own_inset;
+    }
+}
+
+/// The result of `VirtualList.update()`.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct VirtualRange {
+    /// The first row index in the viewport with the overscan.
+    #[wasm_bindgen(readonly)]
+    pub start: u32,
+
+    /// The end (exclusive) row index in the viewport with the overscan.
+    #[wasm_bindgen(readonly)]
+    pub end: u32,
+
+    missing: Vec<u32>,
+    removed: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl VirtualRange {
+    /// The row indices in the range which have no attached node yet, and need a real DOM node.
+    #[wasm_bindgen(getter)]
+    pub fn missing(&self) -> Vec<u32> {
+        self.missing.clone()
+    }
+
+    /// The row indices which left the range, their nodes have been detached and can be released.
+    #[wasm_bindgen(getter)]
+    pub fn removed(&self) -> Vec<u32> {
+        self.removed.clone()
+    }
+}
+
+/// Virtualizes the rows of a container node: only the rows intersecting the viewport plus the overscan are
+/// attached to the layout tree, as absolutely positioned children at their offset along the list axis, so that
+/// `computeLayout` costs the same for a list of ten rows or ten thousand.
+#[wasm_bindgen]
+pub struct VirtualList {
+    allocator: Allocator,
+    container: taffy::node::Node,
+    sizes: RowSizes,
+    rows: HashMap<u32, taffy::node::Node>,
+    scroll_offset: f32,
+    viewport_size: f32,
+
+    /// The number of rows to keep attached before and after the viewport.
+    pub overscan: u32,
+
+    /// Lays the rows out from left to right instead of top to bottom.
+    pub horizontal: bool,
+}
+
+#[wasm_bindgen]
+impl VirtualList {
+    #[wasm_bindgen(constructor)]
+    pub fn new(container: &Node, rowCount: u32, estimatedRowSize: f32) -> Self {
+        Self::with_container(container.allocator.clone(), container.node, rowCount, estimatedRowSize)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rowCount(&self) -> u32 {
+        self.sizes.len() as u32
+    }
+
+    /// Updates the row count, the attached rows past the new count are detached by the next `update()`.
+    #[wasm_bindgen(setter)]
+    pub fn set_rowCount(&mut self, count: u32) {
+        self.sizes.resize(count as usize);
+    }
+
+    /// The total size of all rows along the list axis, with the measured sizes where known.
+    #[wasm_bindgen(getter)]
+    pub fn contentSize(&self) -> f32 {
+        self.sizes.offset(self.sizes.len())
+    }
+
+    /// Records the measured size of a row, which replaces its estimated size.
+    #[wasm_bindgen(js_name = setRowSize)]
+    pub fn set_row_size(&mut self, index: u32, size: f32) {
+        if (index as usize) < self.sizes.len() {
+            self.sizes.set_size(index as usize, size);
+        }
+    }
+
+    /// Returns the offset of a row from the start of the content.
+    #[wasm_bindgen(js_name = getRowOffset)]
+    pub fn get_row_offset(&self, index: u32) -> f32 {
+        self.sizes.offset((index as usize).min(self.sizes.len()))
+    }
+
+    /// Attaches the node of a row to the container, it's positioned by the list at the next `update()`. The node
+    /// of another row attached at the same index is detached.
+    #[wasm_bindgen(js_name = attachRow)]
+    pub fn attach_row(&mut self, index: u32, row: &Node) -> Result<(), JsError> {
+        self.attach_node(index, &row.allocator, row.node)
+            .map_err(|error| JsError::new(&error))
+    }
+
+    /// Detaches the node of a row from the container.
+    #[wasm_bindgen(js_name = detachRow)]
+    pub fn detach_row(&mut self, index: u32) {
+        if let Some(node) = self.rows.remove(&index) {
+            let mut taffy = self.allocator.taffy.borrow_mut();
+            let _ = taffy.remove_child(self.container, node);
+            let placement = self
+                .allocator
+                .nodes
+                .borrow_mut()
+                .get_mut(&node)
+                .and_then(|data| data.placement.take());
+            if let Some(placement) = placement {
+                let mut style = taffy.style(node).unwrap().clone();
+                placement.restore(&mut style);
+                taffy.set_style(node, style).unwrap();
+            }
+        }
+    }
+
+    /// Scrolls the viewport, and returns the range of rows to lay out. The sizes of the attached rows from the last
+    /// computed layout are recorded as measured first, then the rows out of the range are detached and the rest are
+    /// positioned relative to the viewport.
+    #[wasm_bindgen]
+    pub fn update(&mut self, scrollOffset: f32, viewportSize: f32) -> VirtualRange {
+        self.measure_attached_rows();
+        self.scroll_offset = scrollOffset.max(0.0);
+        self.viewport_size = viewportSize.max(0.0);
+
+        let count = self.sizes.len();
+        let (start, end) = if count == 0 {
+            (0, 0)
+        } else {
+            let viewport_end = self.scroll_offset + self.viewport_size;
+            let first = self.sizes.index_at(self.scroll_offset);
+            let mut last = self.sizes.index_at(viewport_end);
+            if last > first && self.sizes.offset(last) >= viewport_end {
+                last -= 1;
+            }
+            (
+                first.saturating_sub(self.overscan as usize),
+                (last + 1 + self.overscan as usize).min(count),
+            )
+        };
+
+        let mut removed = self
+            .rows
+            .keys()
+            .copied()
+            .filter(|index| (*index as usize) < start || (*index as usize) >= end)
+            .collect::<Vec<_>>();
+        removed.sort_unstable();
+        for index in removed.iter() {
+            self.detach_row(*index);
+        }
+
+        let mut missing = Vec::new();
+        for index in start as u32..end as u32 {
+            match self.rows.get(&index) {
+                Some(node) => self.position_row(index, *node),
+                None => missing.push(index),
+            }
+        }
+
+        VirtualRange {
+            start: start as u32,
+            end: end as u32,
+            missing,
+            removed,
+        }
+    }
+}
+
+impl VirtualList {
+    pub(crate) fn with_container(
+        allocator: Allocator,
+        container: taffy::node::Node,
+        row_count: u32,
+        estimated_row_size: f32,
+    ) -> Self {
+        Self {
+            allocator,
+            container,
+            sizes: RowSizes::new(row_count as usize, estimated_row_size),
+            rows: HashMap::new(),
+            scroll_offset: 0.0,
+            viewport_size: 0.0,
+            overscan: 2,
+            horizontal: false,
+        }
+    }
+
+    /// Attaches a node of `allocator` as a row, see `attachRow()`.
+    pub(crate) fn attach_node(
+        &mut self,
+        index: u32,
+        allocator: &Allocator,
+        node: taffy::node::Node,
+    ) -> Result<(), String> {
+        if !Rc::ptr_eq(&self.allocator.taffy, &allocator.taffy) {
+            return Err("The row belongs to another layout root".to_string());
+        }
+        if index as usize >= self.sizes.len() {
+            return Err(format!("The row index {} is out of the {} rows", index, self.sizes.len()));
+        }
+        if let Some((attached, _)) = self.rows.iter().find(|(_, row)| **row == node) {
+            if *attached == index {
+                return Ok(());
+            }
+            return Err(format!("The node is already attached as the row {}", attached));
+        }
+        self.detach_row(index);
+        self.rows.insert(index, node);
+        self.allocator
+            .taffy
+            .borrow_mut()
+            .add_child(self.container, node)
+            .unwrap();
+        self.position_row(index, node);
+        Ok(())
+    }
+
+    fn measure_attached_rows(&mut self) {
+        let taffy = self.allocator.taffy.borrow();
+        if taffy.dirty(self.container).unwrap_or(true) {
+            return;
+        }
+        for (index, node) in self.rows.iter() {
+            let index = *index as usize;
+            if index >= self.sizes.len() {
+                continue;
+            }
+            if let Ok(layout) = taffy.layout(*node) {
+                let size = if self.horizontal {
+                    layout.size.width
+                } else {
+                    layout.size.height
+                };
+                if size > 0.0 {
+                    self.sizes.set_size(index, size);
+                }
+            }
+        }
+    }
+
+    fn position_row(&self, index: u32, node: taffy::node::Node) {
+        let offset = LengthPercentageAuto::Points(self.sizes.offset(index as usize) - self.scroll_offset);
+        let zero = LengthPercentageAuto::Points(0.0);
+        let inset = if self.horizontal {
+            Rect {
+                left: offset,
+                right: LengthPercentageAuto::Auto,
+                top: zero,
+                bottom: zero,
+            }
+        } else {
+            Rect {
+                left: zero,
+                right: zero,
+                top: offset,
+                bottom: LengthPercentageAuto::Auto,
+            }
+        };
+        let mut nodes = self.allocator.nodes.borrow_mut();
+        let data = match nodes.get_mut(&node) {
+            Some(data) => data,
+            None => return,
+        };
+        let mut taffy = self.allocator.taffy.borrow_mut();
+        let mut style = taffy.style(node).unwrap().clone();
+        match data.placement.as_mut() {
+            Some(placement) if placement.inset == inset => return,
+            Some(placement) => {
+                placement.inset = inset;
+                style.inset = inset;
+            }
+            None => {
+                let mut placement = RowPlacement {
+                    inset,
+                    position: style.position,
+                    own_inset: style.inset,
+                };
+                placement.apply(&mut style);
+                data.placement = Some(placement);
+            }
+        }
+        taffy.set_style(node, style).unwrap();
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use taffy::geometry::Size;
+use taffy::style::{FlexDirection, GridAutoFlow, Style};
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "napi", napi_derive::napi)]
+#[wasm_bindgen]
+#[repr(u8)]
+#[cfg_attr(not(feature = "napi"), derive(Clone, Copy))]
+#[derive(Debug, PartialEq, Eq)]
+pub enum WritingMode {
+    HorizontalTb,
+    VerticalRl,
+    VerticalLr,
+}
+
+impl From<i32> for WritingMode {
+    fn from(n: i32) -> Self {
+        match n {
+            0 => WritingMode::HorizontalTb,
+            1 => WritingMode::VerticalRl,
+            2 => WritingMode::VerticalLr,
+            _ => WritingMode::HorizontalTb,
+        }
+    }
+}
+
+impl WritingMode {
+    /// Whether the inline axis is vertical, so the inline size is the physical height.
+    pub fn is_vertical(self) -> bool {
+        self != WritingMode::HorizontalTb
+    }
+
+    /// Maps the logical axes of a style to the physical ones taffy lays out.
+    ///
+    /// The flex direction and grid auto flow follow the inline (row) and block (column) axes, and the gaps are
+    /// between the columns and the rows, so they are swapped in a vertical mode. A block axis which goes from right
+    /// to left in `vertical-rl` reverses the physical row. The sizes, margins, paddings, borders and insets are
+    /// physical and left as they are.
+    pub fn adjust_style(self, style: &mut Style) {
+        if !self.is_vertical() {
+            return;
+        }
+        let rtl = self == WritingMode::VerticalRl;
+        style.flex_direction = match style.flex_direction {
+            FlexDirection::Row => FlexDirection::Column,
+            FlexDirection::RowReverse => FlexDirection::ColumnReverse,
+            FlexDirection::Column if rtl => FlexDirection::RowReverse,
+            FlexDirection::Column => FlexDirection::Row,
+            FlexDirection::ColumnReverse if rtl => FlexDirection::Row,
+            FlexDirection::ColumnReverse => FlexDirection::RowReverse,
+        };
+        style.grid_auto_flow = match style.grid_auto_flow {
+            GridAutoFlow::Row => GridAutoFlow::Column,
+            GridAutoFlow::Column => GridAutoFlow::Row,
+            GridAutoFlow::RowDense => GridAutoFlow::ColumnDense,
+            GridAutoFlow::ColumnDense => GridAutoFlow::RowDense,
+        };
+        style.gap = Size {
+            width: style.gap.height,
+            height: style.gap.width,
+        };
+    }
+}
+
+/// The writing modes of the nodes which are not `horizontal-tb`, which are read by the measure functions to pass
+/// the inline and block sizes, so they need to be `Send + Sync`.
+pub(crate) type WritingModes = Arc<Mutex<HashMap<taffy::node::Node, WritingMode>>>;
+
+/// Returns the writing mode of a node.
+pub(crate) fn get(modes: &WritingModes, node: taffy::node::Node) -> WritingMode {
+    modes.lock().unwrap().get(&node).copied().unwrap_or(WritingMode::HorizontalTb)
+}
+
+/// Sets the writing mode of a node, `horizontal-tb` isn't stored.
+pub(crate) fn set(modes: &WritingModes, node: taffy::node::Node, mode: WritingMode) {
+    let mut modes = modes.lock().unwrap();
+    if mode.is_vertical() {
+        modes.insert(node, mode);
+    } else {
+        modes.remove(&node);
+    }
+}
@@ -0,0 +1,206 @@
+import { beforeAll, describe, expect, it } from '@jest/globals';
+import { existsSync } from 'fs';
+import { createRequire } from 'module';
+import { join } from 'path';
+import * as wasm from '@bindings/taffy';
+
+/**
+ * The layouts of the wasm and Node-API builds of the binding must be the same, so the same trees are laid out with
+ * both and compared. The Node-API build is made by `npm run bindings:napi`, which the CI runs before the tests, so
+ * these tests are only skipped without it locally.
+ */
+const addonPath = join(process.cwd(), 'bindings/taffy/taffy_binding.node');
+const describeBackends = existsSync(addonPath) || process.env.CI ? describe : describe.skip;
+
+type Backend = typeof wasm;
+
+type Fixture = {
+  style?: object;
+  measure?: { width: number; height: number; ascent?: number; descent?: number };
+  intrinsicSize?: [number, number];
+  children?: Fixture[];
+};
+
+type LayoutResult = {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  firstBaseline?: number;
+  lastBaseline?: number;
+};
+
+function pickLayout({ x, y, width, height, firstBaseline, lastBaseline }: LayoutResult): LayoutResult {
+  return { x, y, width, height, firstBaseline, lastBaseline };
+}
+
+function layout(backend: Backend, fixture: Fixture, size: object = {}): LayoutResult[] {
+  const allocator = new backend.Allocator();
+  const nodes: wasm.Node[] = [];
+  const create = (fixture: Fixture): wasm.Node => {
+    const node = new backend.Node(allocator, null, fixture.style || {});
+    nodes.push(node);
+    if (fixture.measure) {
+      const result = fixture.measure;
+      node.setMeasure((width?: number) => ({ ...result, width: Math.min(result.width, width ?? Infinity) }));
+    }
+    if (fixture.intrinsicSize) {
+      node.setIntrinsicSize(...fixture.intrinsicSize);
+    }
+    for (const child of fixture.children || []) {
+      node.addChild(create(child));
+    }
+    return node;
+  };
+  const root = create(fixture);
+  root.computeLayout(size);
+  const layouts = nodes.map((node) => pickLayout(node.getLayout()));
+  allocator.dispose();
+  return layouts;
+}
+
+describeBackends('taffy backends', () => {
+  let napi: Backend;
+  beforeAll(async () => {
+    await wasm.loadTaffy();
+    napi = createRequire(addonPath)(addonPath);
+  });
+
+  const fixtures: Record<string, (taffy: Backend) => [Fixture, object?]> = {
+    'flex rows with grow and wrap': (taffy) => [{
+      style: { flexDirection: taffy.FlexDirection.Row, flexWrap: taffy.FlexWrap.Wrap, width: 300, gapWidth: 10 },
+      children: [
+        { style: { width: 120, height: 40 } },
+        { style: { width: 120, height: 60, flexGrow: 1 } },
+        { style: { width: '50%', height: 20 } },
+      ],
+    }],
+    'columns with percent paddings and auto margins': (taffy) => [{
+      style: { flexDirection: taffy.FlexDirection.Column, paddingLeft: '10%', paddingTop: 8 },
+      children: [
+        { style: { height: 30, marginLeft: 'auto', width: 50 } },
+        { style: { flexBasis: 40, borderTop: 2 } },
+      ],
+    }, { width: 200, height: 400 }],
+    'baseline aligned text': (taffy) => [{
+      style: { alignItems: taffy.AlignItems.Baseline, width: 400 },
+      children: [
+        { style: { paddingTop: 4 }, measure: { width: 80, height: 20, ascent: 16, descent: 4 } },
+        { measure: { width: 120, height: 36, ascent: 28 } },
+        { intrinsicSize: [64, 32], style: { width: 32 } },
+      ],
+    }],
+    'tables': (taffy) => [{
+      style: { display: taffy.Display.Table, borderSpacingWidth: 2, borderSpacingHeight: 2, width: 300 },
+      children: [
+        {
+          style: { display: taffy.Display.TableRow },
+          children: [
+            { style: { display: taffy.Display.TableCell }, measure: { width: 90, height: 20 } },
+            { style: { display: taffy.Display.TableCell, colspan: 2 }, measure: { width: 40, height: 50 } },
+          ],
+        },
+        {
+          style: { display: taffy.Display.TableRow },
+          children: [
+            { style: { display: taffy.Display.TableCell }, measure: { width: 30, height: 20 } },
+            { style: { display: taffy.Display.TableCell }, measure: { width: 30, height: 20 } },
+            { style: { display: taffy.Display.TableCell }, measure: { width: 30, height: 20 } },
+          ],
+        },
+      ],
+    }],
+    'sticky and fixed nodes': (taffy) => [{
+      style: { flexDirection: taffy.FlexDirection.Column, width: 100, height: 100 },
+      children: [
+        { style: { position: taffy.Position.Sticky, insetTop: 0, height: 20, flexShrink: 0 } },
+        { style: { height: 500, flexShrink: 0 } },
+        { style: { position: taffy.Position.Fixed, insetRight: 0, insetBottom: 0, width: 10, height: 10 } },
+      ],
+    }],
+    'vertical writing mode': (taffy) => [{
+      style: { writingMode: taffy.WritingMode.VerticalRl, width: 200, height: 100 },
+      children: [
+        { style: { writingMode: taffy.WritingMode.VerticalRl }, measure: { width: 60, height: 16 } },
+        { style: { width: 20, height: 20 } },
+      ],
+    }],
+    'grid auto flow': (taffy) => [{
+      style: { display: taffy.Display.Grid, gridAutoFlow: taffy.GridAutoFlow.Column, width: 90 },
+      children: [{ style: { height: 10 } }, { style: { height: 20 } }],
+    }],
+  };
+
+  for (const [name, fixture] of Object.entries(fixtures)) {
+    it(`returns the same layouts for ${name}`, () => {
+      const [tree, size] = fixture(wasm);
+      expect(layout(napi, tree, size)).toEqual(layout(wasm, tree, size));
+    });
+  }
+
+  it('parses invalid styles the same way', () => {
+    const tree = { style: { width: 'wide', height: {}, flexGrow: '1' }, children: [{ style: { width: 10 } }] };
+    expect(layout(napi, tree, { width: 'max' })).toEqual(layout(wasm, tree, { width: 'max' }));
+  });
+
+  it('applies the same command buffers', () => {
+    const run = (taffy: Backend) => {
+      const allocator = new taffy.Allocator();
+      const root = new taffy.Node(allocator, null, { width: 100, alignContent: taffy.AlignContent.End });
+      const first = allocator.reserveHandles(1);
+      const ops = [
+        taffy.LayoutCommand.Create, first,
+        taffy.LayoutCommand.SetStyle, first, taffy.StyleProperty.Width, taffy.StyleUnit.Percent,
+        taffy.LayoutCommand.RemoveChild, first, root.handle,
+        taffy.LayoutCommand.AddChild, root.handle, first,
+        taffy.LayoutCommand.SetIntrinsicSize, first,
+      ];
+      const values = new Float32Array([50, 20, 10]);
+      const errors = allocator.applyCommands(new Uint32Array(ops), values);
+      const nodeCount = allocator.nodeCount;
+      const applied = allocator.applyCommands(new Uint32Array([...ops.slice(0, 6), ...ops.slice(9)]), values);
+      root.computeLayout({});
+      const result = {
+        errors,
+        nodeCount,
+        applied,
+        styleKeys: taffy.styleKeys(),
+        layout: pickLayout(allocator.getLayout(first)),
+        computedStyle: root.getComputedLayoutStyle(),
+      };
+      allocator.dispose();
+      return result;
+    };
+    expect(run(napi)).toEqual(run(wasm));
+  });
+
+  it('virtualizes the same rows', () => {
+    const run = (taffy: Backend) => {
+      const allocator = new taffy.Allocator();
+      const container = new taffy.Node(allocator, null, { width: 100, height: 50 });
+      const list = new taffy.VirtualList(container, 1000, 20);
+      list.overscan = 1;
+      const range = list.update(100, 50);
+      const rows = Array.from(range.missing).map((index: number) => {
+        const row = new taffy.Node(allocator, null, { height: 10 + index % 3 });
+        list.attachRow(index, row);
+        return row;
+      });
+      container.computeLayout({});
+      const next = list.update(130, 50);
+      const result = {
+        ranges: [range, next].map(({ start, end, missing, removed }) => ({
+          start,
+          end,
+          missing: Array.from(missing),
+          removed: Array.from(removed),
+        })),
+        contentSize: list.contentSize,
+        rows: rows.map((row: wasm.Node) => pickLayout(row.getLayout())),
+      };
+      allocator.dispose();
+      return result;
+    };
+    expect(run(napi)).toEqual(run(wasm));
+  });
+});
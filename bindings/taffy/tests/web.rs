@@ -11,3 +11,728 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+#[wasm_bindgen_test]
+fn computed_layout_style_reports_coerced_properties() {
+    use js_sys::{Array, Object, Reflect};
+    use taffy_binding::{AlignContent, Allocator, Node};
+    use wasm_bindgen::JsValue;
+
+    let style = Object::new();
+    Reflect::set(&style, &"alignSelf".into(), &JsValue::from(42)).unwrap();
+    Reflect::set(&style, &"width".into(), &JsValue::from("50%")).unwrap();
+    Reflect::set(&style, &"height".into(), &JsValue::from("tall")).unwrap();
+
+    let allocator = Allocator::new();
+    let node = Node::new(&allocator, &JsValue::NULL, &style).ok().unwrap();
+    let computed = node.get_computed_layout_style().ok().unwrap();
+
+    let get = |key: &str| Reflect::get(&computed, &key.into()).unwrap();
+    assert_eq!(get("alignSelf").as_string().unwrap(), "start");
+    assert_eq!(get("width").as_string().unwrap(), "50%");
+    assert_eq!(get("height").as_string().unwrap(), "auto");
+
+    let coerced = Array::from(&get("coercedProperties"));
+    assert_eq!(coerced.length(), 2);
+    assert_eq!(coerced.get(0).as_string().unwrap(), "alignSelf");
+    assert_eq!(coerced.get(1).as_string().unwrap(), "height");
+
+    let style = Object::new();
+    Reflect::set(&style, &"alignContent".into(), &JsValue::from(AlignContent::End as i32)).unwrap();
+    let mut node = Node::new(&allocator, &JsValue::NULL, &style).ok().unwrap();
+    let computed = node.get_computed_layout_style().ok().unwrap();
+    let get = |key: &str| Reflect::get(&computed, &key.into()).unwrap();
+    assert_eq!(get("alignContent").as_string().unwrap(), "flex-end");
+    let coerced = Array::from(&get("coercedProperties"));
+    assert_eq!(coerced.length(), 1);
+    assert_eq!(coerced.get(0).as_string().unwrap(), "alignContent");
+
+    Reflect::set(&style, &"alignContent".into(), &JsValue::from(AlignContent::FlexEnd as i32)).unwrap();
+    node.set_style(&style).ok().unwrap();
+    let computed = node.get_computed_layout_style().ok().unwrap();
+    assert_eq!(Array::from(&Reflect::get(&computed, &"coercedProperties".into()).unwrap()).length(), 0);
+}
+
+#[wasm_bindgen_test]
+fn allocator_stats_count_computes_and_cache() {
+    use js_sys::Object;
+    use taffy_binding::{Allocator, Node};
+    use wasm_bindgen::JsValue;
+
+    let allocator = Allocator::new();
+    let mut root = Node::new(&allocator, &JsValue::NULL, &Object::new()).ok().unwrap();
+    let child = Node::new(&allocator, &JsValue::NULL, &Object::new()).ok().unwrap();
+    assert!(root.add_child(&child).is_ok());
+
+    root.compute_layout(&Object::new()).ok().unwrap();
+    root.compute_layout(&Object::new()).ok().unwrap();
+
+    let stats = allocator.get_stats();
+    assert_eq!(stats.computeCount, 2.0);
+    assert_eq!(stats.estimatedNodesVisited, 4.0);
+    assert_eq!(stats.estimatedCacheMisses, 2.0);
+    assert_eq!(stats.estimatedCacheHits, 2.0);
+
+    allocator.set_tracing(true);
+    root.compute_layout(&Object::new()).ok().unwrap();
+    let trace = allocator.export_trace();
+    assert!(trace.contains("\"name\":\"computeLayout\""));
+    assert_eq!(trace.matches("\"name\":\"LayoutNode\",\"cat\":\"layout\",\"ph\":\"O\"").count(), 2);
+    assert!(!trace.contains("\"ph\":\"i\""));
+}
+
+#[wasm_bindgen_test]
+fn virtual_list_attaches_only_visible_rows() {
+    use js_sys::{Object, Reflect};
+    use taffy_binding::{Allocator, Node, VirtualList};
+    use wasm_bindgen::JsValue;
+
+    let allocator = Allocator::new();
+    let container = Node::new(&allocator, &JsValue::NULL, &Object::new()).ok().unwrap();
+    let mut list = VirtualList::new(&container, 10_000, 20.0);
+    list.overscan = 1;
+
+    let range = list.update(1000.0, 100.0);
+    assert_eq!((range.start, range.end), (49, 56));
+    assert_eq!(range.missing().len(), 7);
+
+    let row_style = Object::new();
+    Reflect::set(&row_style, &"insetTop".into(), &JsValue::from(7)).unwrap();
+    let mut row = Node::new(&allocator, &JsValue::NULL, &row_style).ok().unwrap();
+    assert!(list.attach_row(50, &row).is_ok());
+    assert!(list.attach_row(50, &row).is_ok());
+    assert!(list.attach_row(51, &row).is_err());
+    assert!(list.attach_row(10_000, &row).is_err());
+    assert_eq!(container.childCount().ok().unwrap(), 1);
+    assert_eq!(row.get_style().ok().unwrap(), JsValue::from(row_style.clone()));
+
+    let computed = |row: &Node, key: &str| {
+        Reflect::get(&row.get_computed_layout_style().ok().unwrap(), &key.into())
+            .unwrap()
+            .as_string()
+            .unwrap()
+    };
+    list.update(1000.0, 100.0);
+    assert_eq!(computed(&row, "position"), "absolute");
+    assert_eq!(computed(&row, "insetTop"), "0px");
+    row.set_style(&row_style).ok().unwrap();
+    assert_eq!(computed(&row, "position"), "absolute");
+    assert_eq!(computed(&row, "insetTop"), "0px");
+
+    list.set_row_size(0, 120.0);
+    assert_eq!(list.contentSize(), 200_100.0);
+
+    let range = list.update(0.0, 100.0);
+    assert_eq!(range.removed(), vec![50]);
+    assert_eq!(container.childCount().ok().unwrap(), 0);
+    assert_eq!(computed(&row, "position"), "relative");
+    assert_eq!(computed(&row, "insetTop"), "7px");
+}
+
+#[wasm_bindgen_test]
+fn easing_curves_start_and_end_at_the_endpoints() {
+    use taffy_binding::Easing;
+
+    for easing in [
+        Easing::Linear,
+        Easing::Ease,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ] {
+        assert!(easing.apply(0.0).abs() < 1e-4, "{:?}", easing);
+        assert!((easing.apply(1.0) - 1.0).abs() < 1e-4, "{:?}", easing);
+        assert!((easing.apply(2.0) - 1.0).abs() < 1e-4, "{:?}", easing);
+    }
+}
+
+#[wasm_bindgen_test]
+fn easing_curves_follow_css_timing_functions() {
+    use taffy_binding::Easing;
+
+    assert_eq!(Easing::Linear.apply(0.25), 0.25);
+    assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-3);
+    assert!(Easing::EaseIn.apply(0.25) < 0.25);
+    assert!(Easing::EaseOut.apply(0.25) > 0.25);
+    // cubic-bezier(0.25, 0.1, 0.25, 1.0) at x = 0.5
+    assert!((Easing::Ease.apply(0.5) - 0.8024).abs() < 1e-3);
+}
+
+#[wasm_bindgen_test]
+fn layout_transitions_interpolate_and_restart_from_the_displayed_layout() {
+    use js_sys::{Object, Reflect};
+    use taffy_binding::{Allocator, Easing, Node};
+    use wasm_bindgen::JsValue;
+
+    let style = |width: f64| {
+        let style = Object::new();
+        Reflect::set(&style, &"width".into(), &JsValue::from(width)).unwrap();
+        Reflect::set(&style, &"height".into(), &JsValue::from(10)).unwrap();
+        style
+    };
+    let allocator = Allocator::new();
+    let mut root = Node::new(&allocator, &JsValue::NULL, &style(200.0)).ok().unwrap();
+    let mut child = Node::new(&allocator, &JsValue::NULL, &style(10.0)).ok().unwrap();
+    assert!(root.add_child(&child).is_ok());
+    let width_at = |root: &Node, t: f32| {
+        allocator.interpolate_layout(root, t, Easing::Linear).ok().unwrap().child(0).width
+    };
+
+    // A new node has no transition.
+    root.compute_layout(&Object::new()).ok().unwrap();
+    assert_eq!(width_at(&root, 0.0), 10.0);
+
+    child.set_style(&style(30.0)).ok().unwrap();
+    root.compute_layout(&Object::new()).ok().unwrap();
+    assert_eq!(width_at(&root, 0.0), 10.0);
+    assert_eq!(width_at(&root, 0.5), 20.0);
+    let eased = allocator.interpolate_layout(&root, 0.25, Easing::EaseIn).ok().unwrap().child(0).width;
+    assert!(eased > 10.0 && eased < 15.0);
+
+    // A new layout in the middle of the transition starts from the last displayed width.
+    width_at(&root, 0.5);
+    child.set_style(&style(50.0)).ok().unwrap();
+    root.compute_layout(&Object::new()).ok().unwrap();
+    assert_eq!(width_at(&root, 0.0), 20.0);
+    assert_eq!(width_at(&root, 0.5), 35.0);
+
+    // A finished transition stays at the last layout.
+    assert_eq!(width_at(&root, 1.0), 50.0);
+    assert_eq!(width_at(&root, 0.5), 50.0);
+    assert_eq!(allocator.interpolate_layout(&root, 0.5, Easing::Linear).ok().unwrap().width, 200.0);
+}
+
+#[wasm_bindgen_test]
+fn layout_roots_are_isolated_and_disposable() {
+    use js_sys::Object;
+    use taffy_binding::{Allocator, Node};
+    use wasm_bindgen::JsValue;
+
+    let allocator = Allocator::new();
+    let first = allocator.create_root();
+    let second = allocator.create_root();
+    assert_eq!(allocator.rootCount(), 2);
+
+    let mut parent = Node::new(&first, &JsValue::NULL, &Object::new()).ok().unwrap();
+    let child = Node::new(&first, &JsValue::NULL, &Object::new()).ok().unwrap();
+    let stranger = Node::new(&second, &JsValue::NULL, &Object::new()).ok().unwrap();
+    assert!(parent.add_child(&child).is_ok());
+    assert!(parent.add_child(&stranger).is_err());
+
+    parent.compute_layout(&Object::new()).ok().unwrap();
+    assert_eq!(first.get_stats().computeCount, 1.0);
+    assert_eq!(second.get_stats().computeCount, 0.0);
+
+    // Freeing a node removes it from the tree of its root.
+    let extra = Node::new(&second, &JsValue::NULL, &Object::new()).ok().unwrap();
+    assert_eq!(second.nodeCount(), 2);
+    drop(extra);
+    assert_eq!(second.nodeCount(), 1);
+
+    allocator.dispose_root(&first);
+    assert!(first.disposed());
+    assert_eq!(first.nodeCount(), 0);
+    assert_eq!(second.nodeCount(), 1);
+    assert_eq!(allocator.rootCount(), 1);
+
+    // The nodes of a disposed root throw instead of reading its cleared tree.
+    assert!(parent.childCount().is_err());
+    assert!(parent.get_style().is_err());
+    assert!(parent.get_computed_layout_style().is_err());
+    assert!(parent.compute_layout(&Object::new()).is_err());
+    assert!(parent.get_layout().is_err());
+}
+
+#[wasm_bindgen_test]
+fn table_aligns_columns_across_rows() {
+    use js_sys::{Object, Reflect};
+    use taffy_binding::{Allocator, Display, Node};
+    use wasm_bindgen::JsValue;
+
+    let style = |entries: &[(&str, JsValue)]| {
+        let style = Object::new();
+        for (key, value) in entries {
+            Reflect::set(&style, &(*key).into(), value).unwrap();
+        }
+        style
+    };
+    let cell = |allocator: &Allocator, width: f64, colspan: u32| {
+        let mut cell = Node::new(
+            allocator,
+            &JsValue::NULL,
+            &style(&[
+                ("display", JsValue::from(Display::TableCell as u8)),
+                ("colspan", JsValue::from(colspan)),
+            ]),
+        )
+        .ok()
+        .unwrap();
+        let content = Node::new(
+            allocator,
+            &JsValue::NULL,
+            &style(&[("width", JsValue::from(width)), ("height", JsValue::from(10))]),
+        )
+        .ok()
+        .unwrap();
+        assert!(cell.add_child(&content).is_ok());
+        (cell, content)
+    };
+
+    let allocator = Allocator::new();
+    let mut table = Node::new(
+        &allocator,
+        &JsValue::NULL,
+        &style(&[
+            ("display", JsValue::from(Display::Table as u8)),
+            ("borderSpacingWidth", JsValue::from(4)),
+        ]),
+    )
+    .ok()
+    .unwrap();
+    let row_style = style(&[("display", JsValue::from(Display::TableRow as u8))]);
+    let mut first_row = Node::new(&allocator, &JsValue::NULL, &row_style).ok().unwrap();
+    let mut second_row = Node::new(&allocator, &JsValue::NULL, &row_style).ok().unwrap();
+    let (mut a, _a) = cell(&allocator, 30.0, 1);
+    let (mut b, _b) = cell(&allocator, 20.0, 1);
+    let (mut c, _c) = cell(&allocator, 50.0, 1);
+    let (mut d, _d) = cell(&allocator, 10.0, 2);
+    assert!(first_row.add_child(&a).is_ok());
+    assert!(first_row.add_child(&b).is_ok());
+    assert!(second_row.add_child(&c).is_ok());
+    assert!(table.add_child(&first_row).is_ok());
+    assert!(table.add_child(&second_row).is_ok());
+
+    table.compute_layout(&Object::new()).ok().unwrap();
+    assert_eq!(a.get_layout().ok().unwrap().width, 50.0);
+    assert_eq!(c.get_layout().ok().unwrap().width, 50.0);
+    assert_eq!(b.get_layout().ok().unwrap().x, 54.0);
+    assert_eq!(table.get_layout().ok().unwrap().width, 4.0 + 50.0 + 4.0 + 20.0 + 4.0);
+
+    let mut third_row = Node::new(&allocator, &JsValue::NULL, &row_style).ok().unwrap();
+    assert!(third_row.add_child(&d).is_ok());
+    assert!(table.add_child(&third_row).is_ok());
+    table.compute_layout(&Object::new()).ok().unwrap();
+    assert_eq!(d.get_layout().ok().unwrap().width, 74.0);
+
+    // The spans are clamped like HTML, an infinite colspan spans 1000 columns.
+    let huge = Node::new(
+        &allocator,
+        &JsValue::NULL,
+        &style(&[
+            ("display", JsValue::from(Display::TableCell as u8)),
+            ("colspan", JsValue::from(f64::INFINITY)),
+            ("rowspan", JsValue::from(1e9)),
+        ]),
+    )
+    .ok()
+    .unwrap();
+    let mut fourth_row = Node::new(&allocator, &JsValue::NULL, &row_style).ok().unwrap();
+    assert!(fourth_row.add_child(&huge).is_ok());
+    assert!(table.add_child(&fourth_row).is_ok());
+    table.compute_layout(&Object::new()).ok().unwrap();
+    let computed = huge.get_computed_layout_style().ok().unwrap();
+    let coerced = js_sys::Array::from(&Reflect::get(&computed, &"coercedProperties".into()).unwrap());
+    assert_eq!(coerced.length(), 2);
+    assert_eq!(coerced.get(0).as_string().unwrap(), "colspan");
+    assert_eq!(coerced.get(1).as_string().unwrap(), "rowspan");
+}
+
+#[wasm_bindgen_test]
+fn intrinsic_size_keeps_the_aspect_ratio() {
+    use js_sys::{Object, Reflect};
+    use taffy_binding::{Allocator, Node, ObjectFit};
+    use wasm_bindgen::JsValue;
+
+    let allocator = Allocator::new();
+    let container_style = Object::new();
+    Reflect::set(&container_style, &"width".into(), &JsValue::from(400)).unwrap();
+    Reflect::set(&container_style, &"alignItems".into(), &JsValue::from(2)).unwrap();
+    let mut container = Node::new(&allocator, &JsValue::NULL, &container_style).ok().unwrap();
+
+    let image_style = Object::new();
+    Reflect::set(&image_style, &"width".into(), &JsValue::from(100)).unwrap();
+    let mut image = Node::new(&allocator, &JsValue::NULL, &image_style).ok().unwrap();
+    image.set_intrinsic_size(200.0, 150.0).ok().unwrap();
+    assert!(container.add_child(&image).is_ok());
+
+    container.compute_layout(&Object::new()).ok().unwrap();
+    let layout = image.get_layout().ok().unwrap();
+    assert_eq!((layout.width, layout.height), (100.0, 75.0));
+
+    Reflect::set(&image_style, &"height".into(), &JsValue::from(100)).unwrap();
+    Reflect::set(&image_style, &"objectFit".into(), &JsValue::from(ObjectFit::Contain as u8)).unwrap();
+    image.set_style(&image_style).ok().unwrap();
+    container.compute_layout(&Object::new()).ok().unwrap();
+    let rect = image.get_object_fit_rect().ok().unwrap();
+    assert_eq!((rect.x, rect.y, rect.width, rect.height), (0.0, 12.5, 100.0, 75.0));
+}
+
+#[wasm_bindgen_test]
+fn command_buffer_is_applied_atomically() {
+    use js_sys::{Float32Array, Object, Uint32Array};
+    use taffy_binding::{Allocator, LayoutCommand, Node, StyleProperty, StyleUnit};
+    use wasm_bindgen::JsValue;
+
+    let allocator = Allocator::new();
+    let mut root = Node::new(&allocator, &JsValue::NULL, &Object::new()).ok().unwrap();
+    let first = allocator.reserve_handles(2).ok().unwrap();
+    let ops = [
+        LayoutCommand::Create as u32,
+        first,
+        LayoutCommand::SetStyle as u32,
+        first,
+        StyleProperty::Width as u32,
+        StyleUnit::Number as u32,
+        LayoutCommand::SetStyle as u32,
+        first,
+        StyleProperty::Height as u32,
+        StyleUnit::Number as u32,
+        LayoutCommand::AddChild as u32,
+        root.handle(),
+        first,
+    ];
+    let values = [40.0, 30.0];
+    assert!(allocator
+        .apply_commands(&Uint32Array::from(&ops[..]), &Float32Array::from(&values[..]))
+        .is_empty());
+    assert_eq!(root.childCount().ok().unwrap(), 1);
+
+    root.compute_layout(&Object::new()).ok().unwrap();
+    assert!(allocator.get_layout(first).is_ok());
+    assert_eq!(root.get_layout().ok().unwrap().height, 30.0);
+
+    // The invalid commands are all reported and nothing of the buffer is applied.
+    let other = Node::new(&allocator, &JsValue::NULL, &Object::new()).ok().unwrap();
+    let ops = [
+        LayoutCommand::Create as u32,
+        first + 1,
+        LayoutCommand::RemoveChild as u32,
+        first + 1,
+        root.handle(),
+        LayoutCommand::SetIntrinsicSize as u32,
+        first + 1,
+        LayoutCommand::AddChild as u32,
+        root.handle(),
+        first + 1,
+        // A child which has a parent is moved to the new one.
+        LayoutCommand::InsertChild as u32,
+        other.handle(),
+        0,
+        first,
+        LayoutCommand::MarkDirty as u32,
+        other.handle() + 1,
+    ];
+    let errors = allocator.apply_commands(&Uint32Array::from(&ops[..]), &Float32Array::from(&[20.0, 10.0][..]));
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].as_string().unwrap(),
+        format!("at 2: removeChild node {} is not a child of node {}", root.handle(), first + 1)
+    );
+    assert_eq!(errors[1].as_string().unwrap(), format!("at 14: markDirty node {} does not exist", other.handle() + 1));
+    assert_eq!(root.childCount().ok().unwrap(), 1);
+    assert_eq!(other.childCount().ok().unwrap(), 0);
+    assert!(allocator.get_layout(first + 1).is_err());
+
+    // Without the invalid commands the buffer is applied in order.
+    let ops = [&ops[..2], &ops[5..14]].concat();
+    let errors = allocator.apply_commands(&Uint32Array::from(&ops[..]), &Float32Array::from(&[20.0, 10.0][..]));
+    assert!(errors.is_empty());
+    assert_eq!(root.childCount().ok().unwrap(), 1);
+    assert_eq!(other.childCount().ok().unwrap(), 1);
+    root.compute_layout(&Object::new()).ok().unwrap();
+    assert_eq!(allocator.get_layout(first + 1).ok().unwrap().height, 10.0);
+    assert_eq!(root.get_layout().ok().unwrap().height, 10.0);
+
+    let ops = [LayoutCommand::AddChild as u32, first, other.handle()];
+    let errors = allocator.apply_commands(&Uint32Array::from(&ops[..]), &Float32Array::new_with_length(0));
+    assert_eq!(
+        errors[0].as_string().unwrap(),
+        format!("at 0: addChild node {} can't be a descendant of itself", other.handle())
+    );
+
+    // The handles are never reused, so they run out rather than wrap around.
+    assert!(allocator.reserve_handles(u32::MAX).is_err());
+}
+
+#[wasm_bindgen_test]
+fn style_keys_follow_the_style_properties() {
+    use taffy_binding::{style_keys, StyleProperty};
+
+    let keys = style_keys();
+    assert_eq!(keys[StyleProperty::Display as usize].as_string().unwrap(), "display");
+    assert_eq!(keys[StyleProperty::WritingMode as usize].as_string().unwrap(), "writingMode");
+    assert_eq!(keys.len(), StyleProperty::WritingMode as usize + 1);
+}
+
+#[wasm_bindgen_test]
+fn sticky_nodes_follow_the_scroll_container() {
+    use js_sys::{Float32Array, Object, Reflect, Uint32Array};
+    use taffy_binding::{Allocator, LayoutCommand, Node, Position};
+    use wasm_bindgen::JsValue;
+
+    let style = |entries: &[(&str, f64)]| {
+        let style = Object::new();
+        for (key, value) in entries {
+            Reflect::set(&style, &(*key).into(), &JsValue::from(*value)).unwrap();
+        }
+        style
+    };
+    let allocator = Allocator::new();
+    let mut list = Node::new(
+        &allocator,
+        &JsValue::NULL,
+        &style(&[("flexDirection", 1.0), ("width", 100.0), ("height", 100.0)]),
+    )
+    .ok()
+    .unwrap();
+    let mut header = Node::new(
+        &allocator,
+        &JsValue::NULL,
+        &style(&[
+            ("position", Position::Sticky as u8 as f64),
+            ("insetTop", 0.0),
+            ("height", 20.0),
+            ("flexShrink", 0.0),
+        ]),
+    )
+    .ok()
+    .unwrap();
+    let body = Node::new(
+        &allocator,
+        &JsValue::NULL,
+        &style(&[("height", 500.0), ("flexShrink", 0.0)]),
+    )
+    .ok()
+    .unwrap();
+    let mut hud = Node::new(
+        &allocator,
+        &JsValue::NULL,
+        &style(&[
+            ("position", Position::Fixed as u8 as f64),
+            ("insetRight", 0.0),
+            ("insetBottom", 0.0),
+            ("width", 10.0),
+            ("height", 10.0),
+        ]),
+    )
+    .ok()
+    .unwrap();
+    assert!(list.add_child(&header).is_ok());
+    assert!(list.add_child(&body).is_ok());
+    assert!(list.add_child(&hud).is_ok());
+    list.set_scroll_offset(0.0, 0.0).ok().unwrap();
+    list.compute_layout(&Object::new()).ok().unwrap();
+    assert_eq!(header.get_layout().ok().unwrap().y, 0.0);
+    assert_eq!((hud.get_layout().ok().unwrap().x, hud.get_layout().ok().unwrap().y), (90.0, 90.0));
+
+    // Computing another root of the allocator keeps the nodes of the list resolved.
+    let mut other = Node::new(&allocator, &JsValue::NULL, &style(&[("width", 10.0)])).ok().unwrap();
+    other.compute_layout(&Object::new()).ok().unwrap();
+    assert_eq!((hud.get_layout().ok().unwrap().x, hud.get_layout().ok().unwrap().y), (90.0, 90.0));
+
+    list.set_scroll_offset(0.0, 200.0).ok().unwrap();
+    assert_eq!(header.get_layout().ok().unwrap().y, 200.0);
+    assert_eq!(hud.get_layout().ok().unwrap().y, 290.0);
+
+    // The scroll offsets from the command buffer of the controls are resolved the same way.
+    let ops = [LayoutCommand::SetScrollOffset as u32, list.handle()];
+    let errors = allocator.apply_commands(&Uint32Array::from(&ops[..]), &Float32Array::from(&[0.0, 100.0][..]));
+    assert!(errors.is_empty());
+    assert_eq!(header.get_layout().ok().unwrap().y, 100.0);
+}
+
+#[wasm_bindgen_test]
+fn baseline_aligned_items_share_the_first_baseline() {
+    use js_sys::{Function, Object, Reflect};
+    use taffy_binding::{AlignItems, Allocator, Node};
+    use wasm_bindgen::JsValue;
+
+    let style = |entries: &[(&str, f64)]| {
+        let style = Object::new();
+        for (key, value) in entries {
+            Reflect::set(&style, &(*key).into(), &JsValue::from(*value)).unwrap();
+        }
+        style
+    };
+    let allocator = Allocator::new();
+    let mut row = Node::new(
+        &allocator,
+        &JsValue::NULL,
+        &style(&[("alignItems", AlignItems::Baseline as u8 as f64), ("width", 100.0)]),
+    )
+    .ok()
+    .unwrap();
+    let mut small = Node::new(&allocator, &JsValue::NULL, &style(&[("paddingTop", 2.0)])).ok().unwrap();
+    small
+        .set_measure(&Function::new_no_args("return { width: 10, height: 20, ascent: 16, descent: 4 }").into())
+        .ok()
+        .unwrap();
+    let mut large = Node::new(&allocator, &JsValue::NULL, &style(&[])).ok().unwrap();
+    large.set_measure(&Function::new_no_args("return { width: 10, height: 40, ascent: 30 }").into()).ok().unwrap();
+    assert!(row.add_child(&small).is_ok());
+    assert!(row.add_child(&large).is_ok());
+    row.compute_layout(&Object::new()).ok().unwrap();
+
+    let small_layout = small.get_layout().ok().unwrap();
+    let large_layout = large.get_layout().ok().unwrap();
+    assert_eq!(small_layout.firstBaseline, Some(18.0));
+    assert_eq!(small_layout.lastBaseline, Some(18.0));
+    assert_eq!(large_layout.lastBaseline, Some(30.0));
+    assert_eq!(small_layout.y + 18.0, large_layout.y + 30.0);
+    assert_eq!(row.get_layout().ok().unwrap().firstBaseline, Some(large_layout.y + 30.0));
+}
+
+#[wasm_bindgen_test]
+fn baseline_aligned_items_enlarge_their_row() {
+    use js_sys::{Function, Object, Reflect};
+    use taffy_binding::{AlignItems, Allocator, FlexDirection, Node};
+    use wasm_bindgen::JsValue;
+
+    let style = |entries: &[(&str, f64)]| {
+        let style = Object::new();
+        for (key, value) in entries {
+            Reflect::set(&style, &(*key).into(), &JsValue::from(*value)).unwrap();
+        }
+        style
+    };
+    let allocator = Allocator::new();
+    let mut column = Node::new(
+        &allocator,
+        &JsValue::NULL,
+        &style(&[("flexDirection", FlexDirection::Column as u8 as f64), ("width", 100.0)]),
+    )
+    .ok()
+    .unwrap();
+    let mut row = Node::new(
+        &allocator,
+        &JsValue::NULL,
+        &style(&[("alignItems", AlignItems::Baseline as u8 as f64)]),
+    )
+    .ok()
+    .unwrap();
+    // The label has the lower baseline, so the icon, which is as tall, is moved down below the label.
+    let mut label = Node::new(&allocator, &JsValue::NULL, &style(&[])).ok().unwrap();
+    label.set_measure(&Function::new_no_args("return { width: 40, height: 20, ascent: 16 }").into()).ok().unwrap();
+    let mut icon = Node::new(&allocator, &JsValue::NULL, &style(&[])).ok().unwrap();
+    icon.set_measure(&Function::new_no_args("return { width: 20, height: 20, ascent: 4 }").into()).ok().unwrap();
+    let mut footer = Node::new(&allocator, &JsValue::NULL, &style(&[("height", 10.0)])).ok().unwrap();
+    assert!(row.add_child(&label).is_ok());
+    assert!(row.add_child(&icon).is_ok());
+    assert!(column.add_child(&row).is_ok());
+    assert!(column.add_child(&footer).is_ok());
+    column.compute_layout(&Object::new()).ok().unwrap();
+
+    let label_layout = label.get_layout().ok().unwrap();
+    let icon_layout = icon.get_layout().ok().unwrap();
+    assert_eq!(label_layout.y + 16.0, icon_layout.y + 4.0);
+    assert_eq!(icon_layout.y - label_layout.y, 12.0);
+    // The row contains the shifted icon and the footer is placed after it.
+    assert_eq!(row.get_layout().ok().unwrap().height, icon_layout.y + 20.0);
+    assert_eq!(row.get_layout().ok().unwrap().height, 32.0);
+    assert_eq!(footer.get_layout().ok().unwrap().y, 32.0);
+    assert_eq!(column.get_layout().ok().unwrap().height, 42.0);
+
+    // The row stays the same when it's computed again.
+    column.compute_layout(&Object::new()).ok().unwrap();
+    assert_eq!(row.get_layout().ok().unwrap().height, 32.0);
+    assert_eq!(icon.get_layout().ok().unwrap().y - label.get_layout().ok().unwrap().y, 12.0);
+}
+
+#[wasm_bindgen_test]
+fn vertical_writing_mode_swaps_the_axes() {
+    use js_sys::{Function, Object, Reflect};
+    use taffy_binding::{Allocator, FlexDirection, Node, WritingMode};
+    use wasm_bindgen::JsValue;
+
+    let style = |entries: &[(&str, f64)]| {
+        let style = Object::new();
+        for (key, value) in entries {
+            Reflect::set(&style, &(*key).into(), &JsValue::from(*value)).unwrap();
+        }
+        style
+    };
+    let allocator = Allocator::new();
+    let mut panel = Node::new(
+        &allocator,
+        &JsValue::NULL,
+        &style(&[
+            ("writingMode", WritingMode::VerticalRl as u8 as f64),
+            ("flexDirection", FlexDirection::Column as u8 as f64),
+            ("width", 100.0),
+            ("height", 100.0),
+        ]),
+    )
+    .ok()
+    .unwrap();
+    let mut first = Node::new(
+        &allocator,
+        &JsValue::NULL,
+        &style(&[("writingMode", WritingMode::VerticalRl as u8 as f64), ("flexShrink", 0.0)]),
+    )
+    .ok()
+    .unwrap();
+    first.set_measure(&Function::new_no_args("return { width: 30, height: 10 }").into()).ok().unwrap();
+    let mut second =
+        Node::new(&allocator, &JsValue::NULL, &style(&[("width", 20.0), ("flexShrink", 0.0)])).ok().unwrap();
+    assert!(panel.add_child(&first).is_ok());
+    assert!(panel.add_child(&second).is_ok());
+    panel.compute_layout(&Object::new()).ok().unwrap();
+
+    // The measured inline size is the height, and the lines of the block axis go from right to left.
+    let first = first.get_layout().ok().unwrap();
+    assert_eq!((first.width, first.height), (10.0, 30.0));
+    assert_eq!(first.x, 90.0);
+    assert_eq!(second.get_layout().ok().unwrap().x, 70.0);
+}
+
+#[wasm_bindgen_test]
+fn box_model_is_read_from_the_last_layout() {
+    use js_sys::{Object, Reflect};
+    use taffy_binding::{Allocator, Node};
+    use wasm_bindgen::JsValue;
+
+    let style = |entries: &[(&str, f64)]| {
+        let style = Object::new();
+        for (key, value) in entries {
+            Reflect::set(&style, &(*key).into(), &JsValue::from(*value)).unwrap();
+        }
+        style
+    };
+    let allocator = Allocator::new();
+    let mut root = Node::new(
+        &allocator,
+        &JsValue::from("root"),
+        &style(&[("width", 100.0), ("height", 100.0), ("paddingLeft", 10.0)]),
+    )
+    .ok()
+    .unwrap();
+    let item = Node::new(
+        &allocator,
+        &JsValue::from("item"),
+        &style(&[
+            ("width", 40.0),
+            ("height", 20.0),
+            ("marginTop", 5.0),
+            ("borderLeft", 1.0),
+            ("paddingLeft", 2.0),
+        ]),
+    )
+    .ok()
+    .unwrap();
+    assert!(root.add_child(&item).is_ok());
+    root.compute_layout(&Object::new()).ok().unwrap();
+
+    let model = allocator.get_box_model(&item).unwrap();
+    assert_eq!((model.width, model.height), (40.0, 20.0));
+    assert_eq!(model.border(), vec![10.0, 5.0, 50.0, 5.0, 50.0, 25.0, 10.0, 25.0]);
+    assert_eq!(model.padding()[0], 11.0);
+    assert_eq!(model.content()[0], 13.0);
+    assert_eq!(model.margin()[1], 0.0);
+    let stranger = Node::new(&allocator.create_root(), &JsValue::from("none"), &Object::new()).ok().unwrap();
+    assert!(allocator.get_box_model(&stranger).is_none());
+
+    let hit = allocator.get_node_for_location(&root, 20.0, 10.0);
+    assert_eq!(hit.ok(), Some(JsValue::from("item")));
+    let hit = allocator.get_node_for_location(&root, 80.0, 80.0);
+    assert_eq!(hit.ok(), Some(JsValue::from("root")));
+}
@@ -0,0 +1,22 @@
+const { execSync } = require('child_process');
+const fs = require('fs');
+const path = require('path');
+
+/**
+ * Builds the Node-API addon of the taffy binding to `bindings/taffy/taffy_binding.node`, which is used for the
+ * server-side layout instead of the wasm build.
+ */
+const root = path.resolve(__dirname, '../..');
+execSync('cargo build --release -p taffy-binding --features napi', {
+  stdio: 'inherit',
+  cwd: root,
+});
+
+const libraryName = {
+  win32: 'taffy_binding.dll',
+  darwin: 'libtaffy_binding.dylib',
+}[process.platform] || 'libtaffy_binding.so';
+fs.copyFileSync(
+  path.join(root, 'target/release', libraryName),
+  path.join(root, 'bindings/taffy/taffy_binding.node'),
+);
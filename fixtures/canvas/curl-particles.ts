@@ -0,0 +1,49 @@
+const COUNT = 4000;
+const SPEED = 40;
+
+const noise = new Noise('curl-particles');
+const positions = new Float32Array(COUNT * 2);
+const velocities = new Float32Array(COUNT * 2);
+let width: number;
+let height: number;
+let time = 0;
+
+function respawn(i: number) {
+  positions[i * 2] = Math.random() * width;
+  positions[i * 2 + 1] = Math.random() * height;
+  velocities[i * 2] = velocities[i * 2 + 1] = 0;
+}
+
+export function init(ctx: CanvasRenderingContext2D, w: number, h: number) {
+  width = w;
+  height = h;
+  for (let i = 0; i < COUNT; i++) {
+    respawn(i);
+  }
+  ctx.fillStyle = 'black';
+  ctx.fillRect(0, 0, width, height);
+}
+
+export function step(ctx: CanvasRenderingContext2D, dt: number) {
+  time += dt;
+  noise.advanceParticles(positions, velocities, dt, {
+    scale: 0.006,
+    speed: SPEED,
+    time: time * 0.1,
+    inertia: 0.3,
+  });
+
+  // Fades the previous frames into trails.
+  ctx.fillStyle = 'rgba(0, 0, 0, 0.08)';
+  ctx.fillRect(0, 0, width, height);
+  ctx.fillStyle = 'rgb(255, 180, 90)';
+  for (let i = 0; i < COUNT; i++) {
+    const x = positions[i * 2];
+    const y = positions[i * 2 + 1];
+    if (x < 0 || x >= width || y < 0 || y >= height || Math.random() < 0.002) {
+      respawn(i);
+      continue;
+    }
+    ctx.fillRect(x, y, 1.5, 1.5);
+  }
+}
@@ -0,0 +1,70 @@
+const scene = spatialDocument.scene;
+const noise = new Noise('noise-texture');
+const size = 256;
+
+function applyTexture(id: string, pixels: Uint8Array, slot: 'diffuseTexture' | 'bumpTexture' = 'diffuseTexture') {
+  const texture = new BABYLON.RawTexture(
+    pixels,
+    size,
+    size,
+    BABYLON.Engine.TEXTUREFORMAT_RGBA,
+    scene,
+    false,
+    false,
+    BABYLON.Texture.TRILINEAR_SAMPLINGMODE);
+
+  const mesh = spatialDocument.getSpatialObjectById(id);
+  const material = mesh.asNativeType<BABYLON.Mesh>().material as BABYLON.StandardMaterial;
+  material[slot] = texture;
+}
+
+applyTexture('terrain', noise.texture({
+  width: size,
+  height: size,
+  source: 'fbm',
+  gradient: 'Terrain',
+  light: { contrast: 3 },
+}));
+
+const wood = Noise.graph({
+  type: 'Turbulence',
+  power: 0.05,
+  roughness: 4,
+  source: { type: 'ScalePoint', scale: [8, 1], source: { type: 'Perlin', seed: 'wood' } },
+});
+applyTexture('wood', noise.texture({
+  width: size,
+  height: size,
+  source: wood,
+  gradient: 'Wood',
+}));
+applyTexture('wood', noise.normalMap({
+  width: size,
+  height: size,
+  source: wood,
+  strength: 0.2,
+}), 'bumpTexture');
+
+applyTexture('planet', noise.texture({
+  width: size,
+  height: size,
+  source: 'fbm',
+  gradient: 'Terrain',
+  projection: 'sphere',
+  light: true,
+}));
+
+// Warping the fbm twice by 2 other fbms folds it into the veins of marble.
+const marble = Noise.warp(
+  { type: 'Fbm', seed: 'marble', octaves: 4 },
+  [{ type: 'Fbm', seed: 'marble-x', octaves: 4 }, { type: 'Fbm', seed: 'marble-y', octaves: 4 }],
+  { strength: 1.5, iterations: 2 }
+);
+applyTexture('marble', noise.texture({
+  width: size,
+  height: size,
+  source: marble,
+  scale: 2,
+  gradient: 'Marble',
+}));
+marble.free();
@@ -9,7 +9,9 @@ import { JsonSerializer } from './serializer/json';
 import { ClientCdpSession } from './client';
 import type { SpatialDocumentImpl } from '../../living/nodes/SpatialDocument';
 import { NodeImpl } from '../../living/nodes/Node';
-import { isNode, isAttributeNode, isElementNode, isSpatialElement } from '../../living/node-type';
+import type { HTMLContentElement } from '../../living/nodes/HTMLContentElement';
+import type { InteractiveDynamicTexture } from '../../living/helpers/babylonjs/InteractiveDynamicTexture';
+import { isNode, isAttributeNode, isElementNode, isHTMLContentElement, isSpatialElement } from '../../living/node-type';
 
 function isProxy(value: any) {
   let r: boolean = false;
@@ -25,6 +27,7 @@ namespace CdpJSAR {
   export interface Domains {
     Log: CdpBrowser.Domains['Log'];
     DOM: CdpBrowser.Domains['DOM'];
+    Overlay: CdpBrowser.Domains['Overlay'];
     SpatialDOM: SpatialDOMApi;
   }
 
@@ -149,6 +152,13 @@ function toColor3(input: BABYLON.Color3): CdpJSAR.SpatialElement.Color3 {
   return [input.r, input.g, input.b];
 }
 
+function toCssColor(input?: CdpBrowser.DOM.RGBA): string | undefined {
+  if (input == null) {
+    return undefined;
+  }
+  return `rgba(${input.r}, ${input.g}, ${input.b}, ${input.a ?? 1})`;
+}
+
 function toColor4(input: BABYLON.Color4): CdpJSAR.SpatialElement.Color4 {
   if (input == null) {
     return null;
@@ -184,6 +194,7 @@ export class CdpServerImplementation {
   private _isLogEnabled: boolean = this._init?.log || false;
   private _domNodes: Map<number, NodeImpl> = new Map();
   private _highlightedMeshes: Set<BABYLON.AbstractMesh> = new Set();
+  private _highlightedTexture: InteractiveDynamicTexture | null = null;
 
   get Log() {
     return this.rootSession.api.Log;
@@ -193,6 +204,10 @@ export class CdpServerImplementation {
     return this.rootSession.api.DOM;
   }
 
+  get Overlay() {
+    return this.rootSession.api.Overlay;
+  }
+
   get SpatialDOM() {
     return this.rootSession.api.SpatialDOM;
   }
@@ -252,8 +267,22 @@ export class CdpServerImplementation {
         async getAttributes(client, arg) {
           return null;
         },
-        async getBoxModel(client, arg) {
-          return null;
+        getBoxModel: async (_client, arg) => {
+          const element = this._getContentElement(arg.nodeId ?? arg.backendNodeId);
+          const model = element?._targetTexture?.getBoxModel(element._control);
+          if (!model) {
+            return null;
+          }
+          return {
+            model: {
+              content: Array.from(model.content),
+              padding: Array.from(model.padding),
+              border: Array.from(model.border),
+              margin: Array.from(model.margin),
+              width: model.width,
+              height: model.height,
+            },
+          };
         },
         async getContentQuads(client, arg) {
           return null;
@@ -269,7 +298,15 @@ export class CdpServerImplementation {
         async getNodesForSubtreeByStyle(client, arg) {
           return null;
         },
-        async getNodeForLocation(client, arg) {
+        getNodeForLocation: async (_client, arg) => {
+          // The location is in the pixels of the plane textures, the first texture with a node there wins.
+          for (const texture of this._getTextures()) {
+            const control = texture.getControlForLocation(arg.x, arg.y);
+            if (control) {
+              const nodeId = (control.element as unknown as NodeImpl)._inspectorId;
+              return { backendNodeId: nodeId, nodeId, frameId: '' };
+            }
+          }
           return null;
         },
         async getOuterHTML(client, arg) {
@@ -281,8 +318,9 @@ export class CdpServerImplementation {
         async getSearchResults(client, arg) {
           return null;
         },
-        async hideHighlight(client, arg) {
-          return null;
+        hideHighlight: async (_client, _arg) => {
+          this._hideHighlight();
+          return {};
         },
         async highlightNode(client, arg) {
           return null;
@@ -395,6 +433,109 @@ export class CdpServerImplementation {
           return null;
         },
       },
+      Overlay: {
+        async disable(client, arg) {
+          return null;
+        },
+        async enable(client, arg) {
+          return null;
+        },
+        async getHighlightObjectForTest(client, arg) {
+          return null;
+        },
+        async getGridHighlightObjectsForTest(client, arg) {
+          return null;
+        },
+        async getSourceOrderHighlightObjectForTest(client, arg) {
+          return null;
+        },
+        hideHighlight: async (_client, _arg) => {
+          this._hideHighlight();
+          return {};
+        },
+        async highlightFrame(client, arg) {
+          return null;
+        },
+        highlightNode: async (_client, arg) => {
+          const element = this._getContentElement(arg.nodeId ?? arg.backendNodeId);
+          const texture = element?._targetTexture;
+          this._hideHighlight();
+          if (texture) {
+            const config = arg.highlightConfig || {};
+            texture.highlightControl(element._control, {
+              content: toCssColor(config.contentColor),
+              padding: toCssColor(config.paddingColor),
+              border: toCssColor(config.borderColor),
+              margin: toCssColor(config.marginColor),
+            });
+            this._highlightedTexture = texture;
+          }
+          return {};
+        },
+        async highlightQuad(client, arg) {
+          return null;
+        },
+        async highlightRect(client, arg) {
+          return null;
+        },
+        async highlightSourceOrder(client, arg) {
+          return null;
+        },
+        async setInspectMode(client, arg) {
+          return null;
+        },
+        async setShowAdHighlights(client, arg) {
+          return null;
+        },
+        async setPausedInDebuggerMessage(client, arg) {
+          return null;
+        },
+        async setShowDebugBorders(client, arg) {
+          return null;
+        },
+        async setShowFPSCounter(client, arg) {
+          return null;
+        },
+        async setShowGridOverlays(client, arg) {
+          return null;
+        },
+        async setShowFlexOverlays(client, arg) {
+          return null;
+        },
+        async setShowScrollSnapOverlays(client, arg) {
+          return null;
+        },
+        async setShowContainerQueryOverlays(client, arg) {
+          return null;
+        },
+        async setShowPaintRects(client, arg) {
+          return null;
+        },
+        async setShowLayoutShiftRegions(client, arg) {
+          return null;
+        },
+        async setShowScrollBottleneckRects(client, arg) {
+          return null;
+        },
+        async setShowHitTestBorders(client, arg) {
+          return null;
+        },
+        async setShowWebVitals(client, arg) {
+          return null;
+        },
+        async setShowViewportSizeOnResize(client, arg) {
+          return null;
+        },
+        async setShowHinge(client, arg) {
+          return null;
+        },
+        async setShowIsolatedElements(client, arg) {
+          return null;
+        },
+        async setShowWindowControlsOverlay(client, arg) {
+          return null;
+        },
+      },
       SpatialDOM: {
         describeElement: async (_client, arg) => {
           const node = this._domNodes.get(arg.nodeId);
@@ -557,6 +698,29 @@ export class CdpServerImplementation {
     this._domNodes.delete(node._inspectorId);
   }
 
+  private _getContentElement(nodeId?: number): HTMLContentElement | null {
+    const node = nodeId == null ? null : this._domNodes.get(nodeId);
+    return node && isHTMLContentElement(node) ? node : null;
+  }
+
+  /**
+   * The textures of the planes which have HTML content elements.
+   */
+  private _getTextures(): Set<InteractiveDynamicTexture> {
+    const textures = new Set<InteractiveDynamicTexture>();
+    for (const node of this._domNodes.values()) {
+      if (isHTMLContentElement(node) && node._targetTexture) {
+        textures.add(node._targetTexture);
+      }
+    }
+    return textures;
+  }
+
+  private _hideHighlight() {
+    this._highlightedTexture?.hideHighlight();
+    this._highlightedTexture = null;
+  }
+
   serializeNode(node: NodeImpl, depth: number = -1): CdpBrowser.DOM.Node {
     const serialized: CdpBrowser.DOM.Node = {
       nodeId: node._inspectorId,
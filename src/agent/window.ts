@@ -476,6 +476,7 @@ export class BaseWindowImpl<T extends NativeDocument = NativeDocument> extends E
 
     // Dispose self after the native document is closed.
     try {
+      this._taffyAllocator.dispose();
       this._taffyAllocator.free();
       this._taffyAllocator = null;
     } catch (_err) {
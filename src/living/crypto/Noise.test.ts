@@ -0,0 +1,111 @@
+import { beforeAll, describe, expect, it } from '@jest/globals';
+import { loadNoise } from '@bindings/noise';
+import NoiseImpl from './Noise';
+
+describe('Noise', () => {
+  beforeAll(async () => {
+    await loadNoise();
+  });
+
+  it('fills the 2D grids row by row', () => {
+    const noise = new NoiseImpl('grid');
+    const out = new Float32Array(4 * 3);
+    noise.simplexGrid2(out, [0.5, -1], [0.25, 0.5], [4, 3]);
+    for (let j = 0; j < 3; j++) {
+      for (let i = 0; i < 4; i++) {
+        expect(out[j * 4 + i]).toBeCloseTo(noise.simplex2(0.5 + 0.25 * i, -1 + 0.5 * j), 5);
+      }
+    }
+    noise.perlinGrid2(out, [0, 0], [0.3, 0.3], [4, 3]);
+    expect(out[4 * 2 + 3]).toBeCloseTo(noise.perlin2(0.9, 0.6), 5);
+  });
+
+  it('fills the 3D grids with the x axis varying fastest', () => {
+    const noise = new NoiseImpl('grid');
+    const out = new Float32Array(2 * 3 * 4);
+    noise.simplexGrid3(out, [0, 0, 0], [0.5, 0.5, 0.5], [2, 3, 4]);
+    expect(out[(3 * 3 + 2) * 2 + 1]).toBeCloseTo(noise.simplex3(0.5, 1, 1.5), 5);
+    noise.perlinGrid3(out, [1, 2, 3], [0.1, 0.2, 0.3], [2, 3, 4]);
+    expect(out[(1 * 3 + 0) * 2 + 1]).toBeCloseTo(noise.perlin3(1.1, 2, 3.3), 5);
+  });
+
+  it('throws when the grid output is too small', () => {
+    const noise = new NoiseImpl('grid');
+    expect(() => noise.simplexGrid2(new Float32Array(5), [0, 0], [1, 1], [3, 2])).toThrow();
+  });
+
+  it('samples the packed points', () => {
+    const noise = new NoiseImpl(42);
+    const points = new Float64Array([0.1, 0.2, 0.3, 1.5, -2.5, 0.75]);
+    expect(Array.from(noise.simplexMany(points, 3))).toEqual([
+      noise.simplex3(0.1, 0.2, 0.3),
+      noise.simplex3(1.5, -2.5, 0.75),
+    ]);
+    expect(Array.from(noise.perlinMany(points))).toEqual([
+      noise.perlin2(0.1, 0.2),
+      noise.perlin2(0.3, 1.5),
+      noise.perlin2(-2.5, 0.75),
+    ]);
+    expect(() => noise.simplexMany(points, 4)).toThrow();
+  });
+
+  it('samples the values with their gradients', () => {
+    const noise = new NoiseImpl(42);
+    const gradients2 = noise.simplexGradients(new Float64Array([0.1, 0.2, 1.5, -2.5]));
+    expect(gradients2.length).toBe(2 * 3);
+    expect(gradients2[3]).toBeCloseTo(noise.simplex2(1.5, -2.5), 5);
+    const gradients3 = noise.perlinGradients(new Float64Array([0.1, 0.2, 0.3]), 3);
+    expect(gradients3.length).toBe(4);
+    expect(gradients3[0]).toBeCloseTo(noise.perlin3(0.1, 0.2, 0.3), 5);
+  });
+
+  it('renders the normal maps with the pixel spacings of the projection', () => {
+    const noise = new NoiseImpl('normals');
+    const flat = noise.normalMap({ width: 8, height: 4, strength: 0 });
+    expect(Array.from(flat.slice(0, 4))).toEqual([128, 128, 255, 255]);
+    const sphere = noise.normalMap({ width: 8, height: 4, projection: 'sphere' });
+    expect(sphere.length).toBe(8 * 4 * 4);
+    expect(() => noise.normalMap({ width: 8, height: 4, strength: -1 })).toThrow();
+  });
+
+  it('samples the generators of the new seed when it changes', () => {
+    const noise = new NoiseImpl('before');
+    noise.seed = 'after';
+    expect(noise.seed).toBe('after');
+    expect(noise.simplex2(0.3, 0.7)).toBe(new NoiseImpl('after').simplex2(0.3, 0.7));
+    expect(noise.worley2(0.3, 0.7)).toBe(new NoiseImpl('after').worley2(0.3, 0.7));
+  });
+
+  it('creates a generator owned by the caller for each call', () => {
+    const noise = new NoiseImpl('generators');
+    const first = noise.generator('Cylinders');
+    const second = noise.generator('Cylinders');
+    expect(first).not.toBe(second);
+    first.frequency = 4;
+    expect(second.frequency).toBe(1);
+    first.free();
+    expect(second.get(new Float64Array([0, 1, 0]))).toBe(1);
+    second.free();
+  });
+
+  it('throws for the 4D samples of SuperSimplex', () => {
+    const noise = new NoiseImpl('super');
+    const superSimplex = noise.generator('SuperSimplex');
+    const positions = new Float32Array([0.1, 0.2, 0.3]);
+    const normals = new Float32Array([0, 0, 1]);
+    expect(() => noise.displace(positions, normals, { source: superSimplex, time: 1 })).toThrow();
+    expect(() => superSimplex.sampleMany(new Float64Array([0.1, 0.2, 0.3, 0.4]), 4)).toThrow();
+    expect(superSimplex.sampleMany(new Float64Array([0.1, 0.2, 0.3]), 3).length).toBe(1);
+    superSimplex.free();
+  });
+
+  it('recreates the generators of the seeds which were freed', () => {
+    const noise = new NoiseImpl('first');
+    const value = noise.fbm2(0.3, 0.7);
+    for (let seed = 0; seed < 40; seed++) {
+      new NoiseImpl(seed).fbm2(0.3, 0.7);
+    }
+    expect(noise.fbm2(0.3, 0.7)).toBe(value);
+    expect(new NoiseImpl('first').simplex2(1, 2)).toBe(noise.simplex2(1, 2));
+  });
+});
@@ -1,26 +1,332 @@
 import * as noise from '@bindings/noise';
 
+type Generators = {
+  simplex: noise.Simplex;
+  perlin: noise.Perlin;
+  fractals: Partial<Fractals>;
+  worley?: noise.Worley;
+};
+
+/**
+ * The names of the generators of `noise.Generator`, e.g. `Value` or `Checkerboard`.
+ */
+export type GeneratorType = keyof typeof noise.GeneratorType;
+
+/**
+ * The fractals over Perlin noise with the default parameters, they are created on the first use as they have a
+ * generator per octave.
+ */
+type Fractals = {
+  fbm: noise.Fbm;
+  billow: noise.Billow;
+  ridged: noise.RidgedMulti;
+  hybrid: noise.HybridMulti;
+};
+
+const fractalConstructors: { [K in keyof Fractals]: new (seed: number) => Fractals[K] } = {
+  fbm: noise.Fbm,
+  billow: noise.Billow,
+  ridged: noise.RidgedMulti,
+  hybrid: noise.HybridMulti,
+};
+
+type Vector2 = [number, number];
+type Vector3 = [number, number, number];
+
+type NoiseKind = 'simplex' | 'perlin' | 'worley' | keyof Fractals;
+
+/**
+ * The generators chosen by type or built from the other ones, which are passed as they are.
+ */
+type Generator = noise.Generator | noise.NoiseGraph | noise.Warp;
+
+export type WarpOptions = {
+  /**
+   * How far the points move for a displacement noise of 1, 1 by default.
+   */
+  strength?: number;
+  /**
+   * How many times the displaced points are displaced again, 1 by default and at most 8.
+   */
+  iterations?: number;
+};
+
+/**
+ * A gradient point, the color at a noise value as `[red, green, blue, alpha?]` from 0 to 255.
+ */
+type GradientPoint = [number, [number, number, number, number?]];
+
+/**
+ * The noise field of a `width` x `height` texture.
+ */
+type FieldOptions = {
+  width: number;
+  height: number;
+  /**
+   * The noise of this instance to render, or a compiled graph, `fbm` by default.
+   */
+  source?: NoiseKind | Generator;
+  /**
+   * The noise coordinates of the first pixel, `[0, 0]` by default.
+   */
+  origin?: Vector2;
+  /**
+   * The noise distance covered by the texture on each axis, 4 by default.
+   */
+  scale?: number;
+  /**
+   * How the pixels map to the noise, `plane` by default:
+   * - `tile` is the plane blended so that the texture tiles, from `origin` over `scale`.
+   * - `cylinder` wraps the columns around a cylinder of radius 1, the rows go from `origin[1]` over `scale`.
+   * - `sphere` is the equirectangular map of a sphere of radius 1, the rows go from the south pole to the north one.
+   */
+  projection?: 'plane' | 'tile' | 'cylinder' | 'sphere';
+  /**
+   * Takes the opposite edges as the neighbors of the edges, for the textures which tile, by default only for the
+   * `tile` projection.
+   */
+  wrap?: boolean;
+};
+
+export type TextureOptions = FieldOptions & {
+  /**
+   * A gradient of the noise crate examples by name, or its points, grayscale by default.
+   */
+  gradient?: keyof typeof noise.GradientPreset | GradientPoint[];
+  /**
+   * Shades the noise as a height map, the angles are in degrees.
+   */
+  light?: boolean | {
+    azimuth?: number;
+    elevation?: number;
+    contrast?: number;
+    brightness?: number;
+    color?: [number, number, number];
+  };
+};
+
+export type NormalMapOptions = FieldOptions & {
+  /**
+   * How steep the bumps are, 1 by default, which keeps the slopes of the noise.
+   */
+  strength?: number;
+};
+
+export type DisplaceOptions = {
+  /**
+   * The noise of this instance, or a compiled graph, `simplex` by default.
+   */
+  source?: NoiseKind | Generator;
+  /**
+   * How far the vertices move along their normals for a noise of 1, 1 by default.
+   */
+  amplitude?: number;
+  /**
+   * The 4th coordinate of the noise, which animates the displacement, 0 by default.
+   */
+  time?: number;
+  /**
+   * The triangles of the mesh, the normals are recomputed smooth from the displaced triangles when they are given.
+   */
+  indices?: ArrayLike<number>;
+};
+
+export type FlowOptions = {
+  /**
+   * The noise of this instance, or a compiled graph, `simplex` by default.
+   */
+  source?: NoiseKind | Generator;
+  /**
+   * The frequency of the noise over the positions, 1 by default.
+   */
+  scale?: number;
+  /**
+   * The velocity for a slope of 1 of the noise, 1 by default.
+   */
+  speed?: number;
+  /**
+   * The extra dimension of the noise, advancing it changes the flow smoothly, 0 by default.
+   */
+  time?: number;
+  /**
+   * How long the particles take to follow the flow in seconds, 0 by default.
+   */
+  inertia?: number;
+  /**
+   * The number of coordinates of each particle, 2 by default.
+   */
+  dimensions?: 2 | 3;
+};
+
+export type WorleyCell = {
+  id: number;
+  value: number;
+  distance: number;
+  center: number[];
+};
+
+/**
+ * The number of seeds whose generators are kept, the generators of the least recently used seed are freed beyond it.
+ */
+const MAX_CACHED_SEEDS = 16;
+
+function freeGenerators(generators: Generators) {
+  generators.simplex.free();
+  generators.perlin.free();
+  Object.values(generators.fractals).forEach((fractal) => fractal.free());
+  generators.worley?.free();
+}
+
+/**
+ * Copies the wasm cell into a plain object and frees it.
+ */
+function toWorleyCell(cell: noise.WorleyCell): WorleyCell {
+  const { id, value, distance } = cell;
+  const center = Array.from(cell.center);
+  cell.free();
+  return { id, value, distance, center };
+}
+
 export default class NoiseImpl {
-  constructor(public seed?: number) {
-    if (!seed) {
-      this.seed = Math.random();
+  /**
+   * The wasm generators by seed in the order of their last use, they are shared by the instances so that sampling
+   * doesn't allocate, and the least recently used ones are freed past `MAX_CACHED_SEEDS`.
+   */
+  private static _generators: Map<number, Generators> = new Map();
+
+  private _seed: number | string;
+  private _hashedSeed: number;
+
+  /**
+   * The seed can be any number or string, it's hashed into the 32-bit seed of the generators. It defaults to a random
+   * 32-bit integer.
+   */
+  constructor(seed?: number | string) {
+    this.seed = seed ?? Math.floor(Math.random() * 0x100000000);
+  }
+
+  get seed(): number | string {
+    return this._seed;
+  }
+
+  set seed(value: number | string) {
+    this._hashedSeed = noise.hashSeed(value);
+    this._seed = value;
+  }
+
+  /**
+   * Compiles a noise graph, an object or its JSON, into one generator which evaluates all the nodes per sample, see
+   * `NoiseGraph` for the node types. It throws with the path of the invalid node, e.g. `$.sources[1].control`.
+   */
+  static graph(description: object | string): noise.NoiseGraph {
+    return new noise.NoiseGraph(description);
+  }
+
+  /**
+   * Warps the `source` graph by the `displacements`, one per axis or fewer which are reused in turn. The graphs are
+   * given as for `graph()`, or compiled already and then still owned by the caller.
+   */
+  static warp(
+    source: noise.NoiseGraph | object | string,
+    displacements: Array<noise.NoiseGraph | object | string>,
+    options: WarpOptions = {}
+  ): noise.Warp {
+    if (displacements.length === 0) {
+      throw new Error('The warp needs at least one displacement');
+    }
+    const compiled: noise.NoiseGraph[] = [];
+    const graph = (description: noise.NoiseGraph | object | string) => {
+      if (description instanceof noise.NoiseGraph) {
+        return description;
+      }
+      const graph = NoiseImpl.graph(description);
+      compiled.push(graph);
+      return graph;
+    };
+    try {
+      const [first, ...others] = displacements.map(graph);
+      const warp = new noise.Warp(graph(source), first, options.strength, options.iterations);
+      for (const displacement of others) {
+        warp.addDisplacement(displacement);
+      }
+      return warp;
+    } finally {
+      // The warp shares the compiled modules, so the graphs compiled here are freed.
+      compiled.forEach((graph) => graph.free());
+    }
+  }
+
+  private get _generators(): Generators {
+    const seed = this._hashedSeed;
+    const cache = NoiseImpl._generators;
+    let generators = cache.get(seed);
+    if (generators) {
+      // Moves the seed to the end of the map as the most recently used.
+      cache.delete(seed);
+    } else {
+      generators = {
+        simplex: new noise.Simplex(seed),
+        perlin: new noise.Perlin(seed),
+        fractals: {},
+      };
+      if (cache.size >= MAX_CACHED_SEEDS) {
+        const [leastRecentSeed, leastRecent] = cache.entries().next().value;
+        cache.delete(leastRecentSeed);
+        freeGenerators(leastRecent);
+      }
+    }
+    cache.set(seed, generators);
+    return generators;
+  }
+
+  private _fractal(kind: keyof Fractals): Fractals[keyof Fractals] {
+    const { fractals } = this._generators;
+    let fractal = fractals[kind];
+    if (!fractal) {
+      fractal = new fractalConstructors[kind](this._hashedSeed);
+      fractals[kind] = fractal as any;
+    }
+    return fractal;
+  }
+
+  private _fractalN(kind: keyof Fractals, inputs: number[]): number {
+    return this._fractal(kind).get(new Float64Array(inputs));
+  }
+
+  private _source(kind: NoiseKind) {
+    switch (kind) {
+      case 'simplex':
+      case 'perlin':
+        return this._generators[kind];
+      case 'worley':
+        return this._worley;
+      default:
+        return this._fractal(kind);
     }
   }
 
+  private get _worley(): noise.Worley {
+    const generators = this._generators;
+    if (!generators.worley) {
+      generators.worley = new noise.Worley(this._hashedSeed);
+    }
+    return generators.worley;
+  }
+
+  /**
+   * Creates a generator of `type` with the seed of this instance, e.g. `noise.generator('Value').get(...)`. It is
+   * owned by the caller, which can set its `frequency` and `size` and frees it with `free()`.
+   */
+  generator(type: GeneratorType): noise.Generator {
+    return new noise.Generator(noise.GeneratorType[type], this._hashedSeed);
+  }
+
   private _simplexN(inputs: number[]): number {
-    const simplex = new noise.Simplex(this.seed);
-    const point = new Float64Array(inputs);
-    const v = simplex.get(point);
-    simplex.free();
-    return v;
+    return this._generators.simplex.get(new Float64Array(inputs));
   }
 
   private _perlinN(inputs: number[]): number {
-    const perlin = new noise.Perlin(this.seed);
-    const point = new Float64Array(inputs);
-    const v = perlin.get(point);
-    perlin.free();
-    return v;
+    return this._generators.perlin.get(new Float64Array(inputs));
   }
 
   simplex2(x: number, y: number): number {
@@ -46,4 +352,231 @@ export default class NoiseImpl {
   perlin4(x: number, y: number, z: number, w: number): number {
     return this._perlinN([x, y, z, w]);
   }
+
+  /**
+   * Fractal Brownian motion, the octaves of Perlin noise summed, for terrains and clouds.
+   */
+  fbm2(x: number, y: number): number {
+    return this._fractalN('fbm', [x, y]);
+  }
+
+  fbm3(x: number, y: number, z: number): number {
+    return this._fractalN('fbm', [x, y, z]);
+  }
+
+  /**
+   * Like `fbm` with the absolute value of each octave, for billowy clouds and rocks.
+   */
+  billow2(x: number, y: number): number {
+    return this._fractalN('billow', [x, y]);
+  }
+
+  billow3(x: number, y: number, z: number): number {
+    return this._fractalN('billow', [x, y, z]);
+  }
+
+  /**
+   * Ridged multifractal noise, for mountain ranges.
+   */
+  ridged2(x: number, y: number): number {
+    return this._fractalN('ridged', [x, y]);
+  }
+
+  ridged3(x: number, y: number, z: number): number {
+    return this._fractalN('ridged', [x, y, z]);
+  }
+
+  /**
+   * Hybrid multifractal noise, for smooth valleys and rough peaks.
+   */
+  hybrid2(x: number, y: number): number {
+    return this._fractalN('hybrid', [x, y]);
+  }
+
+  hybrid3(x: number, y: number, z: number): number {
+    return this._fractalN('hybrid', [x, y, z]);
+  }
+
+  /**
+   * Worley cellular noise, the value of the nearest cell from -1 to 1 with the euclidean distance.
+   */
+  worley2(x: number, y: number): number {
+    return this._worley.get(new Float64Array([x, y]));
+  }
+
+  worley3(x: number, y: number, z: number): number {
+    return this._worley.get(new Float64Array([x, y, z]));
+  }
+
+  /**
+   * The Worley cell of a point, its `id` and `center` are the same for all the points of the cell, e.g. to scatter
+   * an object per cell.
+   */
+  worleyCell2(x: number, y: number): WorleyCell {
+    return toWorleyCell(this._worley.cell2(x, y));
+  }
+
+  worleyCell3(x: number, y: number, z: number): WorleyCell {
+    return toWorleyCell(this._worley.cell3(x, y, z));
+  }
+
+  /**
+   * Fills `out` with the simplex noise of a `[width, height]` grid row by row, the first sample is at `origin` and the
+   * next ones are `step` apart on each axis.
+   */
+  simplexGrid2(out: Float32Array, origin: Vector2, step: Vector2, dims: Vector2): Float32Array {
+    this._generators.simplex.fillGrid2D(out, new Float64Array(origin), new Float64Array(step), new Uint32Array(dims));
+    return out;
+  }
+
+  /**
+   * Fills `out` with the simplex noise of a `[width, height, depth]` grid, the x axis varies fastest.
+   */
+  simplexGrid3(out: Float32Array, origin: Vector3, step: Vector3, dims: Vector3): Float32Array {
+    this._generators.simplex.fillGrid3D(out, new Float64Array(origin), new Float64Array(step), new Uint32Array(dims));
+    return out;
+  }
+
+  perlinGrid2(out: Float32Array, origin: Vector2, step: Vector2, dims: Vector2): Float32Array {
+    this._generators.perlin.fillGrid2D(out, new Float64Array(origin), new Float64Array(step), new Uint32Array(dims));
+    return out;
+  }
+
+  perlinGrid3(out: Float32Array, origin: Vector3, step: Vector3, dims: Vector3): Float32Array {
+    this._generators.perlin.fillGrid3D(out, new Float64Array(origin), new Float64Array(step), new Uint32Array(dims));
+    return out;
+  }
+
+  /**
+   * Renders the noise into the RGBA pixels of a `width` x `height` texture through a color gradient, e.g. for a
+   * `BABYLON.RawTexture` of the `TEXTUREFORMAT_RGBA` format.
+   */
+  texture(options: TextureOptions): Uint8Array {
+    const { gradient, light } = options;
+    const preset = typeof gradient === 'string' ? noise.GradientPreset[gradient] : undefined;
+    const renderer = new noise.TextureRenderer(preset);
+    try {
+      if (Array.isArray(gradient)) {
+        renderer.clearGradient();
+        for (const [value, [red, green, blue, alpha]] of gradient) {
+          renderer.addGradientPoint(value, red, green, blue, alpha);
+        }
+      }
+      if (light) {
+        renderer.lightEnabled = true;
+        if (typeof light === 'object') {
+          renderer.lightAzimuth = light.azimuth ?? renderer.lightAzimuth;
+          renderer.lightElevation = light.elevation ?? renderer.lightElevation;
+          renderer.lightContrast = light.contrast ?? renderer.lightContrast;
+          renderer.lightBrightness = light.brightness ?? renderer.lightBrightness;
+          if (light.color) {
+            renderer.setLightColor(...light.color);
+          }
+        }
+      }
+      renderer.wrap = options.wrap ?? options.projection === 'tile';
+      return renderer.render(this._field(options), options.width, options.height);
+    } finally {
+      renderer.free();
+    }
+  }
+
+  /**
+   * Renders the noise as a height map into the RGBA pixels of a tangent-space normal map, e.g. for the `bumpTexture`
+   * of a `BABYLON.StandardMaterial`.
+   */
+  normalMap(options: NormalMapOptions): Uint8Array {
+    const { width, height, strength = 1 } = options;
+    const [spacingX, spacingY] = this._pixelSpacing(options);
+    const wrap = options.wrap ?? options.projection === 'tile';
+    return noise.normalMap(this._field(options), width, height, spacingX / strength, spacingY / strength, wrap);
+  }
+
+  /**
+   * Moves the vertices along their normals by the noise at `[x, y, z, time]`, in place, e.g. for the vertices of a
+   * `geometry-updatable` mesh. The positions are displaced from where they are, so the rest positions are copied back
+   * before each frame.
+   */
+  displace(positions: Float32Array, normals: Float32Array, options: DisplaceOptions = {}): void {
+    const { amplitude = 1, time = 0, indices } = options;
+    const source = typeof options.source === 'object' ? options.source : this._source(options.source ?? 'simplex');
+    const triangles = indices && (indices instanceof Uint32Array ? indices : Uint32Array.from(indices));
+    source.displaceVertices(positions, normals, amplitude, time, triangles);
+  }
+
+  /**
+   * Moves the packed particles by `dt` seconds along the curl of the noise, in place, and updates their velocities. The
+   * flow has no divergence, so the particles swirl like smoke without gathering or spreading.
+   */
+  advanceParticles(positions: Float32Array, velocities: Float32Array, dt: number, options: FlowOptions = {}): void {
+    const { dimensions = 2 } = options;
+    const source = typeof options.source === 'object' ? options.source : this._source(options.source ?? 'simplex');
+    const flow = new noise.CurlFlow();
+    try {
+      flow.scale = options.scale ?? flow.scale;
+      flow.speed = options.speed ?? flow.speed;
+      flow.time = options.time ?? flow.time;
+      flow.inertia = options.inertia ?? flow.inertia;
+      source.advanceParticles(flow, positions, velocities, dt, dimensions);
+    } finally {
+      flow.free();
+    }
+  }
+
+  /**
+   * The distances between the pixels in the noise on each axis, the spheres take the ones at their equator.
+   */
+  private _pixelSpacing(options: FieldOptions): Vector2 {
+    const { width, height, scale = 4, projection = 'plane' } = options;
+    if (projection === 'cylinder') {
+      return [(2 * Math.PI) / width, scale / height];
+    } else if (projection === 'sphere') {
+      return [(2 * Math.PI) / width, Math.PI / height];
+    }
+    return [scale / width, scale / height];
+  }
+
+  /**
+   * Fills the noise values of the pixels row by row.
+   */
+  private _field(options: FieldOptions): Float32Array {
+    const { width, height, origin = [0, 0], scale = 4, projection = 'plane' } = options;
+    const source = typeof options.source === 'object' ? options.source : this._source(options.source ?? 'fbm');
+    const values = new Float32Array(width * height);
+    const dims = new Uint32Array([width, height]);
+    const [x, y] = origin;
+    if (projection === 'plane') {
+      source.fillGrid2D(values, new Float64Array(origin), new Float64Array([scale / width, scale / height]), dims);
+    } else if (projection === 'tile') {
+      source.fillPlaneMap(values, new Float64Array([x, x + scale]), new Float64Array([y, y + scale]), dims, true);
+    } else if (projection === 'cylinder') {
+      source.fillCylinderMap(values, new Float64Array([-180, 180]), new Float64Array([y, y + scale]), dims);
+    } else {
+      source.fillSphereMap(values, new Float64Array([-90, 90]), new Float64Array([-180, 180]), dims);
+    }
+    return values;
+  }
+
+  /**
+   * Samples the simplex noise of the packed points, which have `dimensions` coordinates each.
+   */
+  simplexMany(points: Float64Array, dimensions: 2 | 3 | 4 = 2): Float64Array {
+    return this._generators.simplex.sampleMany(points, dimensions);
+  }
+
+  perlinMany(points: Float64Array, dimensions: 2 | 3 | 4 = 2): Float64Array {
+    return this._generators.perlin.sampleMany(points, dimensions);
+  }
+
+  /**
+   * Samples the values and the gradients of the simplex noise at the packed points, as `[value, dx, dy(, dz)]` per
+   * point. The 3D gradients are exact, the 2D ones are estimated by central differences.
+   */
+  simplexGradients(points: Float64Array, dimensions: 2 | 3 = 2): Float64Array {
+    return this._generators.simplex.sampleGradients(points, dimensions);
+  }
+
+  perlinGradients(points: Float64Array, dimensions: 2 | 3 = 2): Float64Array {
+    return this._generators.perlin.sampleGradients(points, dimensions);
+  }
 }
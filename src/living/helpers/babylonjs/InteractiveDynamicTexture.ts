@@ -6,8 +6,19 @@ import { ShadowRootImpl } from '../../nodes/ShadowRoot';
 import { HTMLContentElement } from '../../nodes/HTMLContentElement';
 import { isHTMLContentElement } from '../../node-type';
 import { Control2D } from '../gui2d/control';
+import { getLayoutCommandBuffer } from '../gui2d/layout-commands';
 import { domSymbolTree } from '../internal-constants';
 
+/**
+ * The fill colors of the boxes of a highlighted control in CSS colors, a box without a color isn't painted.
+ */
+export type HighlightColors = {
+  content?: string;
+  padding?: string;
+  border?: string;
+  margin?: string;
+};
+
 /**
  * The `InteractiveDynamicTexture` is copied from BabylonJS `InteractiveDynamicTexture` and modified to support the texture to interact in JSAR runtime.
  */
@@ -41,10 +52,19 @@ export class InteractiveDynamicTexture extends BABYLON.DynamicTexture {
   // _rootContainer: HTMLContentElement;
   /** @internal */
   _rootLayoutContainer: Control2D;
+  /**
+   * The layout root of this texture, its layout nodes and caches are isolated from the other textures.
+   * @internal
+   */
+  _layoutRoot: taffy.Allocator;
   /** @internal */
   _shadowRoot: ShadowRootImpl;
   /** @internal */
   _lastPickedControl: HTMLElementImpl;
+  /**
+   * The control highlighted by the inspector, its boxes are painted over the rendered content.
+   */
+  private _highlight: { control: Control2D; colors: HighlightColors } | null = null;
   /** @internal */
   /** @internal */
   private _idealWidth = 0;
@@ -167,7 +187,8 @@ export class InteractiveDynamicTexture extends BABYLON.DynamicTexture {
     this._shadowRoot = shadowRoot;
 
     const ownerDocument = shadowRoot._ownerDocument;
-    this._rootLayoutContainer = new Control2D(ownerDocument._defaultView._taffyAllocator, this._shadowRoot);
+    this._layoutRoot = ownerDocument._defaultView._taffyAllocator.createRoot();
+    this._rootLayoutContainer = new Control2D(this._layoutRoot, this._shadowRoot);
     this._rootLayoutContainer.init({
       height: '100%',
       width: '100%',
@@ -215,6 +236,12 @@ export class InteractiveDynamicTexture extends BABYLON.DynamicTexture {
     this.onClipboardObservable.clear();
     this.onControlPickedObservable.clear();
     this.onGuiReadyObservable.clear();
+
+    const defaultView = this._shadowRoot._ownerDocument._defaultView;
+    if (this._layoutRoot && defaultView._taffyAllocator) {
+      defaultView._taffyAllocator.disposeRoot(this._layoutRoot);
+      this._layoutRoot = null;
+    }
     super.dispose();
   }
 
@@ -275,11 +302,13 @@ export class InteractiveDynamicTexture extends BABYLON.DynamicTexture {
     }
     this._isRendering = true;
 
-    // Update styles for GUI nodes
+    // Update styles and scroll offsets for GUI nodes
     const defaultView = this._shadowRoot._ownerDocument._defaultView;
-    domSymbolTree.treeToArray(this._shadowRoot, {
+    const contentElements = domSymbolTree.treeToArray(this._shadowRoot, {
       filter: node => isHTMLContentElement(node)
-    })
+    });
+    contentElements.forEach((node: HTMLContentElement) => node._control?.updateScrollOffset());
+    contentElements
       .filter((node: HTMLContentElement) => {
         /**
          * Check if the node has style cache, if not, it means the node should be updated.
@@ -296,7 +325,8 @@ export class InteractiveDynamicTexture extends BABYLON.DynamicTexture {
         node._adoptStyle(style);
       });
 
-    // Compute layouts
+    // Compute layouts, the layout mutations of this frame are applied at once first.
+    getLayoutCommandBuffer(this._layoutRoot).flush();
     const textureSize = this.getSize();
     this._rootLayoutContainer.layoutNode.computeLayout({
       height: textureSize.height,
@@ -307,12 +337,85 @@ export class InteractiveDynamicTexture extends BABYLON.DynamicTexture {
     const size = this.getSize();
     this.getContext().clearRect(0, 0, size.width, size.height);
     const isDirtyAfterRendering = this._iterateLayoutResult();
+    this._renderHighlight();
 
     // Post steps
     this.markAsDirty(isDirtyAfterRendering);
     this._isRendering = false;
   }
 
+  /**
+   * Returns the content, padding, border and margin boxes of a control from the last layout in the pixels of this
+   * texture, or `undefined` when the control isn't laid out in this texture.
+   */
+  public getBoxModel(control: Control2D): taffy.BoxModel | undefined {
+    const node = control.layoutNode;
+    return node ? this._layoutRoot?.getBoxModel(node) : undefined;
+  }
+
+  /**
+   * Returns the topmost control at a point in the pixels of this texture, or `undefined` when it's outside.
+   */
+  public getControlForLocation(x: number, y: number): Control2D | undefined {
+    const rootNode = this._rootLayoutContainer?.layoutNode;
+    if (!this._layoutRoot || !rootNode) {
+      return undefined;
+    }
+    return this._layoutRoot.getNodeForLocation(rootNode, x, y);
+  }
+
+  /**
+   * Paints the boxes of a control over this texture until `hideHighlight()` is called, it replaces the previous
+   * highlight.
+   */
+  public highlightControl(control: Control2D, colors: HighlightColors) {
+    this._highlight = { control, colors };
+    this.markAsDirty();
+  }
+
+  public hideHighlight() {
+    if (this._highlight) {
+      this._highlight = null;
+      this.markAsDirty();
+    }
+  }
+
+  private _renderHighlight() {
+    const model = this._highlight && this.getBoxModel(this._highlight.control);
+    if (!model) {
+      return;
+    }
+    const { colors } = this._highlight;
+    const context = this.getContext() as CanvasRenderingContext2D;
+    const traceQuad = (quad: Float32Array) => {
+      context.moveTo(quad[0], quad[1]);
+      for (let i = 2; i < quad.length; i += 2) {
+        context.lineTo(quad[i], quad[i + 1]);
+      }
+      context.closePath();
+    };
+    // Each box is painted without the box inside it, like the overlay of the browsers.
+    const fillBox = (outer: Float32Array, inner: Float32Array | null, color?: string) => {
+      if (!color) {
+        return;
+      }
+      context.beginPath();
+      traceQuad(outer);
+      if (inner) {
+        traceQuad(inner);
+      }
+      context.fillStyle = color;
+      context.fill('evenodd');
+    };
+
+    context.save();
+    fillBox(model.margin, model.border, colors.margin);
+    fillBox(model.border, model.padding, colors.border);
+    fillBox(model.padding, model.content, colors.padding);
+    fillBox(model.content, null, colors.content);
+    context.restore();
+  }
+
   private _iterateLayoutResult(
     base = { x: 0, y: 0 },
     currentElementOrControl: HTMLContentElement | null = null
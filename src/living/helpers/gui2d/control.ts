@@ -3,7 +3,7 @@ import { CSSStyleDeclaration } from 'cssstyle';
 import { CanvasTextConfig, drawText, splitText } from 'canvas-txt';
 
 import DOMExceptionImpl from '../../domexception';
-import NodeTypes, { isHTMLContentElement, isTextNode } from '../../node-type';
+import NodeTypes, { isHTMLContentElement } from '../../node-type';
 import DOMRectReadOnlyImpl from '../../geometry/DOMRectReadOnly';
 import { HTMLContentElement } from '../../nodes/HTMLContentElement';
 import { TextImpl } from '../../nodes/Text';
@@ -14,6 +14,7 @@ import { ShadowRootImpl } from '../../nodes/ShadowRoot';
 import { getInterfaceWrapper } from '../../../living/interfaces';
 import { postMultiply, translate, rotate2d } from '../matrix-functions';
 import { parseTransform, UnionTransformFunction} from '../../cssom/parsers';
+import { getLayoutCommandBuffer } from './layout-commands';
 
 type LengthPercentageDimension = string | number;
 type LayoutStyle = Partial<{
@@ -21,6 +22,8 @@ type LayoutStyle = Partial<{
   display: taffy.Display;
   position: taffy.Position;
   aspectRatio: number;
+  objectFit: taffy.ObjectFit;
+  writingMode: taffy.WritingMode;
 
   // Rectangle
   width: LengthPercentageDimension | 'auto';
@@ -71,6 +74,13 @@ type LayoutStyle = Partial<{
 
   // Grid layout
   gridAutoFlow: taffy.GridAutoFlow;
+
+  // Table layout
+  tableLayout: taffy.TableLayout;
+  borderSpacingWidth: number;
+  borderSpacingHeight: number;
+  colspan: number;
+  rowspan: number;
 }>;
 
 type BorderRenderingContext = {
@@ -87,10 +97,31 @@ const CSSValueToLayoutStyleMappings = {
     'none': taffy.Display.None,
     'flex': taffy.Display.Flex,
     'grid': taffy.Display.Grid,
+    'table': taffy.Display.Table,
+    'table-row': taffy.Display.TableRow,
+    'table-cell': taffy.Display.TableCell,
+  },
+  objectFit: {
+    'fill': taffy.ObjectFit.Fill,
+    'contain': taffy.ObjectFit.Contain,
+    'cover': taffy.ObjectFit.Cover,
+    'none': taffy.ObjectFit.None,
+    'scale-down': taffy.ObjectFit.ScaleDown,
+  },
+  writingMode: {
+    'horizontal-tb': taffy.WritingMode.HorizontalTb,
+    'vertical-rl': taffy.WritingMode.VerticalRl,
+    'vertical-lr': taffy.WritingMode.VerticalLr,
+  },
+  tableLayout: {
+    'auto': taffy.TableLayout.Auto,
+    'fixed': taffy.TableLayout.Fixed,
   },
   position: {
     'absolute': taffy.Position.Absolute,
     'relative': taffy.Position.Relative,
+    'sticky': taffy.Position.Sticky,
+    'fixed': taffy.Position.Fixed,
   },
   flexDirection: {
     'row': taffy.FlexDirection.Row,
@@ -167,9 +198,11 @@ export class Control2D {
   private _lastCursor: BABYLON.Vector2;
   private _isCursorInside = false;
   protected _renderingContext: CanvasRenderingContext2D;
-  private _overwriteHeight: number;
-  private _overwriteWidth: number;
+  private _hasTextMeasure = false;
+  private _measuredText: string;
   private _imageBitmap: ImageBitmap;
+  private _scrollLeft = 0;
+  private _scrollTop = 0;
   private _isDirty = true;
   protected currentTransformMatrix: DOMMatrix;
   
@@ -182,7 +215,21 @@ export class Control2D {
     }
   }
 
-  init(defaultStyle?: LayoutStyle) {
+  /**
+   * The element or shadow root laid out by this control.
+   */
+  get element(): HTMLContentElement | ShadowRootImpl {
+    return this._element;
+  }
+
+  /**
+   * Create the layout node, the `allocator` is the layout root to create the node in, it defaults to the allocator passed to
+   * the constructor.
+   */
+  init(defaultStyle?: LayoutStyle, allocator?: taffy.Allocator) {
+    if (allocator) {
+      this._allocator = allocator;
+    }
     if (defaultStyle) {
       this.layoutStyle = defaultStyle;
     } else {
@@ -193,23 +240,49 @@ export class Control2D {
 
   setRenderingContext(renderingContext: CanvasRenderingContext2D) {
     this._renderingContext = renderingContext;
-    if (this._ownInnerText()) {
-      const textNode = this._element.firstChild as Text;
-      const { width, height } = this._fixSizeByText(textNode.data);
-      this._updateRectSize(width, height);
+    if (this._isElementOwnsInnerText()) {
+      const textNode = this._element.firstChild as TextImpl;
+      this._updateTextMeasure(`${textNode.data}`);
     }
   }
 
   setImageData(bitmap: ImageBitmap) {
+    if (this._imageBitmap === bitmap) {
+      return;
+    }
     this._imageBitmap = bitmap;
+    // The layout sizes the image box from its natural size, so it's right in the next layout without a resize pass.
+    if (this.layoutNode) {
+      const commands = getLayoutCommandBuffer(this._allocator);
+      commands.setIntrinsicSize(this.layoutNode, bitmap?.width || 0, bitmap?.height || 0);
+    }
+    this._isDirty = true;
+  }
+
+  /**
+   * Records the scroll offset of the element when it has changed, which makes the layout node a scroll container and
+   * resolves the sticky and fixed nodes inside it.
+   */
+  updateScrollOffset() {
+    if (!this.layoutNode || !isHTMLContentElement(this._element)) {
+      return;
+    }
+    const { scrollLeft, scrollTop } = this._element;
+    if (scrollLeft === this._scrollLeft && scrollTop === this._scrollTop) {
+      return;
+    }
+    this._scrollLeft = scrollLeft;
+    this._scrollTop = scrollTop;
+    getLayoutCommandBuffer(this._allocator).setScrollOffset(this.layoutNode, scrollLeft, scrollTop);
+    this._isDirty = true;
   }
 
   addChild(child: Control2D) {
-    this.layoutNode.addChild(child.layoutNode);
+    getLayoutCommandBuffer(this._allocator).addChild(this.layoutNode, child.layoutNode);
   }
 
   removeChild(child: Control2D) {
-    this.layoutNode.removeChild(child.layoutNode);
+    getLayoutCommandBuffer(this._allocator).removeChild(this.layoutNode, child.layoutNode);
   }
 
   isDirty() {
@@ -218,6 +291,8 @@ export class Control2D {
 
   dispose() {
     if (this.layoutNode) {
+      // The pending commands could refer to this node, so they are applied before it's freed.
+      getLayoutCommandBuffer(this._allocator).flush();
       this.layoutNode.free();
       this.layoutNode = null;
     }
@@ -231,11 +306,6 @@ export class Control2D {
     }
   }
   
-  private _ownInnerText(): boolean {
-    const element = this._element;
-    return element.childNodes.length === 1 && isTextNode(element);
-  }
-
   private _parseLengthStr(input: string): LengthPercentageDimension | 'auto' {
     if (input.endsWith('px')) {
       return parseFloat(input);
@@ -250,8 +320,8 @@ export class Control2D {
     const layoutStyle: LayoutStyle = {
       display: taffy.Display.Flex,
       flexDirection: taffy.FlexDirection.Column,
-      height: this._overwriteHeight || 'auto',
-      width: this._overwriteWidth || 'auto',
+      height: 'auto',
+      width: 'auto',
     };
 
     const inputStyle = this._style;
@@ -317,6 +387,13 @@ export class Control2D {
         layoutStyle.paddingBottom = this._parseLengthStr(inputStyle.paddingBottom);
       }
 
+      if (inputStyle.borderSpacing) {
+        // border-spacing: <horizontal> [<vertical>]
+        const [horizontal, vertical = horizontal] = inputStyle.borderSpacing.split(/\s+/).map(parseFloat);
+        layoutStyle.borderSpacingWidth = horizontal || 0;
+        layoutStyle.borderSpacingHeight = vertical || 0;
+      }
+
       for (const property of CSSValueToLayoutStyleProperties) {
         const value = inputStyle[property];
         if (value) {
@@ -324,29 +401,26 @@ export class Control2D {
         }
       }
     }
-    return layoutStyle;
-  }
 
-  private _updateRectSize(width: number, height: number) {
-    let needUpdateLayout = false;
-    if (typeof width === 'number' && this._overwriteWidth !== width) {
-      this._overwriteWidth = width;
-      needUpdateLayout = true;
-    }
-    if (typeof height === 'number' && this._overwriteHeight !== height) {
-      this._overwriteHeight = height;
-      needUpdateLayout = true;
-    }
-    if (needUpdateLayout) {
-      this.updateLayoutStyle();
+    if (isHTMLContentElement(this._element)) {
+      const colspan = parseInt(this._element.getAttribute('colspan'));
+      if (colspan > 1) {
+        layoutStyle.colspan = colspan;
+      }
+      const rowspan = parseInt(this._element.getAttribute('rowspan'));
+      if (rowspan > 1) {
+        layoutStyle.rowspan = rowspan;
+      }
     }
+    return layoutStyle;
   }
 
   updateLayoutStyle(): boolean {
     this.layoutStyle = this._initializeLayoutStyle();
     if (this.layoutNode) {
-      this.layoutNode.setStyle(this.layoutStyle);
-      this.layoutNode.markDirty();
+      const commands = getLayoutCommandBuffer(this._allocator);
+      commands.setStyle(this.layoutNode, this.layoutStyle);
+      commands.markDirty(this.layoutNode);
     }
     this._isDirty = true;
     return true;
@@ -378,21 +452,12 @@ export class Control2D {
     const boxRect = new DOMRectImpl(x, y, width, height);
     const hasTextChildren = this._isElementOwnsInnerText();
     /**
-     * 1. Check if this node is an image or has text children, if yes, we need to fix the size by the image or text.
+     * 1. Check if this node has text children, if yes, the layout measures the text, which is measured again in the
+     * next layout when it's changed. The images are sized by the layout from their intrinsic size.
      */
-    if (this._imageBitmap && this._element instanceof getInterfaceWrapper('HTMLImageElement')) {
-      // Fix the size by the image.
-      this._element._fixSizeByImage(boxRect);
-      this._updateRectSize(boxRect.width, boxRect.height);
-    } else if (hasTextChildren) {
-      // Fix the size by the text.
+    if (hasTextChildren) {
       const textNode = this._element.firstChild as unknown as TextImpl;
-      const fixedRect = this._fixSizeByText(textNode.data, boxRect);
-      if (fixedRect != null) {
-        boxRect.width = fixedRect.width;
-        boxRect.height = fixedRect.height;
-        this._updateRectSize(boxRect.width, boxRect.height);
-      }
+      this._updateTextMeasure(`${textNode.data}`);
     }
 
     /**
@@ -422,76 +487,52 @@ export class Control2D {
      * 6. Render the inner text.
      */
     if (hasTextChildren) {
-      this._renderInnerText(canvasContext, boxRect);
+      this._renderInnerText(canvasContext, boxRect, (rect as unknown as taffy.LayoutSimple).firstBaseline);
     }
     this._lastRect = boxRect;
   }
 
   /**
-   * Fix the rect size by text.
+   * Sizes the layout node by its text with a measure function, and marks it dirty when the text is changed since it
+   * was measured.
    */
-  private _fixSizeByText(value: string, rect?: DOMRectReadOnly) {
-    /**
-     * There are the following 3 cases that we don't need to fix size by text:
-     * 1. The target texture is not ready.
-     * 2. The text is empty.
-     * 3. The width is already set.
-     */
-    if (!value) {
-      return null;
+  private _updateTextMeasure(text: string) {
+    if (!this.layoutNode || !this._renderingContext) {
+      return;
     }
-
-    /**
-     * If the width and height has been set, we don't need to fix the size by text.
-     */
-    if (this._style.height && this._style.width) {
-      return null;
+    const commands = getLayoutCommandBuffer(this._allocator);
+    if (!this._hasTextMeasure) {
+      this._hasTextMeasure = true;
+      commands.setMeasure(this.layoutNode, (width?: number) => this._measureInnerText(width));
+      this._isDirty = true;
+    } else if (this._measuredText !== undefined && text !== this._measuredText) {
+      this._measuredText = text;
+      commands.markDirty(this.layoutNode);
+      this._isDirty = true;
     }
+  }
 
-    const metrics = this._measureText(this._renderingContext, value);
-
-    /**
-     * If the height is not set, we need to set it by the text height.
-     */
-    if (!this._style.height) {
-      const charHeight = metrics.height;
-      let height = charHeight;
-      let width: number;
-
-      /**
-       * If the width is not 0px and ends with px, such as 100px, 200px, we are able to split the text by this width value.
-       */
-      if (this._style.width.endsWith('px') && this._style.width !== '0px') {
-        width = parseFloat(this._style.width);
-      } else if (rect) {
-        width = rect.width;
-      } else {
-        width = metrics.width;
-      }
-
-      /**
-       * Calculate the total height by `splitText()` if the width is set.
-       */
-      if (width) {
-        const textArray = splitText({
-          ctx: this._renderingContext,
-          text: value,
-          justify: false,
-          width,
-        });
-        height = getLineHeightValue(charHeight, this._style.lineHeight) * textArray.length;
-      }
-      return { width, height };
-    } else {
-      let width: number;
-      const height = parseFloat(this._style.height);
-      if (!this._style.width) {
-        width = metrics.width;
-      } else {
-        width = parseFloat(this._style.width);
-      }
-      return { width, height };
+  /**
+   * Measures the inner text for the layout, the text is split into lines when the width is known. The ascent is the
+   * first baseline of the text, which lines it up in the baseline-aligned rows and where it's drawn.
+   */
+  private _measureInnerText(width?: number) {
+    const text = `${(this._element.firstChild as TextImpl).data}`;
+    this._measuredText = text;
+    const context = this._renderingContext;
+    const metrics = this._measureText(context, text);
+    const lineHeight = getLineHeightValue(metrics.height, this._style.lineHeight);
+    let lines = 1;
+    if (width === undefined) {
+      width = metrics.width;
+    } else if (width > 0 && metrics.width > width) {
+      lines = splitText({ ctx: context, text, justify: false, width }).length;
     }
+    return {
+      width,
+      height: lineHeight * lines,
+      ascent: (lineHeight - metrics.height) / 2 + metrics.ascent,
+    };
   }
 
   private _getBorderRenderingContext(name?: 'top' | 'right' | 'bottom' | 'left'): BorderRenderingContext {
@@ -559,6 +600,8 @@ export class Control2D {
     context.textBaseline = 'bottom';
     context.font = style;
     const { actualBoundingBoxAscent, width } = context.measureText(text);
+    context.textBaseline = 'alphabetic';
+    const { actualBoundingBoxAscent: baselineAscent } = context.measureText(text);
 
     // Reset baseline
     context.textBaseline = previousTextBaseline;
@@ -567,6 +610,8 @@ export class Control2D {
     return {
       height: Math.abs(actualBoundingBoxAscent),
       width,
+      // From the top of the text to the alphabetic baseline.
+      ascent: baselineAscent,
     };
   }
 
@@ -582,9 +627,10 @@ export class Control2D {
   }
 
   /**
-   * Render the inner text in this control.
+   * Render the inner text in this control, a single line of horizontal text is drawn on the `firstBaseline` of the
+   * layout when it has one.
    */
-  _renderInnerText(context: CanvasRenderingContext2D, rect: DOMRectReadOnlyImpl) {
+  _renderInnerText(context: CanvasRenderingContext2D, rect: DOMRectReadOnlyImpl, firstBaseline?: number) {
     const textNode = this._element.firstChild as TextImpl;
     const text = `${textNode.data}`;
 
@@ -595,11 +641,33 @@ export class Control2D {
       return;
     }
 
-    const textConfig = this._getTextConfig(rect);
-    const { height: textHeight } = this._measureText(context, text);
-
+    const { height: textHeight, width: textWidth } = this._measureText(context, text);
     context.fillStyle = this._style.color || 'black';
     const lineHeight = getLineHeightValue(textHeight, this._style.lineHeight);
+
+    /**
+     * The layout of a vertical writing mode is physical, so the text runs are drawn in the rect rotated by 90deg,
+     * where the inline axis goes down and the lines are stacked from right to left.
+     */
+    const writingMode = this.layoutStyle?.writingMode;
+    if (writingMode === taffy.WritingMode.VerticalRl || writingMode === taffy.WritingMode.VerticalLr) {
+      const rotatedRect = new DOMRectReadOnlyImpl(0, 0, rect.height, rect.width);
+      context.save();
+      context.translate(rect.x + rect.width, rect.y);
+      context.rotate(Math.PI / 2);
+      drawText(context, text, {
+        lineHeight,
+        ...this._getTextConfig(rotatedRect),
+      });
+      context.restore();
+      return;
+    }
+
+    const textConfig = this._getTextConfig(rect);
+    if (firstBaseline !== undefined && textWidth <= rect.width) {
+      this._drawTextOnBaseline(context, text, textConfig, rect.y + firstBaseline);
+      return;
+    }
     drawText(context, text, {
       lineHeight,
       height: rect.height,
@@ -607,6 +675,32 @@ export class Control2D {
     });
   }
 
+  /**
+   * Draws a single line of text with its alphabetic baseline at `baselineY`.
+   */
+  private _drawTextOnBaseline(
+    context: CanvasRenderingContext2D,
+    text: string,
+    config: CanvasTextConfig,
+    baselineY: number
+  ) {
+    let x = config.x;
+    let textAlign: CanvasTextAlign = 'left';
+    if (config.align === 'center') {
+      x += config.width / 2;
+      textAlign = 'center';
+    } else if (config.align === 'right') {
+      x += config.width;
+      textAlign = 'right';
+    }
+    context.save();
+    context.font = `${config.fontStyle || ''} ${config.fontWeight || ''} ${config.fontSize}px ${config.font}`.trim();
+    context.textAlign = textAlign;
+    context.textBaseline = 'alphabetic';
+    context.fillText(text, x, baselineY);
+    context.restore();
+  }
+
   /**
    * Render the rectangle with background color in this control.
    */
@@ -763,7 +857,14 @@ export class Control2D {
      * NOTE (Faych): The `putImageData` method directly places the image's pixels onto the canvas,
      * which causes the transformation matrix not to be applied to the pixels.
      */
-    renderingContext.drawImage(this._imageBitmap, rect.x, rect.y, rect.width, rect.height);
+    const fitRect = this.layoutNode.getObjectFitRect();
+    renderingContext.drawImage(
+      this._imageBitmap,
+      rect.x + fitRect.x,
+      rect.y + fitRect.y,
+      fitRect.width,
+      fitRect.height
+    );
   }
 
   private _updateCurrentTransformMatrix() {
@@ -0,0 +1,118 @@
+import * as taffy from '@bindings/taffy';
+
+let styleProperties: Map<string, taffy.StyleProperty> | null = null;
+
+/**
+ * Returns the style keys of `Node.setStyle()` to the `StyleProperty` of the `SetStyle` command, which are read from
+ * the binding once it's loaded.
+ */
+function getStyleProperties(): Map<string, taffy.StyleProperty> {
+  if (styleProperties === null) {
+    styleProperties = new Map(taffy.styleKeys().map((key: string, property: taffy.StyleProperty) => [key, property]));
+  }
+  return styleProperties;
+}
+
+/**
+ * Records the mutations of a layout root and applies them with a single `Allocator.applyCommands()` call, which is
+ * one wasm call per frame instead of one per `setStyle()`, `addChild()` or `markDirty()`. All the mutations of the
+ * nodes go through the buffer, so they are applied in the order they are made.
+ */
+export class LayoutCommandBuffer {
+  private _ops: number[] = [];
+  private _values: number[] = [];
+
+  constructor(private _allocator: taffy.Allocator) { }
+
+  get isEmpty(): boolean {
+    return this._ops.length === 0;
+  }
+
+  setStyle(node: taffy.Node, style: object) {
+    const handle = node.handle;
+    const properties = getStyleProperties();
+    this._ops.push(taffy.LayoutCommand.ResetStyle, handle);
+    for (const [key, value] of Object.entries(style)) {
+      const property = properties.get(key);
+      if (property === undefined || value === undefined || value === null) {
+        continue;
+      }
+      if (value === 'auto') {
+        this._pushStyle(handle, property, taffy.StyleUnit.Auto, 0);
+      } else if (typeof value === 'string' && value.endsWith('%')) {
+        this._pushStyle(handle, property, taffy.StyleUnit.Percent, parseFloat(value));
+      } else {
+        this._pushStyle(handle, property, taffy.StyleUnit.Number, Number(value));
+      }
+    }
+  }
+
+  addChild(parent: taffy.Node, child: taffy.Node) {
+    this._ops.push(taffy.LayoutCommand.AddChild, parent.handle, child.handle);
+  }
+
+  removeChild(parent: taffy.Node, child: taffy.Node) {
+    this._ops.push(taffy.LayoutCommand.RemoveChild, parent.handle, child.handle);
+  }
+
+  markDirty(node: taffy.Node) {
+    this._ops.push(taffy.LayoutCommand.MarkDirty, node.handle);
+  }
+
+  setIntrinsicSize(node: taffy.Node, width: number, height: number) {
+    this._ops.push(taffy.LayoutCommand.SetIntrinsicSize, node.handle);
+    this._values.push(width, height);
+  }
+
+  setScrollOffset(node: taffy.Node, x: number, y: number) {
+    this._ops.push(taffy.LayoutCommand.SetScrollOffset, node.handle);
+    this._values.push(x, y);
+  }
+
+  /**
+   * Sets the measure function of a node, the function can't be recorded, so the recorded commands are applied first
+   * to keep the order.
+   */
+  setMeasure(node: taffy.Node, measure: Function | null) {
+    this.flush();
+    node.setMeasure(measure);
+  }
+
+  /**
+   * Applies the recorded commands atomically: when any of them is invalid none is applied, and the recorded commands
+   * are dropped. Returns the error messages of all the invalid commands, which is empty when they were applied.
+   */
+  flush(): string[] {
+    if (this.isEmpty) {
+      return [];
+    }
+    const ops = new Uint32Array(this._ops);
+    const values = new Float32Array(this._values);
+    this._ops = [];
+    this._values = [];
+    const errors: string[] = this._allocator.applyCommands(ops, values);
+    for (const error of errors) {
+      console.warn(`Invalid layout command ${error}`);
+    }
+    return errors;
+  }
+
+  private _pushStyle(handle: number, property: taffy.StyleProperty, unit: taffy.StyleUnit, value: number) {
+    this._ops.push(taffy.LayoutCommand.SetStyle, handle, property, unit);
+    this._values.push(value);
+  }
+}
+
+const buffers = new WeakMap<taffy.Allocator, LayoutCommandBuffer>();
+
+/**
+ * Returns the command buffer of a layout root, which is flushed before the root computes its layout.
+ */
+export function getLayoutCommandBuffer(allocator: taffy.Allocator): LayoutCommandBuffer {
+  let buffer = buffers.get(allocator);
+  if (!buffer) {
+    buffer = new LayoutCommandBuffer(allocator);
+    buffers.set(allocator, buffer);
+  }
+  return buffer;
+}
@@ -10,7 +10,8 @@ import { Control2D } from '../helpers/gui2d/control';
 const { forEach } = Array.prototype;
 
 export class HTMLContentElement extends HTMLElementImpl {
-  private _targetTexture: InteractiveDynamicTexture;
+  /** @internal */
+  _targetTexture: InteractiveDynamicTexture;
   /** @internal */
   _control: Control2D;
   /** @internal */
@@ -45,19 +46,9 @@ export class HTMLContentElement extends HTMLElementImpl {
   }
 
   _attach(): void {
-    this._control.init();
-
-    let textureToUpdate: InteractiveDynamicTexture;
     const parent = this.parentNode;
-    if (isHTMLContentElement(parent)) {
-      parent._control.addChild(this._control);
-    } else if (parent instanceof ShadowRootImpl) {
-      textureToUpdate = parent._interactiveDynamicTexture;
-      textureToUpdate._rootLayoutContainer.addChild(this._control);
-    }
-
-    if (textureToUpdate) {
-      this._targetTexture = textureToUpdate;
+    if (parent instanceof ShadowRootImpl) {
+      this._targetTexture = parent._interactiveDynamicTexture;
     } else {
       const root = this.getRootNode();
       if (root instanceof ShadowRootImpl) {
@@ -67,6 +58,14 @@ export class HTMLContentElement extends HTMLElementImpl {
       }
     }
 
+    // The layout node must be created in the layout root of the target texture.
+    this._control.init(undefined, this._targetTexture._layoutRoot);
+    if (isHTMLContentElement(parent)) {
+      parent._control.addChild(this._control);
+    } else if (parent instanceof ShadowRootImpl) {
+      this._targetTexture._rootLayoutContainer.addChild(this._control);
+    }
+
     this._control.setRenderingContext(this._targetTexture.getContext() as CanvasRenderingContext2D);
     super._attach();
 
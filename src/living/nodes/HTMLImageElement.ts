@@ -3,7 +3,6 @@ import { parseURLToResultingURLRecord } from '../helpers/document-base-url';
 import { HTMLContentElement } from './HTMLContentElement';
 import ImageDataImpl from '../image/ImageData';
 import DOMExceptionImpl from '../domexception';
-import DOMRectImpl from '../geometry/DOMRect';
 
 export default class HTMLImageElementImpl extends HTMLContentElement implements HTMLImageElement {
   private _imageBitmap: ImageBitmap | null = null;
@@ -15,7 +14,6 @@ export default class HTMLImageElementImpl extends HTMLContentElement implements
   private _height: number;
   private _naturalWidth: number;
   private _naturalHeight: number;
-  private _naturalImageRatio: number;
   private _enableResizing: boolean;
 
   align: string;
@@ -119,20 +117,6 @@ export default class HTMLImageElementImpl extends HTMLContentElement implements
     return this._resizeImageData();
   }
 
-  _fixSizeByImage(rect: DOMRectImpl) {
-    const style = this._adoptedStyle;
-    if (!style.height && !style.width) {
-      rect.width = this._naturalWidth;
-      rect.height = this._naturalHeight;
-    }
-    if (!style.height) {
-      rect.height = rect.width / this._naturalImageRatio;
-    }
-    if (!style.width) {
-      rect.width = rect.height * this._naturalImageRatio;
-    }
-  }
-
   private async _resizeImageData() {
     if (this._enableResizing && this._imageBitmap) {
       this._imageData = await this._hostObject.decodeImage(this._imageBitmap, [this.width, this.height]);
@@ -167,7 +151,6 @@ export default class HTMLImageElementImpl extends HTMLContentElement implements
         this._imageData = await this._hostObject.decodeImage(this._imageBitmap, [this.width, this.height]);
         this._naturalWidth = this._imageBitmap.width;
         this._naturalHeight = this._imageBitmap.height;
-        this._naturalImageRatio = this._naturalWidth / this._naturalHeight;
         this._currentSrc = urlString;
         this._currentRequestState = 'available';
       } catch (err) {